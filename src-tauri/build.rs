@@ -1,3 +1,5 @@
 fn main() {
+    // 迁移文件通过 sqlx::migrate! 编译进程序，修改后需要重新编译
+    println!("cargo:rerun-if-changed=migrations");
     tauri_build::build()
 }
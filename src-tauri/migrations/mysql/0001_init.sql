@@ -0,0 +1,67 @@
+-- 初始表结构，与原先启动时执行的建表语句一致
+-- 基线迁移：已有数据库中这些表通常已经存在，因此没有对应的回滚脚本
+
+CREATE TABLE IF NOT EXISTS `users` (
+    `id` INT AUTO_INCREMENT PRIMARY KEY,
+    `username` VARCHAR(50) NOT NULL UNIQUE,
+    `email` VARCHAR(100) NOT NULL,
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `batch` (
+    `BatchID` int(11) NOT NULL AUTO_INCREMENT,
+    `BatchTask` varchar(30) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL,
+    `PotCode` varchar(30) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci DEFAULT NULL,
+    `GrillageCode` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci,
+    `SampleCodeList` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL,
+    `SampleSourceIDList` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci,
+    `Operator` varchar(25) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci DEFAULT NULL,
+    `CreateTime` datetime DEFAULT NULL,
+    `Status` varchar(15) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci DEFAULT NULL,
+    `UseTime` datetime DEFAULT NULL,
+    `BoardType` int(11) DEFAULT NULL,
+    `PotType` int(11) DEFAULT NULL,
+    `Devno` varchar(30) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci DEFAULT NULL,
+    `Sign` varchar(15) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci DEFAULT NULL,
+    `OperationType` int(11) DEFAULT NULL,
+    PRIMARY KEY (`BatchID`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `book` (
+    `id` int(11) NOT NULL AUTO_INCREMENT,
+    `price` float DEFAULT NULL,
+    `sales` bigint(20) DEFAULT NULL,
+    `publish_date` datetime DEFAULT NULL,
+    `title` varchar(100) DEFAULT NULL,
+    `author` varchar(100) DEFAULT NULL,
+    `category` varchar(100) DEFAULT NULL,
+    `rating` int(11) DEFAULT NULL,
+    `img` varchar(100) DEFAULT NULL,
+    `status` varchar(100) DEFAULT NULL,
+    PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `yearbook_popula` (
+    `id` int(11) NOT NULL AUTO_INCREMENT,
+    `year` int(11) DEFAULT NULL,
+    `population` double DEFAULT NULL,
+    `growth_popula` double DEFAULT NULL,
+    `annual_average` double DEFAULT NULL,
+    `over15` double DEFAULT NULL,
+    `newborn` double DEFAULT NULL,
+    `death` double DEFAULT NULL,
+    `total_dependency_ratio` double DEFAULT NULL,
+    `child_dependency_ratio` double DEFAULT NULL,
+    `old_dependency_ratio` double DEFAULT NULL,
+    `birth_rate` double DEFAULT NULL,
+    `mortality` double DEFAULT NULL,
+    `growth_rate` double DEFAULT NULL,
+    `first_marriage` double DEFAULT NULL,
+    `unmarried` double DEFAULT NULL,
+    `remarry` double DEFAULT NULL,
+    `divorce` double DEFAULT NULL,
+    `y0_y14` double DEFAULT NULL,
+    `y15_y64` double DEFAULT NULL,
+    `over65` double DEFAULT NULL,
+    PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,27 @@
+-- 查询历史和保存的查询
+
+CREATE TABLE IF NOT EXISTS `query_history` (
+    `id` BIGINT NOT NULL AUTO_INCREMENT,
+    `profile` VARCHAR(100) NOT NULL,
+    `db_name` VARCHAR(100) DEFAULT NULL,
+    `sql_text` MEDIUMTEXT NOT NULL,
+    `duration_ms` BIGINT NOT NULL DEFAULT 0,
+    `row_count` BIGINT DEFAULT NULL,
+    `error` TEXT,
+    `created_at` DATETIME NOT NULL,
+    PRIMARY KEY (`id`),
+    KEY `idx_query_history_created_at` (`created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `saved_query` (
+    `id` BIGINT NOT NULL AUTO_INCREMENT,
+    `name` VARCHAR(100) NOT NULL,
+    `description` VARCHAR(500) DEFAULT NULL,
+    `profile` VARCHAR(100) DEFAULT NULL,
+    `sql_text` MEDIUMTEXT NOT NULL,
+    `params` TEXT NOT NULL,
+    `created_at` DATETIME NOT NULL,
+    `updated_at` DATETIME NOT NULL,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_saved_query_name` (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,3 @@
+DROP INDEX `idx_book_publish_date` ON `book`;
+DROP INDEX `idx_book_status` ON `book`;
+DROP INDEX `idx_book_category` ON `book`;
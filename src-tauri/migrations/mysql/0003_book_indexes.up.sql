@@ -0,0 +1,5 @@
+-- 书籍列表排序和分组统计常用的字段
+
+CREATE INDEX `idx_book_category` ON `book` (`category`);
+CREATE INDEX `idx_book_status` ON `book` (`status`);
+CREATE INDEX `idx_book_publish_date` ON `book` (`publish_date`);
@@ -0,0 +1,64 @@
+-- 初始表结构，与 MySQL 版本一致
+-- 基线迁移：已有数据库中这些表通常已经存在，因此没有对应的回滚脚本
+
+CREATE TABLE IF NOT EXISTS `users` (
+    `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `username` VARCHAR(50) NOT NULL UNIQUE,
+    `email` VARCHAR(100) NOT NULL,
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS `batch` (
+    `BatchID` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `BatchTask` VARCHAR(30) NOT NULL,
+    `PotCode` VARCHAR(30) DEFAULT NULL,
+    `GrillageCode` TEXT,
+    `SampleCodeList` TEXT NOT NULL,
+    `SampleSourceIDList` TEXT,
+    `Operator` VARCHAR(25) DEFAULT NULL,
+    `CreateTime` DATETIME DEFAULT NULL,
+    `Status` VARCHAR(15) DEFAULT NULL,
+    `UseTime` DATETIME DEFAULT NULL,
+    `BoardType` INTEGER DEFAULT NULL,
+    `PotType` INTEGER DEFAULT NULL,
+    `Devno` VARCHAR(30) DEFAULT NULL,
+    `Sign` VARCHAR(15) DEFAULT NULL,
+    `OperationType` INTEGER DEFAULT NULL
+);
+
+CREATE TABLE IF NOT EXISTS `book` (
+    `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `price` REAL DEFAULT NULL,
+    `sales` BIGINT DEFAULT NULL,
+    `publish_date` DATETIME DEFAULT NULL,
+    `title` VARCHAR(100) DEFAULT NULL,
+    `author` VARCHAR(100) DEFAULT NULL,
+    `category` VARCHAR(100) DEFAULT NULL,
+    `rating` INTEGER DEFAULT NULL,
+    `img` VARCHAR(100) DEFAULT NULL,
+    `status` VARCHAR(100) DEFAULT NULL
+);
+
+CREATE TABLE IF NOT EXISTS `yearbook_popula` (
+    `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `year` INTEGER DEFAULT NULL,
+    `population` REAL DEFAULT NULL,
+    `growth_popula` REAL DEFAULT NULL,
+    `annual_average` REAL DEFAULT NULL,
+    `over15` REAL DEFAULT NULL,
+    `newborn` REAL DEFAULT NULL,
+    `death` REAL DEFAULT NULL,
+    `total_dependency_ratio` REAL DEFAULT NULL,
+    `child_dependency_ratio` REAL DEFAULT NULL,
+    `old_dependency_ratio` REAL DEFAULT NULL,
+    `birth_rate` REAL DEFAULT NULL,
+    `mortality` REAL DEFAULT NULL,
+    `growth_rate` REAL DEFAULT NULL,
+    `first_marriage` REAL DEFAULT NULL,
+    `unmarried` REAL DEFAULT NULL,
+    `remarry` REAL DEFAULT NULL,
+    `divorce` REAL DEFAULT NULL,
+    `y0_y14` REAL DEFAULT NULL,
+    `y15_y64` REAL DEFAULT NULL,
+    `over65` REAL DEFAULT NULL
+);
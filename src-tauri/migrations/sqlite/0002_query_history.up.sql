@@ -0,0 +1,25 @@
+-- 查询历史和保存的查询，与 MySQL 版本一致
+
+CREATE TABLE IF NOT EXISTS `query_history` (
+    `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `profile` VARCHAR(100) NOT NULL,
+    `db_name` VARCHAR(100) DEFAULT NULL,
+    `sql_text` TEXT NOT NULL,
+    `duration_ms` BIGINT NOT NULL DEFAULT 0,
+    `row_count` BIGINT DEFAULT NULL,
+    `error` TEXT,
+    `created_at` DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS `idx_query_history_created_at` ON `query_history` (`created_at`);
+
+CREATE TABLE IF NOT EXISTS `saved_query` (
+    `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+    `name` VARCHAR(100) NOT NULL UNIQUE,
+    `description` VARCHAR(500) DEFAULT NULL,
+    `profile` VARCHAR(100) DEFAULT NULL,
+    `sql_text` TEXT NOT NULL,
+    `params` TEXT NOT NULL,
+    `created_at` DATETIME NOT NULL,
+    `updated_at` DATETIME NOT NULL
+);
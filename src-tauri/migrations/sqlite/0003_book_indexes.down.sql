@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS `idx_book_publish_date`;
+DROP INDEX IF EXISTS `idx_book_status`;
+DROP INDEX IF EXISTS `idx_book_category`;
@@ -1,6 +1,6 @@
 use crate::commands::envpath::env_path;
-use crate::dao::BookDao;
-use crate::dto::{Book, BookQuery, Page};
+use crate::dao::{rebuild_book_index, BookDao};
+use crate::dto::{Book, BookFacets, BookHit, BookQuery, BookStats, Page, Sort};
 use chrono::Local;
 use std::fs;
 use std::path::PathBuf;
@@ -59,38 +59,63 @@ pub fn greetbooks() -> String {
     format!("{:?}", bks)
 }
 
+// 数据库错误转换为返回给前端的信息
+fn db_error(action: &str, e: anyhow::Error) -> String {
+    let error_message = format!("Failed to {}: {:?}", action, e);
+    println!("{}", error_message);
+
+    if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
+        println!("Database error: {:?}", db_err);
+    }
+
+    error_message
+}
+
 #[command]
 pub async fn list() -> Result<Vec<Book>, String> {
-    match BookDao::list(1, 10).await {
-        Ok(books) => Ok(books),
-        Err(e) => {
-            let error_message = format!("Failed to list books: {:?}", e);
-            println!("{}", error_message);
-
-            if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
-                println!("Database error: {:?}", db_err);
-            }
+    BookDao::list(1, 10)
+        .await
+        .map(|page| page.data)
+        .map_err(|e| db_error("list books", e))
+}
 
-            Err(error_message)
-        }
-    }
+#[command]
+pub async fn search(
+    query: &str,
+    current_page: i32,
+    page_size: i32,
+    sort: Option<Vec<Sort>>,
+) -> Result<Page<Book>, String> {
+    let sort = sort.unwrap_or_default();
+    // 有关键字且未指定排序时按相关度检索
+    let result = if !query.trim().is_empty() && sort.is_empty() {
+        BookDao::full_text_search(query, current_page, page_size)
+            .await
+            .map(|page| page.map(|hit| hit.doc))
+    } else {
+        BookDao::search(query, &sort, current_page, page_size).await
+    };
+    result.map_err(|e| db_error("list books", e))
 }
 
+/// 全文检索书籍，结果带相关度和高亮
 #[command]
-pub async fn search(query: &str, current_page: i32, page_size: i32) -> Result<Page<Book>, String> {
-    match BookDao::search(query, current_page, page_size).await {
-        Ok(pagebooks) => Ok(pagebooks),
-        Err(e) => {
-            let error_message = format!("Failed to list books: {:?}", e);
-            println!("{}", error_message);
-
-            if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
-                println!("Database error: {:?}", db_err);
-            }
+pub async fn full_text_search(
+    query: &str,
+    current_page: i32,
+    page_size: i32,
+) -> Result<Page<BookHit>, String> {
+    BookDao::full_text_search(query, current_page, page_size)
+        .await
+        .map_err(|e| db_error("search books", e))
+}
 
-            Err(error_message)
-        }
-    }
+/// 从数据库重建书籍检索索引，返回索引的书籍数
+#[command]
+pub async fn rebuild_search_index() -> Result<usize, String> {
+    rebuild_book_index()
+        .await
+        .map_err(|e| db_error("rebuild search index", e))
 }
 
 #[command]
@@ -99,19 +124,25 @@ pub async fn dynamics_search(
     current_page: i32,
     page_size: i32,
 ) -> Result<Page<Book>, String> {
-    match BookDao::dynamics_search(&query, current_page, page_size).await {
-        Ok(pagebooks) => Ok(pagebooks),
-        Err(e) => {
-            let error_message = format!("Failed to list books: {:?}", e);
-            println!("{}", error_message);
-
-            if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
-                println!("Database error: {:?}", db_err);
-            }
+    BookDao::dynamics_search(&query, current_page, page_size)
+        .await
+        .map_err(|e| db_error("list books", e))
+}
 
-            Err(error_message)
-        }
-    }
+/// 当前筛选条件下的分类、状态、评分计数
+#[command]
+pub async fn book_facets(query: BookQuery) -> Result<BookFacets, String> {
+    BookDao::facets(&query)
+        .await
+        .map_err(|e| db_error("count books", e))
+}
+
+/// 书籍汇总统计，不传条件时统计全部书籍
+#[command]
+pub async fn book_stats(query: Option<BookQuery>) -> Result<BookStats, String> {
+    BookDao::stats(&query.unwrap_or_default())
+        .await
+        .map_err(|e| db_error("get book stats", e))
 }
 
 #[command]
@@ -124,54 +155,26 @@ pub async fn create(book: Book) -> Result<String, String> {
 
 #[command]
 pub async fn update(book: Book) -> Result<String, String> {
-    match BookDao::update(&book).await {
-        Ok(b) => Ok(b.to_string()),
-        Err(e) => {
-            let error_message = format!("Failed to update books: {:?}", e);
-            println!("{}", error_message);
-
-            if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
-                println!("Database error: {:?}", db_err);
-            }
-
-            Err(error_message)
-        }
-    }
-    // let res = BookDao::update(&book).await.map_err(|e| format!("更新书籍失败: {}", e))?;
-    // Ok(format!("{:?}", res))
+    BookDao::update(&book)
+        .await
+        .map(|b| b.to_string())
+        .map_err(|e| db_error("update books", e))
 }
+
 #[command]
 pub async fn delete(id: i32) -> Result<String, String> {
-    match BookDao::delete(id.try_into().unwrap()).await {
-        Ok(b) => Ok(b.to_string()),
-        Err(e) => {
-            let error_message = format!("Failed to delete books: {:?}", e);
-            println!("{}", error_message);
-
-            if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
-                println!("Database error: {:?}", db_err);
-            }
-
-            Err(error_message)
-        }
-    }
+    BookDao::delete(id)
+        .await
+        .map(|b| b.to_string())
+        .map_err(|e| db_error("delete books", e))
 }
 
 #[command]
 pub async fn deletes(ids: Vec<u32>) -> Result<String, String> {
-    match BookDao::deletes(&ids).await {
-        Ok(b) => Ok(b.to_string()),
-        Err(e) => {
-            let error_message = format!("Failed to delete books: {:?}", e);
-            println!("{}", error_message);
-
-            if let Some(db_err) = e.downcast_ref::<sqlx::Error>() {
-                println!("Database error: {:?}", db_err);
-            }
-
-            Err(error_message)
-        }
-    }
+    BookDao::deletes(&ids)
+        .await
+        .map(|n| (n > 0).to_string())
+        .map_err(|e| db_error("delete books", e))
 }
 
 #[command]
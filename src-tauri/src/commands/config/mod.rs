@@ -1,13 +1,51 @@
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{Manager, State};
 
-use crate::util::AppConfig;
+use crate::commands::request_db_reconnect;
+use crate::util::{AppConfig, CredentialStatus, CredentialStore, KeySource, Secret, APP_DB_CREDENTIAL};
 
 // 包装配置状态
 pub struct AppState {
     pub(crate) config: Arc<Mutex<AppConfig>>,
 }
 
+// 凭据库状态，打开失败时为 None
+pub struct CredentialState {
+    pub(crate) store: Mutex<Option<CredentialStore>>,
+}
+
+impl CredentialState {
+    pub fn new(store: Option<CredentialStore>) -> Self {
+        Self {
+            store: Mutex::new(store),
+        }
+    }
+
+    /// 在凭据库上执行操作
+    pub fn with_store<T>(&self, f: impl FnOnce(&mut CredentialStore) -> Result<T, String>) -> Result<T, String> {
+        let mut store = self.store.lock().map_err(|e| e.to_string())?;
+        match store.as_mut() {
+            Some(store) => f(store),
+            None => Err("凭据库不可用".to_string()),
+        }
+    }
+
+    /// 凭据库是否已打开
+    pub fn available(&self) -> bool {
+        self.store.lock().map(|store| store.is_some()).unwrap_or(false)
+    }
+
+    /// 读取凭据，不存在时返回空密码
+    pub fn secret(&self, name: &str) -> Result<Secret, String> {
+        self.with_store(|store| store.get(name)).map(Option::unwrap_or_default)
+    }
+}
+
+/// 从凭据库读取凭据，不存在时返回空密码
+pub fn load_secret(app: &tauri::AppHandle, name: &str) -> Result<Secret, String> {
+    app.state::<CredentialState>().secret(name)
+}
+
 #[tauri::command]
 pub fn get_config(state: State<AppState>) -> Result<AppConfig, String> {
     let config = state.config.lock().map_err(|e| e.to_string())?;
@@ -34,9 +72,25 @@ pub fn set_config_field(
     value: serde_json::Value,
     state: State<AppState>,
 ) -> Result<(), String> {
+    // 连接配置中的密码要存入凭据库，只能通过 save_db_profile 修改
+    if field == "db_profiles" {
+        return Err("连接配置请通过 save_db_profile 修改".to_string());
+    }
+    // 密码写入凭据库，不写入配置文件；凭据库打开失败时仍保存在配置中
+    if field == "password" && app.state::<CredentialState>().available() {
+        let password = Secret::from(value.as_str().unwrap_or_default());
+        app.state::<CredentialState>()
+            .with_store(|store| store.set(APP_DB_CREDENTIAL, &password))?;
+        request_db_reconnect();
+        return Ok(());
+    }
     let mut config = state.config.lock().map_err(|e| e.to_string())?;
     config.update_field(&field, value)?;
     config.save(&app)?;
+    // 业务数据库的连接配置变化后按新配置重连
+    if matches!(field.as_str(), "password" | "ip" | "username" | "db_backend" | "sqlite_path") {
+        request_db_reconnect();
+    }
     Ok(())
 }
 
@@ -53,3 +107,27 @@ pub fn reset_config(
     default_config.save(&app)?;
     Ok(default_config)
 }
+
+// 凭据库状态
+#[tauri::command]
+pub fn credential_status(state: State<CredentialState>) -> Result<CredentialStatus, String> {
+    state.with_store(|store| Ok(store.status()))
+}
+
+// 使用主口令解锁凭据库
+#[tauri::command]
+pub fn unlock_credentials(passphrase: String, state: State<CredentialState>) -> Result<CredentialStatus, String> {
+    let status = state.with_store(|store| {
+        store.unlock(&passphrase)?;
+        Ok(store.status())
+    })?;
+    // 解锁后数据库密码才能读取，让连接守护立即重连
+    request_db_reconnect();
+    Ok(status)
+}
+
+// 设置或取消主口令，passphrase 为空时改用系统钥匙串（不可用时使用密钥文件）保存主密钥
+#[tauri::command]
+pub fn set_master_passphrase(passphrase: Option<String>, state: State<CredentialState>) -> Result<KeySource, String> {
+    state.with_store(|store| store.change_key(passphrase.as_deref()))
+}
@@ -0,0 +1,280 @@
+use crate::commands::ServerAccess;
+use crate::dao::BookDao;
+use crate::dto::{Book, BookFacets, BookHit, BookQuery, BookStats, Page, Sort};
+use axum::{
+    extract::{Path, Query, Request, State as AxumState},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+// 单页最多返回的记录数
+const MAX_PAGE_SIZE: i32 = 100;
+
+// 本地 HTTP 接口服务状态
+#[derive(Default)]
+pub struct HttpApiState {
+    pub shutdown_tx: Mutex<Option<oneshot::Sender<()>>>,
+    pub addr: Mutex<Option<String>>,
+}
+
+// 分页参数：/books?q=rust&page=1&page_size=10&sort=price,-sales
+#[derive(Debug, Deserialize)]
+pub struct PageParams {
+    pub q: Option<String>,
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    pub sort: Option<String>,
+}
+
+impl PageParams {
+    fn page(&self) -> i32 {
+        self.page.filter(|p| *p > 0).unwrap_or(1)
+    }
+
+    fn page_size(&self) -> i32 {
+        self.page_size.filter(|s| *s > 0).unwrap_or(10).min(MAX_PAGE_SIZE)
+    }
+
+    fn sorts(&self) -> Vec<Sort> {
+        self.sort.as_deref().map(Sort::parse_list).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdsBody {
+    pub ids: Vec<u32>,
+}
+
+// 接口错误统一转为 JSON 响应
+pub struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+// 完整的错误链只写日志，响应中只返回最外层的说明
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        eprintln!("HTTP API error: {:?}", e);
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Debug, Serialize)]
+pub struct ApiStatus {
+    pub ok: bool,
+}
+
+// Host 或 Origin 中的主机是否可信：localhost 或 IP 地址
+// 其它域名可能通过 DNS 重绑定指向本机，网页借此绕过同源限制访问接口，一律拒绝
+fn trusted_host(authority: &str) -> bool {
+    let host = match authority.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => authority.rsplit_once(':').map_or(authority, |(host, _)| host),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host.eq_ignore_ascii_case("tauri.localhost")
+        || host.parse::<IpAddr>().is_ok()
+}
+
+// 校验 Host、Origin 和访问令牌（配置了 server_token 时需要 Authorization: Bearer <token>）
+async fn check_access(
+    AxumState(access): AxumState<Arc<ServerAccess>>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    if !header(header::HOST).is_some_and(trusted_host) {
+        return Err(ApiError(StatusCode::FORBIDDEN, "Host not allowed".into()));
+    }
+    if let Some(origin) = header(header::ORIGIN) {
+        let authority = origin.split_once("://").map(|(_, a)| a);
+        if !authority.is_some_and(trusted_host) {
+            return Err(ApiError(StatusCode::FORBIDDEN, "Origin not allowed".into()));
+        }
+    }
+    let token = header(header::AUTHORIZATION).and_then(|v| v.strip_prefix("Bearer "));
+    if !access.check_token(token) {
+        return Err(ApiError(StatusCode::UNAUTHORIZED, "Unauthorized".into()));
+    }
+    Ok(next.run(request).await)
+}
+
+// 书籍 id 超出数据库字段范围时按请求错误处理
+fn book_id(id: u32) -> Result<i32, ApiError> {
+    i32::try_from(id).map_err(|_| ApiError(StatusCode::BAD_REQUEST, format!("Invalid book id {}", id)))
+}
+
+// 构建路由
+pub fn book_router(access: ServerAccess) -> Router {
+    Router::new()
+        .route("/books", get(list_books).post(create_book).delete(delete_books))
+        .route("/books/search", post(search_books))
+        .route("/books/fulltext", get(full_text_search))
+        .route("/books/facets", post(book_facets))
+        .route("/books/stats", post(book_stats))
+        .route(
+            "/books/:id",
+            get(get_book).put(update_book).delete(delete_book),
+        )
+        .layer(middleware::from_fn_with_state(Arc::new(access), check_access))
+}
+
+async fn list_books(Query(params): Query<PageParams>) -> ApiResult<Page<Book>> {
+    let q = params.q.clone().unwrap_or_default();
+    let sorts = params.sorts();
+    // 有关键字且未指定排序时按相关度检索
+    let page = if !q.trim().is_empty() && sorts.is_empty() {
+        BookDao::full_text_search(&q, params.page(), params.page_size())
+            .await?
+            .map(|hit| hit.doc)
+    } else {
+        BookDao::search(&q, &sorts, params.page(), params.page_size()).await?
+    };
+    Ok(Json(page))
+}
+
+async fn full_text_search(Query(params): Query<PageParams>) -> ApiResult<Page<BookHit>> {
+    let q = params.q.clone().unwrap_or_default();
+    let page = BookDao::full_text_search(&q, params.page(), params.page_size()).await?;
+    Ok(Json(page))
+}
+
+async fn search_books(
+    Query(params): Query<PageParams>,
+    Json(query): Json<BookQuery>,
+) -> ApiResult<Page<Book>> {
+    let page = BookDao::dynamics_search(&query, params.page(), params.page_size()).await?;
+    Ok(Json(page))
+}
+
+async fn book_facets(Json(query): Json<BookQuery>) -> ApiResult<BookFacets> {
+    Ok(Json(BookDao::facets(&query).await?))
+}
+
+async fn book_stats(Json(query): Json<BookQuery>) -> ApiResult<BookStats> {
+    Ok(Json(BookDao::stats(&query).await?))
+}
+
+async fn get_book(Path(id): Path<u32>) -> ApiResult<Book> {
+    match BookDao::get_by_id(book_id(id)?).await? {
+        Some(book) => Ok(Json(book)),
+        None => Err(ApiError(StatusCode::NOT_FOUND, format!("Book {} not found", id))),
+    }
+}
+
+async fn create_book(Json(book): Json<Book>) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let id = BookDao::create(&book).await?;
+    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+}
+
+async fn update_book(Path(id): Path<u32>, Json(mut book): Json<Book>) -> ApiResult<ApiStatus> {
+    book.id = Some(book_id(id)?);
+    if BookDao::update(&book).await? {
+        Ok(Json(ApiStatus { ok: true }))
+    } else {
+        Err(ApiError(StatusCode::NOT_FOUND, format!("Book {} not found", id)))
+    }
+}
+
+async fn delete_book(Path(id): Path<u32>) -> ApiResult<ApiStatus> {
+    if BookDao::delete(book_id(id)?).await? {
+        Ok(Json(ApiStatus { ok: true }))
+    } else {
+        Err(ApiError(StatusCode::NOT_FOUND, format!("Book {} not found", id)))
+    }
+}
+
+async fn delete_books(Json(body): Json<IdsBody>) -> ApiResult<ApiStatus> {
+    let ids = body.ids.iter().map(|&id| book_id(id)).collect::<Result<Vec<_>, _>>()?;
+    let ok = BookDao::deletes(&ids).await? > 0;
+    Ok(Json(ApiStatus { ok }))
+}
+
+/// 启动本地 HTTP 接口服务，供 setup 和命令共用
+/// 使用 TCP/WebSocket 服务的访问令牌，未设置令牌时只能绑定本机地址
+pub async fn serve_http_api(app: &AppHandle, ip: &str, port: u16) -> Result<String, String> {
+    let state = app.state::<HttpApiState>();
+    if state.shutdown_tx.lock().unwrap().is_some() {
+        return Err("HTTP API is already running".into());
+    }
+
+    let access = ServerAccess::from_app(app);
+    let loopback = ip == "localhost" || ip.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+    if !loopback && !access.requires_token() {
+        return Err(format!("HTTP API can only bind to {} after server_token is set", ip));
+    }
+
+    let constr = format!("{}:{}", ip, port);
+    let listener = TcpListener::bind(&constr)
+        .await
+        .map_err(|e| format!("Failed to bind to {}: {}", constr, e))?;
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    *state.shutdown_tx.lock().unwrap() = Some(shutdown_tx);
+    *state.addr.lock().unwrap() = Some(constr.clone());
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        let res = axum::serve(listener, book_router(access))
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = res {
+            eprintln!("HTTP API stopped with error: {}", e);
+        }
+        // 出错退出时清除状态，之后可以重新启动；shutdown_rx 已随服务释放，
+        // 发送端已关闭说明状态仍属于本次服务，而不是之后重新启动的服务
+        let state = app.state::<HttpApiState>();
+        let mut tx = state.shutdown_tx.lock().unwrap();
+        if tx.as_ref().is_some_and(|tx| tx.is_closed()) {
+            *tx = None;
+            *state.addr.lock().unwrap() = None;
+        }
+        println!("HTTP API stopped");
+    });
+
+    println!("HTTP API listening on {}", constr);
+    Ok(constr)
+}
+
+#[tauri::command]
+pub async fn start_http_api(app: AppHandle, ip: Option<String>, port: u16) -> Result<String, String> {
+    // 默认只绑定本机地址
+    let ip = ip.unwrap_or_else(|| "127.0.0.1".to_string());
+    serve_http_api(&app, &ip, port).await
+}
+
+#[tauri::command]
+pub fn stop_http_api(state: State<'_, HttpApiState>) -> Result<(), String> {
+    let tx = state.shutdown_tx.lock().unwrap().take();
+    *state.addr.lock().unwrap() = None;
+    match tx {
+        Some(tx) => {
+            let _ = tx.send(());
+            Ok(())
+        }
+        None => Err("HTTP API is not running".into()),
+    }
+}
+
+// 返回当前监听地址，未运行时为 None
+#[tauri::command]
+pub fn get_http_api_addr(state: State<'_, HttpApiState>) -> Option<String> {
+    state.addr.lock().unwrap().clone()
+}
@@ -0,0 +1,463 @@
+use crate::commands::{enroll_face, run_backup, AppRecog2021State};
+use crate::util::uuid_util;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::fs;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use walkdir::WalkDir;
+
+// 历史记录最多保留的条数
+const MAX_HISTORY: usize = 200;
+
+// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+// 任务信息（运行中的任务和历史记录共用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: String,
+    pub status: JobStatus,
+    pub progress: u32,
+    pub message: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+// job_progress 事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct JobProgress {
+    pub id: String,
+    pub kind: String,
+    pub progress: u32,
+    pub message: String,
+    pub timestamp: String,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+// 后台任务管理器
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+    history: Mutex<Vec<JobInfo>>,
+    history_path: PathBuf,
+}
+
+impl JobManager {
+    // 从应用数据目录加载任务历史
+    pub fn load(app: &AppHandle) -> Self {
+        let history_path = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join("job_history.json"))
+            .unwrap_or_else(|_| PathBuf::from("job_history.json"));
+        let history = fs::read_to_string(&history_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<Vec<JobInfo>>(&s).ok())
+            .unwrap_or_default();
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            history: Mutex::new(history),
+            history_path,
+        }
+    }
+
+    fn save_history(&self, history: &[JobInfo]) {
+        if let Some(parent) = self.history_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(history) {
+            Ok(s) => {
+                if let Err(e) = fs::write(&self.history_path, s) {
+                    eprintln!("保存任务历史失败: {}", e);
+                }
+            }
+            Err(e) => eprintln!("序列化任务历史失败: {}", e),
+        }
+    }
+
+    fn update<F: FnOnce(&mut JobInfo)>(&self, id: &str, f: F) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(id)?;
+        f(&mut entry.info);
+        Some(entry.info.clone())
+    }
+
+    // 任务结束：移出运行列表并写入历史
+    fn finish(&self, id: &str, outcome: Result<Value, String>, elapsed: Duration) -> Option<JobInfo> {
+        let entry = self.jobs.lock().unwrap().remove(id)?;
+        let mut info = entry.info;
+        info.finished_at = Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        info.duration_ms = Some(elapsed.as_millis() as u64);
+        match outcome {
+            Ok(result) => {
+                info.status = JobStatus::Succeeded;
+                info.progress = 100;
+                info.result = Some(result);
+            }
+            Err(_) if entry.cancel.load(Ordering::SeqCst) => {
+                info.status = JobStatus::Cancelled;
+                info.error = Some("任务已取消".to_string());
+            }
+            Err(e) => {
+                info.status = JobStatus::Failed;
+                info.error = Some(e);
+            }
+        }
+
+        let mut history = self.history.lock().unwrap();
+        history.insert(0, info.clone());
+        history.truncate(MAX_HISTORY);
+        self.save_history(&history);
+        Some(info)
+    }
+}
+
+// 传给任务体的上下文，用于上报进度和检查取消
+#[derive(Clone)]
+pub struct JobContext {
+    pub id: String,
+    pub kind: String,
+    app_handle: AppHandle,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    // 上报进度（0-100）
+    pub fn progress(&self, progress: u32, message: impl Into<String>) {
+        let progress = progress.min(100);
+        let message = message.into();
+        let manager = self.app_handle.state::<JobManager>();
+        manager.update(&self.id, |info| {
+            info.progress = progress;
+            info.message = message.clone();
+        });
+        let event = JobProgress {
+            id: self.id.clone(),
+            kind: self.kind.clone(),
+            progress,
+            message,
+            timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        };
+        if let Err(e) = self.app_handle.emit("job_progress", &event) {
+            eprintln!("Failed to emit event: {}", e);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    // 已取消时返回错误，方便在任务体中用 ? 提前退出
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err("任务已取消".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn app_handle(&self) -> &AppHandle {
+        &self.app_handle
+    }
+}
+
+/// 提交一个后台任务，返回任务 id
+pub fn spawn_job<F, Fut>(app_handle: &AppHandle, kind: &str, work: F) -> String
+where
+    F: FnOnce(JobContext) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Value, String>> + Send + 'static,
+{
+    let id = uuid_util::new_id();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let info = JobInfo {
+        id: id.clone(),
+        kind: kind.to_string(),
+        status: JobStatus::Running,
+        progress: 0,
+        message: String::new(),
+        started_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        finished_at: None,
+        duration_ms: None,
+        result: None,
+        error: None,
+    };
+    {
+        let manager = app_handle.state::<JobManager>();
+        manager.jobs.lock().unwrap().insert(
+            id.clone(),
+            JobEntry {
+                info: info.clone(),
+                cancel: Arc::clone(&cancel),
+            },
+        );
+    }
+    let _ = app_handle.emit("job_started", &info);
+
+    let ctx = JobContext {
+        id: id.clone(),
+        kind: kind.to_string(),
+        app_handle: app_handle.clone(),
+        cancel,
+    };
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let start = Instant::now();
+        let job_id = ctx.id.clone();
+        // 任务体在单独的 task 中运行，panic 时记为失败而不是一直停留在运行中
+        let outcome = tauri::async_runtime::spawn(work(ctx))
+            .await
+            .unwrap_or_else(|e| Err(format!("任务异常退出: {}", e)));
+        let manager = app_handle.state::<JobManager>();
+        if let Some(info) = manager.finish(&job_id, outcome, start.elapsed()) {
+            println!("任务结束 {} {:?}", info.kind, info.status);
+            if let Err(e) = app_handle.emit("job_finished", &info) {
+                eprintln!("Failed to emit event: {}", e);
+            }
+        }
+    });
+    id
+}
+
+// 提交内置任务
+// kind: backup_db { dbname, filename, gzip?, profile? }
+//       scan_directory { path }
+//       face_enroll_batch { items: [{ name, path }] }
+#[command]
+pub fn submit_job(app_handle: AppHandle, kind: String, params: Value) -> Result<String, String> {
+    match kind.as_str() {
+        "backup_db" => {
+            let dbname = param_str(&params, "dbname")?;
+            let filename = param_str(&params, "filename")?;
+            let gzip = params["gzip"].as_bool().unwrap_or_else(|| filename.ends_with(".gz"));
+            let profile = params["profile"].as_str().map(|s| s.to_string());
+            Ok(spawn_job(&app_handle, &kind, move |ctx| async move {
+                backup_db_job(ctx, profile, dbname, filename, gzip).await
+            }))
+        }
+        "scan_directory" => {
+            let path = param_str(&params, "path")?;
+            Ok(spawn_job(&app_handle, &kind, move |ctx| async move {
+                tauri::async_runtime::spawn_blocking(move || scan_directory_job(ctx, PathBuf::from(path)))
+                    .await
+                    .map_err(|e| e.to_string())?
+            }))
+        }
+        "face_enroll_batch" => {
+            let items: Vec<FaceEnrollItem> = serde_json::from_value(params["items"].clone())
+                .map_err(|e| format!("参数 items 无效: {}", e))?;
+            Ok(spawn_job(&app_handle, &kind, move |ctx| async move {
+                tauri::async_runtime::spawn_blocking(move || face_enroll_batch_job(ctx, items))
+                    .await
+                    .map_err(|e| e.to_string())?
+            }))
+        }
+        _ => Err(format!("未知的任务类型: {}", kind)),
+    }
+}
+
+fn param_str(params: &Value, key: &str) -> Result<String, String> {
+    params[key]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("缺少参数: {}", key))
+}
+
+async fn backup_db_job(
+    ctx: JobContext,
+    profile: Option<String>,
+    dbname: String,
+    filename: String,
+    gzip: bool,
+) -> Result<Value, String> {
+    ctx.progress(0, format!("开始备份数据库 {}", dbname));
+    // 以表为单位计算进度，每张表开始前和每批数据后检查取消，取消或失败时删除未写完的文件
+    let summary = run_backup(profile.as_deref(), &dbname, &filename, gzip, |p| {
+        ctx.check_cancelled()?;
+        let pct = ((p.table_index.saturating_sub(1)) * 100 / p.table_count.max(1)) as u32;
+        ctx.progress(pct, format!("{} ({}/{}) {} 行", p.table, p.table_index, p.table_count, p.rows));
+        Ok(())
+    })
+    .await?;
+    ctx.progress(100, "Backup successful".to_string());
+    serde_json::to_value(&summary).map_err(|e| e.to_string())
+}
+
+// 扫描目录，统计文件数量和大小
+fn scan_directory_job(ctx: JobContext, path: PathBuf) -> Result<Value, String> {
+    if !path.is_dir() {
+        return Err(format!("路径不是目录: {}", path.display()));
+    }
+    // 以顶层条目为单位计算进度
+    let top: Vec<PathBuf> = fs::read_dir(&path)
+        .map_err(|e| format!("无法读取目录内容: {} - {}", path.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    let total = top.len().max(1);
+    let (mut files, mut dirs, mut size) = (0u64, 0u64, 0u64);
+
+    for (i, entry) in top.iter().enumerate() {
+        ctx.check_cancelled()?;
+        for item in WalkDir::new(entry).into_iter().filter_map(|e| e.ok()) {
+            if ctx.is_cancelled() {
+                break;
+            }
+            match item.metadata() {
+                Ok(m) if m.is_file() => {
+                    files += 1;
+                    size += m.len();
+                }
+                Ok(m) if m.is_dir() => dirs += 1,
+                _ => {}
+            }
+        }
+        ctx.progress(
+            (((i + 1) * 100) / total) as u32,
+            format!("已扫描 {}", display_name(entry)),
+        );
+    }
+    ctx.check_cancelled()?;
+    Ok(json!({
+        "path": path.to_string_lossy(),
+        "total_files": files,
+        "total_dirs": dirs,
+        "total_size": size,
+    }))
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FaceEnrollItem {
+    pub name: String,
+    pub path: String,
+}
+
+// 批量录入人脸，单张失败不影响其它图片
+fn face_enroll_batch_job(ctx: JobContext, items: Vec<FaceEnrollItem>) -> Result<Value, String> {
+    let state = ctx
+        .app_handle()
+        .try_state::<AppRecog2021State>()
+        .ok_or_else(|| "人脸识别未初始化".to_string())?;
+    let total = items.len().max(1);
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+
+    for (i, item) in items.into_iter().enumerate() {
+        ctx.check_cancelled()?;
+        let res = fs::read(&item.path)
+            .map_err(|e| format!("读取图片失败: {}", e))
+            .and_then(|data| {
+                let file_name = display_name(Path::new(&item.path));
+                enroll_face(&state, item.name.clone(), data, file_name)
+            });
+        match res {
+            Ok(_) => succeeded.push(item.name.clone()),
+            Err(e) => failed.push(json!({ "name": item.name, "path": item.path, "error": e })),
+        }
+        ctx.progress((((i + 1) * 100) / total) as u32, format!("已处理 {}", item.name));
+    }
+    Ok(json!({ "succeeded": succeeded, "failed": failed }))
+}
+
+// 演示任务：原 progress_update 的流程
+pub async fn demo_progress_job(ctx: JobContext) -> Result<Value, String> {
+    let steps = [
+        (2, "操作准备中"),
+        (11, "下班回家"),
+        (24, "钉钉打卡"),
+        (32, "自行车行驶"),
+        (44, "菜市场买菜"),
+        (54, "自行车停放"),
+        (64, "收快递回家"),
+        (77, "播放音乐"),
+        (87, "洗菜做饭"),
+        (92, "打开电视并吃饭"),
+        (100, "准备睡觉"),
+    ];
+    for (progress, msg) in steps {
+        ctx.check_cancelled()?;
+        let formatted_now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        // 保留旧事件，兼容现有页面
+        let _ = ctx.app_handle().emit("progress_msg", format!("{},{}", formatted_now, msg));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let _ = ctx.app_handle().emit("progress_update", progress);
+        ctx.progress(progress, msg);
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+    }
+    Ok(Value::Null)
+}
+
+#[command]
+pub fn cancel_job(manager: State<'_, JobManager>, id: String) -> Result<(), String> {
+    let jobs = manager.jobs.lock().unwrap();
+    let entry = jobs.get(&id).ok_or_else(|| format!("任务 {} 不存在或已结束", id))?;
+    entry.cancel.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+// 查询任务状态，运行中的任务优先，其次查历史
+#[command]
+pub fn get_job(manager: State<'_, JobManager>, id: String) -> Result<JobInfo, String> {
+    if let Some(entry) = manager.jobs.lock().unwrap().get(&id) {
+        return Ok(entry.info.clone());
+    }
+    manager
+        .history
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|j| j.id == id)
+        .cloned()
+        .ok_or_else(|| format!("任务 {} 不存在", id))
+}
+
+#[command]
+pub fn list_jobs(manager: State<'_, JobManager>) -> Vec<JobInfo> {
+    let jobs = manager.jobs.lock().unwrap();
+    let mut list: Vec<JobInfo> = jobs.values().map(|j| j.info.clone()).collect();
+    list.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    list
+}
+
+#[command]
+pub fn get_job_history(manager: State<'_, JobManager>, kind: Option<String>) -> Vec<JobInfo> {
+    let history = manager.history.lock().unwrap();
+    history
+        .iter()
+        .filter(|j| kind.as_ref().map_or(true, |k| &j.kind == k))
+        .cloned()
+        .collect()
+}
+
+#[command]
+pub fn clear_job_history(manager: State<'_, JobManager>) -> Result<(), String> {
+    let mut history = manager.history.lock().unwrap();
+    history.clear();
+    manager.save_history(&history);
+    Ok(())
+}
@@ -13,6 +13,10 @@ pub mod udp;
 pub mod blazeface;
 pub mod recog2021;
 pub mod detectyunet2023;
+pub mod websocket;
+pub mod httpapi;
+pub mod job;
+pub mod scheduler;
 
 // 导出所有命令函数
 pub use book::*;
@@ -30,6 +34,10 @@ pub use udp::*;
 pub use blazeface::*;
 pub use recog2021::*;
 pub use detectyunet2023::*;
+pub use websocket::*;
+pub use httpapi::*;
+pub use job::*;
+pub use scheduler::*;
 
 // 生成最终的处理函数
 #[macro_export]
@@ -44,6 +52,10 @@ macro_rules! register_commands {
             $crate::commands::book::list,
             $crate::commands::book::search,
             $crate::commands::book::dynamics_search,
+            $crate::commands::book::book_facets,
+            $crate::commands::book::book_stats,
+            $crate::commands::book::full_text_search,
+            $crate::commands::book::rebuild_search_index,
             $crate::commands::book::create,
             $crate::commands::book::update,
             $crate::commands::book::delete,
@@ -56,6 +68,9 @@ macro_rules! register_commands {
             $crate::commands::config::get_config_field,
             $crate::commands::config::set_config_field,
             $crate::commands::config::reset_config,
+            $crate::commands::config::credential_status,
+            $crate::commands::config::unlock_credentials,
+            $crate::commands::config::set_master_passphrase,
             $crate::commands::thread::start_time,
             $crate::commands::thread::stop_time,
             $crate::commands::thread::start_timer,
@@ -64,6 +79,24 @@ macro_rules! register_commands {
             $crate::commands::thread::set_shared_value,
             $crate::commands::thread::get_last_update,
             $crate::commands::thread::progress_update,
+            $crate::commands::thread::create_timer,
+            $crate::commands::thread::pause_timer,
+            $crate::commands::thread::resume_timer,
+            $crate::commands::thread::cancel_timer,
+            $crate::commands::thread::set_timer_payload,
+            $crate::commands::thread::list_timers,
+            $crate::commands::job::submit_job,
+            $crate::commands::job::cancel_job,
+            $crate::commands::job::get_job,
+            $crate::commands::job::list_jobs,
+            $crate::commands::job::get_job_history,
+            $crate::commands::job::clear_job_history,
+            $crate::commands::scheduler::list_schedules,
+            $crate::commands::scheduler::save_schedule,
+            $crate::commands::scheduler::delete_schedule,
+            $crate::commands::scheduler::run_schedule_now,
+            $crate::commands::scheduler::get_schedule_next_run,
+            $crate::commands::scheduler::get_schedule_log,
             $crate::commands::tcp::start_tcp_server,
             $crate::commands::tcp::stop_tcp_server,
             $crate::commands::tcp::send_to_clients,
@@ -72,6 +105,14 @@ macro_rules! register_commands {
             $crate::commands::tcp::tcp_client_connect,
             $crate::commands::tcp::disconnect,
             $crate::commands::tcp::send_message,
+            $crate::commands::websocket::start_ws_server,
+            $crate::commands::websocket::stop_ws_server,
+            $crate::commands::websocket::ws_send_to_clients,
+            $crate::commands::websocket::ws_send_to_client,
+            $crate::commands::websocket::get_ws_clients,
+            $crate::commands::httpapi::start_http_api,
+            $crate::commands::httpapi::stop_http_api,
+            $crate::commands::httpapi::get_http_api_addr,
             $crate::commands::udp::open_broadcast_service,
             $crate::commands::udp::close_broadcast_service,
             $crate::commands::udp::send_broadcast_message,
@@ -112,15 +153,56 @@ macro_rules! register_commands {
             $crate::commands::recog2021::get_face_image,
             $crate::commands::recog2021::save_captured_frame,
             $crate::commands::sqlx::connect_db,
+            $crate::commands::sqlx::connect_sqlite,
             $crate::commands::sqlx::get_alldbname,
             $crate::commands::sqlx::get_alltablenamebydbname,
+            $crate::commands::sqlx::get_allschemaname,
             $crate::commands::sqlx::get_table_columns,
             $crate::commands::sqlx::execute_sql_command,
             $crate::commands::sqlx::query_table_data,
             $crate::commands::sqlx::backup_db_command,
             $crate::commands::sqlx::revert_db_command,
+            $crate::commands::sqlx::check_restore,
             $crate::commands::sqlx::execute_dbtable_command,
-            $crate::commands::sqlx::check_and_create_traft_db
+            $crate::commands::sqlx::check_and_create_traft_db,
+            $crate::commands::sqlx::list_db_profiles,
+            $crate::commands::sqlx::save_db_profile,
+            $crate::commands::sqlx::delete_db_profile,
+            $crate::commands::sqlx::open_db_profile,
+            $crate::commands::sqlx::close_db_profile,
+            $crate::commands::sqlx::list_open_profiles,
+            $crate::commands::sqlx::stream_query,
+            $crate::commands::sqlx::cancel_query,
+            $crate::commands::sqlx::ack_query_batch,
+            $crate::commands::sqlx::count_query,
+            $crate::commands::sqlx::query_page,
+            $crate::commands::sqlx::check_sql,
+            $crate::commands::sqlx::list_migrations,
+            $crate::commands::sqlx::rollback_migration,
+            $crate::commands::sqlx::import_table_data,
+            $crate::commands::sqlx::export_query_data,
+            $crate::commands::sqlx::describe_table,
+            $crate::commands::sqlx::diff_schema,
+            $crate::commands::sqlx::insert_table_row,
+            $crate::commands::sqlx::update_table_row,
+            $crate::commands::sqlx::delete_table_row,
+            $crate::commands::sqlx::open_console,
+            $crate::commands::sqlx::close_console,
+            $crate::commands::sqlx::list_consoles,
+            $crate::commands::sqlx::console_execute,
+            $crate::commands::sqlx::console_begin,
+            $crate::commands::sqlx::console_commit,
+            $crate::commands::sqlx::console_rollback,
+            $crate::commands::sqlx::search_query_history,
+            $crate::commands::sqlx::delete_query_history,
+            $crate::commands::sqlx::clear_query_history,
+            $crate::commands::sqlx::save_query,
+            $crate::commands::sqlx::list_saved_queries,
+            $crate::commands::sqlx::delete_saved_query,
+            $crate::commands::sqlx::run_saved_query,
+            $crate::commands::sqlx::explain_query,
+            $crate::commands::sqlx::get_db_status,
+            $crate::commands::sqlx::reconnect_db
         ]
     };
 }
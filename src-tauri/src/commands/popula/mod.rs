@@ -4,7 +4,7 @@ use tauri::command;
 
 #[command]
 pub async fn popula_list() -> Result<Vec<Popula>, String> {
-    match PopulaDao::list().await {
+    match PopulaDao::all().await {
         Ok(populas) => Ok(populas),
         Err(e) => {
             let error_message = format!("Failed to list populas: {:?}", e);
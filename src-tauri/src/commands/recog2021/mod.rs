@@ -17,6 +17,18 @@ pub struct FaceData {
     image_path: String,
 }
 
+// 单个人脸的识别结果（通过 recognition_result 事件发送）
+#[derive(Debug, Clone, Serialize)]
+pub struct RecognitionResult {
+    pub name: Option<String>,
+    pub similarity: f32,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub timestamp: String,
+}
+
 // 应用状态
 pub struct AppRecog2021State {
     pub face_db: Arc<RwLock<HashMap<String, FaceData>>>,
@@ -132,6 +144,11 @@ pub fn load_cascade_classifier_from_memory() -> Result<objdetect::CascadeClassif
 
 #[tauri::command]
 pub fn add_face(state: State<'_, AppRecog2021State>, name: String, image_data: Vec<u8>, file_name: String) -> Result<String, String> {
+    enroll_face(&state, name, image_data, file_name)
+}
+
+/// 录入一张人脸，供 add_face 命令和批量录入任务共用
+pub fn enroll_face(state: &AppRecog2021State, name: String, image_data: Vec<u8>, file_name: String) -> Result<String, String> {
     println!("添加人脸: {}", name);
 
     // 创建人脸存储目录
@@ -348,6 +365,9 @@ pub fn start_recognition(state: State<'_, AppRecog2021State>, window: Window) ->
                 continue;
             }
 
+            // 本帧的识别结果
+            let mut results: Vec<RecognitionResult> = Vec::new();
+
             // 对每个检测到的人脸进行识别
             for face_rect in faces.iter() {
                 // 提取人脸区域
@@ -433,6 +453,16 @@ pub fn start_recognition(state: State<'_, AppRecog2021State>, window: Window) ->
                     eprintln!("绘制矩形失败: {}", e);
                 }
 
+                results.push(RecognitionResult {
+                    name: best_match.map(|f| f.name.clone()),
+                    similarity: if best_match.is_some() { best_similarity } else { 0.0 },
+                    x: face_rect.x,
+                    y: face_rect.y,
+                    width: face_rect.width,
+                    height: face_rect.height,
+                    timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                });
+
                 if let Some(face_data) = best_match {
                     let label = format!("{}: {:.2}", face_data.name, best_similarity);
                     let pos = core::Point::new(face_rect.x, face_rect.y - 10);
@@ -460,6 +490,12 @@ pub fn start_recognition(state: State<'_, AppRecog2021State>, window: Window) ->
                 }
             };
 
+            if !results.is_empty() {
+                if let Err(e) = window_guard.emit("recognition_result", &results) {
+                    eprintln!("发送识别结果失败: {}", e);
+                }
+            }
+
             if let Err(e) = window_guard.emit("video-frame", img_str) {
                 eprintln!("发送视频帧失败: {}", e);
                 break;
@@ -0,0 +1,307 @@
+use crate::commands::{delete_empty_directories, run_backup, AppState, TcpServerState};
+use crate::util::{cron_util, uuid_util, ScheduledAction, ScheduledTask};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::async_runtime::JoinHandle;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+// 运行日志最多保留的条数
+const MAX_RUN_LOG: usize = 500;
+
+// 单次执行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRunLog {
+    pub task_id: String,
+    pub name: String,
+    pub action: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub message: String,
+}
+
+// 调度器状态
+pub struct SchedulerState {
+    handles: Mutex<HashMap<String, JoinHandle<()>>>,
+    run_log: Mutex<Vec<ScheduleRunLog>>,
+    log_path: PathBuf,
+}
+
+impl SchedulerState {
+    pub fn load(app: &AppHandle) -> Self {
+        let log_path = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join("schedule_log.json"))
+            .unwrap_or_else(|_| PathBuf::from("schedule_log.json"));
+        let run_log = fs::read_to_string(&log_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<Vec<ScheduleRunLog>>(&s).ok())
+            .unwrap_or_default();
+        Self {
+            handles: Mutex::new(HashMap::new()),
+            run_log: Mutex::new(run_log),
+            log_path,
+        }
+    }
+
+    fn push_log(&self, entry: ScheduleRunLog) {
+        let mut log = self.run_log.lock().unwrap();
+        log.insert(0, entry);
+        log.truncate(MAX_RUN_LOG);
+        if let Some(parent) = self.log_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&*log) {
+            Ok(s) => {
+                if let Err(e) = fs::write(&self.log_path, s) {
+                    eprintln!("保存定时任务日志失败: {}", e);
+                }
+            }
+            Err(e) => eprintln!("序列化定时任务日志失败: {}", e),
+        }
+    }
+
+    // 停止某个任务的调度循环
+    fn stop(&self, id: &str) {
+        if let Some(handle) = self.handles.lock().unwrap().remove(id) {
+            handle.abort();
+        }
+    }
+}
+
+fn action_name(action: &ScheduledAction) -> &'static str {
+    match action {
+        ScheduledAction::BackupDb { .. } => "backup_db",
+        ScheduledAction::DeleteEmptyDirectories { .. } => "delete_empty_directories",
+        ScheduledAction::TcpBroadcast { .. } => "tcp_broadcast",
+    }
+}
+
+// 为 id 为空或重复的任务生成新 id，返回是否有修改
+// 调度循环按 id 区分，同 id 的任务启动时会互相停止
+fn assign_missing_ids(tasks: &mut [ScheduledTask]) -> bool {
+    let mut seen = HashSet::new();
+    let mut changed = false;
+    for task in tasks {
+        if task.id.is_empty() || !seen.insert(task.id.clone()) {
+            task.id = uuid_util::new_id();
+            seen.insert(task.id.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// 启动时根据配置加载全部定时任务，手动编辑的配置中缺少或重复的 id 会重新生成并保存
+pub fn start_scheduler(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let tasks = match state.config.lock() {
+        Ok(mut config) => {
+            if assign_missing_ids(&mut config.schedules) {
+                if let Err(e) = config.save(app) {
+                    eprintln!("保存定时任务 id 失败: {}", e);
+                }
+            }
+            config.schedules.clone()
+        }
+        Err(_) => return,
+    };
+    for task in tasks {
+        if let Err(e) = schedule_task(app, task) {
+            eprintln!("定时任务加载失败: {}", e);
+        }
+    }
+}
+
+// 为单个任务启动调度循环（会先停止同 id 的旧循环）
+fn schedule_task(app: &AppHandle, task: ScheduledTask) -> Result<(), String> {
+    let scheduler = app.state::<SchedulerState>();
+    scheduler.stop(&task.id);
+    if !task.enabled {
+        return Ok(());
+    }
+    let schedule = cron_util::parse_cron(&task.cron)?;
+    let app_handle = app.clone();
+    let id = task.id.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        while let Some(delay) = cron_util::duration_until_next(&schedule) {
+            tokio::time::sleep(delay).await;
+            execute_task(&app_handle, &task).await;
+        }
+    });
+    scheduler.handles.lock().unwrap().insert(id, handle);
+    Ok(())
+}
+
+// 执行任务并写入运行日志
+async fn execute_task(app: &AppHandle, task: &ScheduledTask) -> ScheduleRunLog {
+    let started_at = Local::now();
+    let start = Instant::now();
+    let result = run_action(app, &task.action).await;
+    let entry = ScheduleRunLog {
+        task_id: task.id.clone(),
+        name: task.name.clone(),
+        action: action_name(&task.action).to_string(),
+        started_at: started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        success: result.is_ok(),
+        message: result.unwrap_or_else(|e| e),
+    };
+    println!("定时任务执行 {} {:?}", task.name, entry.success);
+    app.state::<SchedulerState>().push_log(entry.clone());
+    if let Err(e) = app.emit("schedule_run", &entry) {
+        eprintln!("Failed to emit event: {}", e);
+    }
+    entry
+}
+
+async fn run_action(app: &AppHandle, action: &ScheduledAction) -> Result<String, String> {
+    match action.clone() {
+        ScheduledAction::BackupDb {
+            dbname,
+            filename,
+            gzip,
+            profile,
+        } => {
+            // 格式串非法时 to_string 会 panic，这里用 write! 捕获错误
+            let mut path = String::new();
+            write!(path, "{}", Local::now().format(&filename)).map_err(|_| format!("无效的文件名格式: {}", filename))?;
+            let filename = path;
+            let gzip = gzip.unwrap_or_else(|| filename.ends_with(".gz"));
+            let summary = run_backup(profile.as_deref(), &dbname, &filename, gzip, |_| Ok(())).await?;
+            Ok(format!(
+                "Backup successful: {} ({} 张表, {} 行)",
+                filename, summary.tables, summary.rows
+            ))
+        }
+        ScheduledAction::DeleteEmptyDirectories { paths } => {
+            let count = paths.len();
+            delete_empty_directories(paths)?;
+            Ok(format!("已处理 {} 个目录", count))
+        }
+        ScheduledAction::TcpBroadcast { message } => {
+            let state = app.state::<Arc<RwLock<TcpServerState>>>();
+            let state = state.read().await;
+            let tx = state
+                .tx
+                .as_ref()
+                .ok_or_else(|| "TCP 服务未运行".to_string())?;
+            let receivers = tx
+                .send(message + "\r\n")
+                .map_err(|_| "没有已连接的客户端".to_string())?;
+            Ok(format!("已发送给 {} 个客户端", receivers))
+        }
+    }
+}
+
+// 保存配置中的定时任务列表
+fn persist_schedules<F: FnOnce(&mut Vec<ScheduledTask>)>(app: &AppHandle, f: F) -> Result<Vec<ScheduledTask>, String> {
+    let state = app.state::<AppState>();
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    f(&mut config.schedules);
+    config.save(app)?;
+    Ok(config.schedules.clone())
+}
+
+#[command]
+pub fn list_schedules(state: State<'_, AppState>) -> Result<Vec<ScheduledTask>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    Ok(config.schedules.clone())
+}
+
+// 新增或更新定时任务（id 为空时新增）
+#[command]
+pub fn save_schedule(app: AppHandle, mut task: ScheduledTask) -> Result<ScheduledTask, String> {
+    cron_util::parse_cron(&task.cron)?;
+    if task.id.is_empty() {
+        task.id = uuid_util::new_id();
+    }
+    let saved = task.clone();
+    persist_schedules(&app, |list| match list.iter_mut().find(|t| t.id == saved.id) {
+        Some(existing) => *existing = saved.clone(),
+        None => list.push(saved.clone()),
+    })?;
+    schedule_task(&app, task.clone())?;
+    Ok(task)
+}
+
+#[command]
+pub fn delete_schedule(app: AppHandle, id: String) -> Result<(), String> {
+    app.state::<SchedulerState>().stop(&id);
+    persist_schedules(&app, |list| list.retain(|t| t.id != id))?;
+    Ok(())
+}
+
+// 立即执行一次
+#[command]
+pub async fn run_schedule_now(app: AppHandle, id: String) -> Result<ScheduleRunLog, String> {
+    let task = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        config
+            .schedules
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+            .ok_or_else(|| format!("定时任务 {} 不存在", id))?
+    };
+    Ok(execute_task(&app, &task).await)
+}
+
+// 下一次触发时间
+#[command]
+pub fn get_schedule_next_run(cron: String) -> Result<Option<String>, String> {
+    let schedule = cron_util::parse_cron(&cron)?;
+    Ok(cron_util::next_fire(&schedule).map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()))
+}
+
+#[command]
+pub fn get_schedule_log(
+    scheduler: State<'_, SchedulerState>,
+    task_id: Option<String>,
+) -> Vec<ScheduleRunLog> {
+    let log = scheduler.run_log.lock().unwrap();
+    log.iter()
+        .filter(|l| task_id.as_ref().map_or(true, |id| &l.task_id == id))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str) -> ScheduledTask {
+        ScheduledTask {
+            id: id.to_string(),
+            name: id.to_string(),
+            cron: "0 0 * * *".to_string(),
+            enabled: true,
+            action: ScheduledAction::TcpBroadcast {
+                message: String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn assign_missing_ids_fills_empty_and_duplicate_ids() {
+        let mut tasks = vec![task("a"), task(""), task("a"), task(""), task("b")];
+        assert!(assign_missing_ids(&mut tasks));
+        let ids: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids.len(), tasks.len());
+        assert!(!ids.contains(""));
+        assert_eq!((tasks[0].id.as_str(), tasks[4].id.as_str()), ("a", "b"));
+
+        assert!(!assign_missing_ids(&mut tasks));
+    }
+}
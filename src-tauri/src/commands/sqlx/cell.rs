@@ -0,0 +1,246 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::Value;
+use sqlx::database::Database;
+use sqlx::query::Query;
+use sqlx::types::{Decimal, Json, Uuid};
+use sqlx::{MySql, Postgres, Sqlite};
+use std::str::FromStr;
+
+// 按字段类型转换并绑定参数值，导入和行编辑共用
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%Y%m%d"];
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M",
+];
+const TIME_FORMATS: &[&str] = &["%H:%M:%S%.f", "%H:%M"];
+
+// 字段类型大类，决定单元格的转换方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Int,
+    Float,
+    Decimal,
+    Bool,
+    Date,
+    DateTime,
+    Time,
+    Json,
+    Uuid,
+    Text,
+}
+
+pub fn column_kind(data_type: &str) -> ColumnKind {
+    let data_type = data_type.to_ascii_lowercase();
+    // 去掉长度和修饰，如 varchar(20)、timestamp with time zone
+    let base = data_type.split(['(', ' ']).next().unwrap_or("");
+    match base {
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2" | "int4"
+        | "int8" | "serial" | "smallserial" | "bigserial" | "year" => ColumnKind::Int,
+        "float" | "double" | "real" | "float4" | "float8" => ColumnKind::Float,
+        "decimal" | "numeric" | "dec" => ColumnKind::Decimal,
+        "bool" | "boolean" | "bit" => ColumnKind::Bool,
+        "date" => ColumnKind::Date,
+        "datetime" | "timestamp" | "timestamptz" => ColumnKind::DateTime,
+        "time" | "timetz" => ColumnKind::Time,
+        "json" | "jsonb" => ColumnKind::Json,
+        "uuid" => ColumnKind::Uuid,
+        _ => ColumnKind::Text,
+    }
+}
+
+// 转换后的单元格，NULL 也带类型，PostgreSQL 不接受类型不符的 NULL 参数
+#[derive(Debug, Clone)]
+pub enum Cell {
+    Int(Option<i64>),
+    Float(Option<f64>),
+    Decimal(Option<Decimal>),
+    Bool(Option<bool>),
+    Date(Option<NaiveDate>),
+    DateTime(Option<NaiveDateTime>),
+    Time(Option<NaiveTime>),
+    Json(Option<Json<Value>>),
+    Uuid(Option<Uuid>),
+    Text(Option<String>),
+}
+
+pub fn null_cell(kind: ColumnKind) -> Cell {
+    match kind {
+        ColumnKind::Int => Cell::Int(None),
+        ColumnKind::Float => Cell::Float(None),
+        ColumnKind::Decimal => Cell::Decimal(None),
+        ColumnKind::Bool => Cell::Bool(None),
+        ColumnKind::Date => Cell::Date(None),
+        ColumnKind::DateTime => Cell::DateTime(None),
+        ColumnKind::Time => Cell::Time(None),
+        ColumnKind::Json => Cell::Json(None),
+        ColumnKind::Uuid => Cell::Uuid(None),
+        ColumnKind::Text => Cell::Text(None),
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "t" | "yes" | "y" | "是" => Some(true),
+        "0" | "false" | "f" | "no" | "n" | "否" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|d| d.naive_utc()))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+        .or_else(|| parse_datetime(value).map(|d| d.date()))
+}
+
+/// 按字段类型转换单元格文本
+pub fn coerce(value: &str, kind: ColumnKind, empty_as_null: bool) -> Result<Cell, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() && (empty_as_null || kind != ColumnKind::Text) {
+        return Ok(null_cell(kind));
+    }
+    let cell = match kind {
+        ColumnKind::Int => {
+            let v = trimmed
+                .parse::<i64>()
+                .ok()
+                // Excel 中的整数可能带 .0
+                .or_else(|| {
+                    trimmed
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+                        .map(|f| f as i64)
+                })
+                .or_else(|| parse_bool(trimmed).map(i64::from))
+                .ok_or_else(|| format!("无法转换为整数: {}", trimmed))?;
+            Cell::Int(Some(v))
+        }
+        ColumnKind::Float => Cell::Float(Some(
+            trimmed
+                .parse::<f64>()
+                .map_err(|_| format!("无法转换为数字: {}", trimmed))?,
+        )),
+        ColumnKind::Decimal => Cell::Decimal(Some(
+            Decimal::from_str(trimmed)
+                .or_else(|_| Decimal::from_scientific(trimmed))
+                .map_err(|_| format!("无法转换为小数: {}", trimmed))?,
+        )),
+        ColumnKind::Bool => Cell::Bool(Some(
+            parse_bool(trimmed).ok_or_else(|| format!("无法转换为布尔值: {}", trimmed))?,
+        )),
+        ColumnKind::Date => Cell::Date(Some(
+            parse_date(trimmed).ok_or_else(|| format!("无法识别的日期: {}", trimmed))?,
+        )),
+        ColumnKind::DateTime => Cell::DateTime(Some(
+            parse_datetime(trimmed)
+                .or_else(|| parse_date(trimmed).map(|d| d.and_time(NaiveTime::MIN)))
+                .ok_or_else(|| format!("无法识别的时间: {}", trimmed))?,
+        )),
+        ColumnKind::Time => Cell::Time(Some(
+            TIME_FORMATS
+                .iter()
+                .find_map(|f| NaiveTime::parse_from_str(trimmed, f).ok())
+                .or_else(|| parse_datetime(trimmed).map(|d| d.time()))
+                .ok_or_else(|| format!("无法识别的时间: {}", trimmed))?,
+        )),
+        ColumnKind::Json => Cell::Json(Some(Json(
+            serde_json::from_str(trimmed).map_err(|e| format!("JSON 格式错误: {}", e))?,
+        ))),
+        ColumnKind::Uuid => Cell::Uuid(Some(
+            Uuid::parse_str(trimmed).map_err(|_| format!("无法识别的 UUID: {}", trimmed))?,
+        )),
+        ColumnKind::Text => Cell::Text(Some(value.to_string())),
+    };
+    Ok(cell)
+}
+
+/// 前端传来的 JSON 值按字段类型转换，字符串按单元格文本处理
+pub fn coerce_json(value: &Value, kind: ColumnKind) -> Result<Cell, String> {
+    match value {
+        Value::Null => Ok(null_cell(kind)),
+        Value::String(s) => coerce(s, kind, false),
+        Value::Bool(b) if kind == ColumnKind::Int => Ok(Cell::Int(Some(i64::from(*b)))),
+        Value::Bool(b) => coerce(&b.to_string(), kind, false),
+        Value::Number(n) => coerce(&n.to_string(), kind, false),
+        other if kind == ColumnKind::Json => Ok(Cell::Json(Some(Json(other.clone())))),
+        other if kind == ColumnKind::Text => Ok(Cell::Text(Some(other.to_string()))),
+        other => Err(format!("不能把 {} 转换为字段类型", other)),
+    }
+}
+
+/// 第 n 个参数的占位符（从 1 开始）
+pub fn placeholder(postgres: bool, n: usize) -> String {
+    if postgres {
+        format!("${}", n)
+    } else {
+        "?".to_string()
+    }
+}
+
+pub fn bind_mysql<'q>(
+    query: Query<'q, MySql, <MySql as Database>::Arguments<'q>>,
+    cell: &Cell,
+) -> Query<'q, MySql, <MySql as Database>::Arguments<'q>> {
+    match cell {
+        Cell::Int(v) => query.bind(*v),
+        Cell::Float(v) => query.bind(*v),
+        Cell::Decimal(v) => query.bind(*v),
+        Cell::Bool(v) => query.bind(*v),
+        Cell::Date(v) => query.bind(*v),
+        Cell::DateTime(v) => query.bind(*v),
+        Cell::Time(v) => query.bind(*v),
+        Cell::Json(v) => query.bind(v.clone()),
+        // MySQL 没有 UUID 类型，以文本保存
+        Cell::Uuid(v) => query.bind(v.map(|u| u.to_string())),
+        Cell::Text(v) => query.bind(v.clone()),
+    }
+}
+
+pub fn bind_pg<'q>(
+    query: Query<'q, Postgres, <Postgres as Database>::Arguments<'q>>,
+    cell: &Cell,
+) -> Query<'q, Postgres, <Postgres as Database>::Arguments<'q>> {
+    match cell {
+        Cell::Int(v) => query.bind(*v),
+        Cell::Float(v) => query.bind(*v),
+        Cell::Decimal(v) => query.bind(*v),
+        Cell::Bool(v) => query.bind(*v),
+        Cell::Date(v) => query.bind(*v),
+        Cell::DateTime(v) => query.bind(*v),
+        Cell::Time(v) => query.bind(*v),
+        Cell::Json(v) => query.bind(v.clone()),
+        Cell::Uuid(v) => query.bind(*v),
+        Cell::Text(v) => query.bind(v.clone()),
+    }
+}
+
+pub fn bind_sqlite<'q>(
+    query: Query<'q, Sqlite, <Sqlite as Database>::Arguments<'q>>,
+    cell: &Cell,
+) -> Query<'q, Sqlite, <Sqlite as Database>::Arguments<'q>> {
+    match cell {
+        Cell::Int(v) => query.bind(*v),
+        Cell::Float(v) => query.bind(*v),
+        // SQLite 不支持 Decimal，以文本绑定
+        Cell::Decimal(v) => query.bind(v.map(|d| d.to_string())),
+        Cell::Bool(v) => query.bind(*v),
+        Cell::Date(v) => query.bind(*v),
+        Cell::DateTime(v) => query.bind(*v),
+        Cell::Time(v) => query.bind(*v),
+        Cell::Json(v) => query.bind(v.clone()),
+        Cell::Uuid(v) => query.bind(v.map(|u| u.to_string())),
+        Cell::Text(v) => query.bind(v.clone()),
+    }
+}
@@ -0,0 +1,411 @@
+use crate::commands::sqlx::{
+    classify, explorer, get_open_pool, guard_sql, split_statements, DbPool, ScriptDialect, StatementKind,
+    DEFAULT_PROFILE,
+};
+use crate::util::uuid_util;
+use futures_util::TryStreamExt;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::mysql::MySqlConnection;
+use sqlx::postgres::PgConnection;
+use sqlx::sqlite::SqliteConnection;
+use sqlx::{Connection, Either, Executor, Row};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::command;
+use tokio::sync::Mutex;
+
+// 每条查询默认最多返回的行数
+const DEFAULT_MAX_ROWS: usize = 1000;
+// 会话空闲超过这个时间后自动关闭（未提交的事务回滚），避免前端忘记关闭时一直占用连接
+const IDLE_TIMEOUT_SECS: u64 = 30 * 60;
+// 检查空闲会话的间隔
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+// 控制台会话独占的连接，从连接池中取出后脱离连接池
+enum SessionConn {
+    MySql(MySqlConnection),
+    Postgres(PgConnection),
+    Sqlite(SqliteConnection),
+}
+
+struct ConsoleSession {
+    profile: String,
+    // 用于语句分类和权限检查
+    pool: DbPool,
+    conn: SessionConn,
+    in_transaction: bool,
+    created_at: u64,
+    last_used: u64,
+}
+
+// 打开的控制台会话
+static CONSOLE_SESSIONS: Lazy<Mutex<HashMap<String, Arc<Mutex<ConsoleSession>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// 空闲会话清理任务是否已启动
+static REAPER_STARTED: AtomicBool = AtomicBool::new(false);
+
+// 会话信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleInfo {
+    pub id: String,
+    pub profile: String,
+    pub backend: String,
+    pub in_transaction: bool,
+    pub created_at: u64,
+    pub last_used: u64,
+}
+
+// 单条语句的执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementResult {
+    pub index: usize,
+    pub sql: String,
+    pub kind: StatementKind,
+    pub keyword: String,
+    // 有结果集时返回列信息和数据
+    pub columns: Option<Value>,
+    pub rows: Option<Vec<Value>>,
+    // 结果集超过 max_rows 时只返回前 max_rows 行
+    pub truncated: bool,
+    pub rows_affected: u64,
+    pub last_insert_id: Option<i64>,
+    // MySQL 的 SHOW WARNINGS 结果
+    pub warnings: Vec<Value>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+// 脚本执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptResult {
+    pub session: String,
+    pub results: Vec<StatementResult>,
+    // 被跳过的语句数（出错后停止执行时）
+    pub skipped: usize,
+    pub in_transaction: bool,
+    pub elapsed_ms: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+async fn get_session(id: &str) -> Result<Arc<Mutex<ConsoleSession>>, String> {
+    CONSOLE_SESSIONS
+        .lock()
+        .await
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("控制台会话 {} 不存在或已关闭", id))
+}
+
+fn session_info(id: &str, session: &ConsoleSession) -> ConsoleInfo {
+    ConsoleInfo {
+        id: id.to_string(),
+        profile: session.profile.clone(),
+        backend: session.pool.backend().to_string(),
+        in_transaction: session.in_transaction,
+        created_at: session.created_at,
+        last_used: session.last_used,
+    }
+}
+
+/// 在会话连接上执行一条语句，结果集最多读取 max_rows 行
+async fn run_statement(
+    conn: &mut SessionConn,
+    result: &mut StatementResult,
+    max_rows: usize,
+) -> Result<(), sqlx::Error> {
+    let sql = result.sql.as_str();
+    // 使用文本协议执行，存储过程定义等不能预处理的语句也能执行
+    macro_rules! run {
+        ($conn:expr, $to_json:path, $last_id:expr) => {{
+            let mut rows = Vec::new();
+            let mut stream = $conn.fetch_many(sqlx::raw_sql(sql));
+            while let Some(step) = stream.try_next().await? {
+                match step {
+                    Either::Left(done) => {
+                        result.rows_affected += done.rows_affected();
+                        if let Some(id) = ($last_id)(&done) {
+                            result.last_insert_id = Some(id);
+                        }
+                    }
+                    Either::Right(row) => {
+                        if result.columns.is_none() {
+                            result.columns = Some(explorer::column_json(row.columns()));
+                        }
+                        if rows.len() >= max_rows {
+                            // 剩余的行不再读取
+                            result.truncated = true;
+                            break;
+                        }
+                        rows.push($to_json(&row));
+                    }
+                }
+            }
+            drop(stream);
+            // 没有数据的查询也返回列信息
+            if result.columns.is_none() && result.kind == StatementKind::Read {
+                if let Ok(describe) = $conn.describe(sql).await {
+                    result.columns = Some(explorer::column_json(describe.columns()));
+                }
+            }
+            if result.columns.is_some() {
+                result.rows = Some(rows);
+            }
+        }};
+    }
+    match conn {
+        SessionConn::MySql(conn) => {
+            run!(conn, explorer::mysql_row_to_json, |r: &sqlx::mysql::MySqlQueryResult| {
+                (r.last_insert_id() > 0).then(|| r.last_insert_id() as i64)
+            });
+            let warnings = conn.fetch_all(sqlx::raw_sql("SHOW WARNINGS")).await?;
+            result.warnings = warnings.iter().map(explorer::mysql_row_to_json).collect();
+        }
+        SessionConn::Postgres(conn) => {
+            run!(conn, explorer::pg_row_to_json, |_: &sqlx::postgres::PgQueryResult| None)
+        }
+        SessionConn::Sqlite(conn) => {
+            run!(conn, explorer::sqlite_row_to_json, |r: &sqlx::sqlite::SqliteQueryResult| {
+                (r.rows_affected() > 0).then(|| r.last_insert_rowid())
+            })
+        }
+    }
+    Ok(())
+}
+
+// 根据执行的语句推断事务状态
+fn track_transaction(session: &mut ConsoleSession, kind: StatementKind, sql: &str) {
+    let upper = sql.trim_start().to_ascii_uppercase();
+    match kind {
+        StatementKind::Session if upper.starts_with("BEGIN") || upper.starts_with("START TRANSACTION") => {
+            session.in_transaction = true
+        }
+        StatementKind::Session
+            if upper.starts_with("COMMIT") || (upper.starts_with("ROLLBACK") && !upper.contains(" TO ")) =>
+        {
+            session.in_transaction = false
+        }
+        // MySQL 的 DDL 会隐式提交事务
+        StatementKind::Ddl if matches!(session.conn, SessionConn::MySql(_)) => session.in_transaction = false,
+        _ => {}
+    }
+}
+
+// 定期关闭空闲超时的会话，正在执行语句的会话不算空闲
+fn start_reaper() {
+    if REAPER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(REAP_INTERVAL).await;
+            let idle: Vec<String> = CONSOLE_SESSIONS
+                .lock()
+                .await
+                .iter()
+                .filter(|(_, s)| {
+                    s.try_lock()
+                        .is_ok_and(|s| now_secs().saturating_sub(s.last_used) >= IDLE_TIMEOUT_SECS)
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in idle {
+                info!("控制台会话 {} 空闲超时，自动关闭", id);
+                if let Err(e) = close_console(id).await {
+                    warn!("关闭空闲控制台会话失败: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// 打开控制台会话，会话独占一个连接，事务可以跨多次调用
+/// 空闲超过 IDLE_TIMEOUT_SECS 的会话会被自动关闭
+#[command]
+pub async fn open_console(profile: Option<String>) -> Result<ConsoleInfo, String> {
+    let open = get_open_pool(profile.as_deref())
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    let conn = match &open.pool {
+        DbPool::MySql(pool) => SessionConn::MySql(pool.acquire().await.map_err(|e| e.to_string())?.detach()),
+        DbPool::Postgres(pool) => SessionConn::Postgres(pool.acquire().await.map_err(|e| e.to_string())?.detach()),
+        DbPool::Sqlite(pool) => SessionConn::Sqlite(pool.acquire().await.map_err(|e| e.to_string())?.detach()),
+    };
+    let id = uuid_util::new_id();
+    let session = ConsoleSession {
+        profile: profile
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+        pool: open.pool,
+        conn,
+        in_transaction: false,
+        created_at: now_secs(),
+        last_used: now_secs(),
+    };
+    let info = session_info(&id, &session);
+    CONSOLE_SESSIONS
+        .lock()
+        .await
+        .insert(id.clone(), Arc::new(Mutex::new(session)));
+    info!("open console {} ({})", id, info.profile);
+    start_reaper();
+    Ok(info)
+}
+
+/// 关闭控制台会话，未提交的事务会被回滚
+#[command]
+pub async fn close_console(id: String) -> Result<(), String> {
+    let session = CONSOLE_SESSIONS
+        .lock()
+        .await
+        .remove(&id)
+        .ok_or_else(|| format!("控制台会话 {} 不存在或已关闭", id))?;
+    let session = match Arc::try_unwrap(session) {
+        Ok(session) => session.into_inner(),
+        // 仍有语句在执行，连接在执行结束后随会话释放
+        Err(_) => return Ok(()),
+    };
+    let res = match session.conn {
+        SessionConn::MySql(conn) => conn.close().await,
+        SessionConn::Postgres(conn) => conn.close().await,
+        SessionConn::Sqlite(conn) => conn.close().await,
+    };
+    info!("close console {}", id);
+    res.map_err(|e| e.to_string())
+}
+
+/// 列出打开的控制台会话
+#[command]
+pub async fn list_consoles() -> Result<Vec<ConsoleInfo>, String> {
+    let sessions: Vec<(String, Arc<Mutex<ConsoleSession>>)> = CONSOLE_SESSIONS
+        .lock()
+        .await
+        .iter()
+        .map(|(id, s)| (id.clone(), Arc::clone(s)))
+        .collect();
+    let mut list = Vec::with_capacity(sessions.len());
+    for (id, session) in sessions {
+        list.push(session_info(&id, &*session.lock().await));
+    }
+    list.sort_by_key(|s| s.created_at);
+    Ok(list)
+}
+
+/// 在会话中执行 SQL 脚本：按分隔符拆分（支持 DELIMITER）后逐条执行
+/// 危险语句需带上 check_sql 对整段脚本返回的确认令牌；默认遇到错误停止，continue_on_error 为 true 时继续
+#[command]
+pub async fn console_execute(
+    id: String,
+    sql: String,
+    confirm_token: Option<String>,
+    max_rows: Option<usize>,
+    continue_on_error: Option<bool>,
+) -> Result<ScriptResult, String> {
+    let start = Instant::now();
+    let session = get_session(&id).await?;
+    let mut session = session.lock().await;
+    guard_sql(Some(&session.profile), &sql, confirm_token.as_deref()).await?;
+
+    let max_rows = max_rows.unwrap_or(DEFAULT_MAX_ROWS);
+    let continue_on_error = continue_on_error.unwrap_or(false);
+    let statements = split_statements(&sql, ScriptDialect::of(&session.pool));
+    let mut results = Vec::with_capacity(statements.len());
+    let mut skipped = 0;
+    for (index, stmt) in statements.iter().enumerate() {
+        let info = classify(&session.pool, stmt);
+        let (kind, keyword) = info
+            .first()
+            .map(|i| (i.kind, i.keyword.clone()))
+            .unwrap_or((StatementKind::Admin, String::new()));
+        let mut result = StatementResult {
+            index,
+            sql: stmt.clone(),
+            kind,
+            keyword,
+            columns: None,
+            rows: None,
+            truncated: false,
+            rows_affected: 0,
+            last_insert_id: None,
+            warnings: Vec::new(),
+            error: None,
+            elapsed_ms: 0,
+        };
+        let stmt_start = Instant::now();
+        let res = run_statement(&mut session.conn, &mut result, max_rows).await;
+        result.elapsed_ms = stmt_start.elapsed().as_millis() as u64;
+        let failed = res.is_err();
+        match res {
+            Ok(()) => track_transaction(&mut session, kind, stmt),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+        results.push(result);
+        if failed && !continue_on_error {
+            skipped = statements.len() - index - 1;
+            break;
+        }
+    }
+    session.last_used = now_secs();
+    debug!(
+        "console {} executed {} statements, skipped {}",
+        id,
+        results.len(),
+        skipped
+    );
+    Ok(ScriptResult {
+        session: id,
+        results,
+        skipped,
+        in_transaction: session.in_transaction,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+// 执行事务控制语句
+async fn transaction_control(id: &str, sql: &str, begin: bool) -> Result<ConsoleInfo, String> {
+    let session = get_session(id).await?;
+    let mut session = session.lock().await;
+    if begin && session.in_transaction {
+        return Err("会话中已有未结束的事务".to_string());
+    }
+    if !begin && !session.in_transaction {
+        return Err("会话中没有进行中的事务".to_string());
+    }
+    let res = match &mut session.conn {
+        SessionConn::MySql(conn) => conn.execute(sqlx::raw_sql(sql)).await.map(|_| ()),
+        SessionConn::Postgres(conn) => conn.execute(sqlx::raw_sql(sql)).await.map(|_| ()),
+        SessionConn::Sqlite(conn) => conn.execute(sqlx::raw_sql(sql)).await.map(|_| ()),
+    };
+    res.map_err(|e| e.to_string())?;
+    session.in_transaction = begin;
+    session.last_used = now_secs();
+    Ok(session_info(id, &session))
+}
+
+/// 开始事务
+#[command]
+pub async fn console_begin(id: String) -> Result<ConsoleInfo, String> {
+    transaction_control(&id, "BEGIN", true).await
+}
+
+/// 提交事务
+#[command]
+pub async fn console_commit(id: String) -> Result<ConsoleInfo, String> {
+    transaction_control(&id, "COMMIT", false).await
+}
+
+/// 回滚事务
+#[command]
+pub async fn console_rollback(id: String) -> Result<ConsoleInfo, String> {
+    transaction_control(&id, "ROLLBACK", false).await
+}
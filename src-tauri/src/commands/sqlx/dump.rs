@@ -0,0 +1,612 @@
+use crate::commands::sqlx::{
+    check_sql, classify_sql, first_keyword, get_open_pool, guard_sql, DbPool, ScriptDialect, SqlCheck,
+    StatementSplitter,
+};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::TryStreamExt;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::mysql::types::MySqlTime;
+use sqlx::mysql::{MySqlConnection, MySqlPool, MySqlRow};
+use sqlx::types::Decimal;
+use sqlx::{Column, Connection, Executor, Row, TypeInfo, ValueRef};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+// 每条 INSERT 语句包含的行数
+const INSERT_BATCH_ROWS: usize = 500;
+// 恢复时每执行多少条语句上报一次进度
+const RESTORE_PROGRESS_EVERY: u64 = 200;
+// 读写线程与数据库之间缓冲的最大块数，文件读写跟不上时等待
+const DUMP_CHANNEL_CHUNKS: usize = 64;
+
+// 备份进度
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpProgress {
+    pub dbname: String,
+    pub table: String,
+    pub table_index: usize,
+    pub table_count: usize,
+    pub rows: u64,
+}
+
+// 备份结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpSummary {
+    pub filename: String,
+    pub tables: usize,
+    pub rows: u64,
+    pub elapsed_ms: u64,
+}
+
+// 可能删除数据的语句开头，只有这些语句需要交给 classify_sql 判断
+const DESTRUCTIVE_KEYWORDS: &[&str] = &["DROP", "TRUNCATE", "ALTER", "DELETE", "UPDATE", "WITH", ""];
+
+// check_restore 检查过的备份文件，revert_db_command 使用时文件未变化则不再重新检查
+struct RestoreCheck {
+    len: u64,
+    modified: Option<SystemTime>,
+    confirm_sql: Option<String>,
+}
+
+static RESTORE_CHECKS: Lazy<Mutex<HashMap<(String, String), RestoreCheck>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 恢复进度，total_bytes 为文件大小（压缩文件为压缩后的大小）
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreProgress {
+    pub dbname: String,
+    pub statements: u64,
+    pub bytes: u64,
+    pub total_bytes: u64,
+}
+
+// 取连接配置对应的 MySQL 连接池，备份恢复只支持 MySQL
+async fn mysql_pool(profile: Option<&str>, write: bool) -> Result<MySqlPool, String> {
+    let open = get_open_pool(profile)
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    if write && open.read_only {
+        return Err("只读连接不能恢复数据库".to_string());
+    }
+    match open.pool {
+        DbPool::MySql(pool) => Ok(pool),
+        other => Err(format!("{} 暂不支持备份恢复", other.backend())),
+    }
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+// 备份文件写入线程的发送端，写入出错退出后发送失败，错误由 join 返回
+struct DumpWriter {
+    tx: mpsc::Sender<String>,
+}
+
+impl DumpWriter {
+    async fn write(&self, s: String) -> Result<(), String> {
+        self.tx.send(s).await.map_err(|_| "备份文件写入已中止".to_string())
+    }
+}
+
+// 在阻塞线程中创建并写入备份文件
+fn spawn_dump_writer(
+    filename: &str,
+    gzip: bool,
+) -> (DumpWriter, tauri::async_runtime::JoinHandle<Result<(), String>>) {
+    let (tx, mut rx) = mpsc::channel::<String>(DUMP_CHANNEL_CHUNKS);
+    let filename = filename.to_string();
+    let handle = tauri::async_runtime::spawn_blocking(move || {
+        let file = File::create(&filename).map_err(|e| e.to_string())?;
+        let mut out: Box<dyn Write + Send> = if gzip {
+            Box::new(GzEncoder::new(BufWriter::new(file), Compression::default()))
+        } else {
+            Box::new(BufWriter::new(file))
+        };
+        while let Some(chunk) = rx.blocking_recv() {
+            out.write_all(chunk.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        out.flush().map_err(|e| e.to_string())
+    });
+    (DumpWriter { tx }, handle)
+}
+
+/// 备份数据库到文件：表结构来自 SHOW CREATE TABLE，数据为批量 INSERT
+/// progress 返回错误时中止备份（用于取消），中止或失败时删除未写完的文件
+pub async fn run_backup<F>(
+    profile: Option<&str>,
+    dbname: &str,
+    filename: &str,
+    gzip: bool,
+    mut progress: F,
+) -> Result<DumpSummary, String>
+where
+    F: FnMut(&DumpProgress) -> Result<(), String>,
+{
+    let start = Instant::now();
+    let pool = mysql_pool(profile, false).await?;
+    let (out, writer) = spawn_dump_writer(filename, gzip);
+    let dumped = dump_tables(&pool, dbname, &out, &mut progress).await;
+    // 关闭通道后等待写入线程把剩余内容写完
+    drop(out);
+    let written = writer.await.map_err(|e| e.to_string()).and_then(|r| r);
+    let (tables, rows) = match (dumped, written) {
+        (Ok(counts), Ok(())) => counts,
+        // 写入出错时读取端只会得到通道关闭的错误，以写入线程的错误为准
+        (_, Err(e)) | (Err(e), Ok(())) => {
+            if Path::new(filename).exists() {
+                if let Err(remove_err) = std::fs::remove_file(filename) {
+                    eprintln!("删除未完成的备份文件失败 {}: {}", filename, remove_err);
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    Ok(DumpSummary {
+        filename: filename.to_string(),
+        tables,
+        rows,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+// 在同一个连接上读取表结构和数据，返回表数量和总行数
+async fn dump_tables<F>(
+    pool: &MySqlPool,
+    dbname: &str,
+    out: &DumpWriter,
+    progress: &mut F,
+) -> Result<(usize, u64), String>
+where
+    F: FnMut(&DumpProgress) -> Result<(), String>,
+{
+    let db = quote_ident(dbname);
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    // 备份文件头声明了 UTC，TIMESTAMP 按会话时区返回，读取前显式设置为 UTC
+    conn.execute("SET time_zone = '+00:00'")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tables: Vec<(String, String)> = sqlx::query(&format!("SHOW FULL TABLES FROM {}", db))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|r| (r.get::<String, _>(0), r.get::<String, _>(1)))
+        .collect();
+    // 先导出表，视图依赖表，放在最后
+    let (base_tables, views): (Vec<_>, Vec<_>) =
+        tables.into_iter().partition(|(_, kind)| kind != "VIEW");
+
+    out.write(format!(
+        "-- draft dump of {}\n-- {}\n\nSET NAMES utf8mb4;\nSET TIME_ZONE = '+00:00';\nSET FOREIGN_KEY_CHECKS = 0;\n\n",
+        dbname,
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    ))
+    .await?;
+
+    let table_count = base_tables.len() + views.len();
+    let mut total_rows: u64 = 0;
+    for (index, (table, _)) in base_tables.iter().enumerate() {
+        // 每张表开始前上报一次，取消时不再读取下一张表
+        let mut report = DumpProgress {
+            dbname: dbname.to_string(),
+            table: table.clone(),
+            table_index: index + 1,
+            table_count,
+            rows: 0,
+        };
+        progress(&report)?;
+
+        let full_name = format!("{}.{}", db, quote_ident(table));
+        let ddl: String = sqlx::query(&format!("SHOW CREATE TABLE {}", full_name))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .get(1);
+        out.write(format!(
+            "-- table {}\nDROP TABLE IF EXISTS {};\n{};\n\n",
+            table,
+            quote_ident(table),
+            ddl
+        ))
+        .await?;
+
+        let select = format!("SELECT * FROM {}", full_name);
+        let mut rows = sqlx::query(&select).fetch(&mut *conn);
+        let mut values: Vec<String> = Vec::with_capacity(INSERT_BATCH_ROWS);
+        while let Some(row) = rows.try_next().await.map_err(|e| e.to_string())? {
+            values.push(row_literal(&row)?);
+            if values.len() >= INSERT_BATCH_ROWS {
+                out.write(insert_sql(table, &values)).await?;
+                report.rows += values.len() as u64;
+                values.clear();
+                progress(&report)?;
+            }
+        }
+        drop(rows);
+        if !values.is_empty() {
+            out.write(insert_sql(table, &values)).await?;
+            report.rows += values.len() as u64;
+            progress(&report)?;
+        }
+        total_rows += report.rows;
+        out.write("\n".to_string()).await?;
+    }
+
+    for (index, (view, _)) in views.iter().enumerate() {
+        progress(&DumpProgress {
+            dbname: dbname.to_string(),
+            table: view.clone(),
+            table_index: base_tables.len() + index + 1,
+            table_count,
+            rows: 0,
+        })?;
+        let ddl: String = sqlx::query(&format!("SHOW CREATE VIEW {}.{}", db, quote_ident(view)))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .get(1);
+        out.write(format!(
+            "-- view {}\nDROP VIEW IF EXISTS {};\n{};\n\n",
+            view,
+            quote_ident(view),
+            ddl
+        ))
+        .await?;
+    }
+
+    out.write("SET FOREIGN_KEY_CHECKS = 1;\n".to_string()).await?;
+    Ok((table_count, total_rows))
+}
+
+fn insert_sql(table: &str, values: &[String]) -> String {
+    format!("INSERT INTO {} VALUES\n{};\n", quote_ident(table), values.join(",\n"))
+}
+// 一行数据转为 INSERT 的 VALUES 元组
+fn row_literal(row: &MySqlRow) -> Result<String, String> {
+    let mut parts = Vec::with_capacity(row.columns().len());
+    for col in row.columns() {
+        let i = col.ordinal();
+        let is_null = row.try_get_raw(i).map(|v| v.is_null()).unwrap_or(true);
+        let literal = if is_null {
+            "NULL".to_string()
+        } else {
+            value_literal(row, i, col.type_info().name())
+                .map_err(|e| format!("{} 列 {} 导出失败: {}", col.type_info().name(), col.name(), e))?
+        };
+        parts.push(literal);
+    }
+    Ok(format!("({})", parts.join(",")))
+}
+
+fn value_literal(row: &MySqlRow, i: usize, type_name: &str) -> Result<String, sqlx::Error> {
+    let literal = match type_name {
+        "BOOLEAN" => (row.try_get::<bool, _>(i)? as u8).to_string(),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => row.try_get::<i64, _>(i)?.to_string(),
+        "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED"
+        | "BIGINT UNSIGNED" => row.try_get::<u64, _>(i)?.to_string(),
+        "YEAR" => row.try_get_unchecked::<u16, _>(i)?.to_string(),
+        "BIT" => row.try_get_unchecked::<u64, _>(i)?.to_string(),
+        "FLOAT" => row.try_get::<f32, _>(i)?.to_string(),
+        "DOUBLE" => row.try_get::<f64, _>(i)?.to_string(),
+        "DECIMAL" => row.try_get::<Decimal, _>(i)?.to_string(),
+        "DATE" => quote_str(&row.try_get::<NaiveDate, _>(i)?.to_string()),
+        "TIME" => quote_str(&row.try_get::<MySqlTime, _>(i)?.to_string()),
+        "DATETIME" | "TIMESTAMP" => quote_str(&row.try_get::<NaiveDateTime, _>(i)?.to_string()),
+        "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" => {
+            quote_str(&row.try_get::<String, _>(i)?)
+        }
+        "SET" | "JSON" => quote_str(&row.try_get_unchecked::<String, _>(i)?),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => {
+            hex_literal(&row.try_get_unchecked::<Vec<u8>, _>(i)?)
+        }
+        _ => quote_str(&String::from_utf8_lossy(&row.try_get_unchecked::<Vec<u8>, _>(i)?)),
+    };
+    Ok(literal)
+}
+
+// 字符串字面量转义
+fn quote_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\0' => out.push_str("\\0"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\x1a' => out.push_str("\\Z"),
+            _ => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+fn hex_literal(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "''".to_string();
+    }
+    let mut out = String::with_capacity(bytes.len() * 2 + 2);
+    out.push_str("0x");
+    for b in bytes {
+        out.push_str(&format!("{:02X}", b));
+    }
+    out
+}
+
+// 统计已读取的字节数，用于计算恢复进度
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+// 在阻塞线程中读取备份文件并拆分语句，each 收到语句和已读取的字节数，返回 false 时停止读取
+fn read_dump<F>(filename: &str, mut each: F) -> Result<(), String>
+where
+    F: FnMut(String, u64) -> bool,
+{
+    let path = Path::new(filename);
+    let mut magic = [0u8; 2];
+    let is_gzip = File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map(|_| magic == [0x1f, 0x8b])
+        .unwrap_or(false);
+
+    let count = Arc::new(AtomicU64::new(0));
+    let file = CountingReader {
+        inner: File::open(path).map_err(|e| e.to_string())?,
+        count: Arc::clone(&count),
+    };
+    let reader: Box<dyn BufRead + Send> = if is_gzip {
+        Box::new(BufReader::new(GzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    let mut splitter = StatementSplitter::new(ScriptDialect::MySql);
+    let mut lines = reader.lines();
+    loop {
+        let (statements, eof) = match lines.next() {
+            Some(line) => (splitter.push_line(&line.map_err(|e| e.to_string())?), false),
+            None => (splitter.finish().into_iter().collect(), true),
+        };
+        let bytes = count.load(Ordering::Relaxed);
+        for stmt in statements {
+            if !each(stmt, bytes) {
+                return Ok(());
+            }
+        }
+        if eof {
+            return Ok(());
+        }
+    }
+}
+
+// 备份文件中会删除数据的语句（DROP TABLE 等），连同目标库组成确认用的 SQL，没有时为 None
+// 先按开头的关键字筛选，INSERT 等语句不做解析
+async fn scan_confirm_sql(dbname: &str, filename: &str) -> Result<Option<String>, String> {
+    let path = filename.to_string();
+    let statements = tauri::async_runtime::spawn_blocking(move || {
+        let mut statements = Vec::new();
+        read_dump(&path, |stmt, _| {
+            let candidate = DESTRUCTIVE_KEYWORDS.contains(&first_keyword(&stmt).as_str());
+            if candidate && classify_sql(ScriptDialect::MySql, &stmt).iter().any(|s| s.destructive) {
+                statements.push(stmt);
+            }
+            true
+        })?;
+        Ok::<_, String>(statements)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if statements.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "-- restore {} from {}\n{};",
+        quote_ident(dbname),
+        filename,
+        statements.join(";\n")
+    )))
+}
+
+fn file_stamp(filename: &str) -> Result<(u64, Option<SystemTime>), String> {
+    let meta = std::fs::metadata(filename).map_err(|e| e.to_string())?;
+    Ok((meta.len(), meta.modified().ok()))
+}
+
+// 检查备份文件并记录结果，供随后的恢复使用
+async fn check_restore_file(dbname: &str, filename: &str) -> Result<Option<String>, String> {
+    let (len, modified) = file_stamp(filename)?;
+    let confirm_sql = scan_confirm_sql(dbname, filename).await?;
+    RESTORE_CHECKS.lock().unwrap().insert(
+        (dbname.to_string(), filename.to_string()),
+        RestoreCheck {
+            len,
+            modified,
+            confirm_sql: confirm_sql.clone(),
+        },
+    );
+    Ok(confirm_sql)
+}
+
+// 恢复时使用 check_restore 记录的结果，没有记录或文件已变化时重新检查
+async fn restore_confirm_sql(dbname: &str, filename: &str) -> Result<Option<String>, String> {
+    let stamp = file_stamp(filename)?;
+    let checked = RESTORE_CHECKS
+        .lock()
+        .unwrap()
+        .remove(&(dbname.to_string(), filename.to_string()));
+    match checked {
+        Some(check) if (check.len, check.modified) == stamp => Ok(check.confirm_sql),
+        _ => scan_confirm_sql(dbname, filename).await,
+    }
+}
+
+/// 从备份文件恢复数据库，自动识别 gzip 文件
+/// 所有语句在同一个独立的连接上执行，保证 SET 等会话设置生效；
+/// 该连接的会话状态（USE、FOREIGN_KEY_CHECKS 等）已被修改，结束后关闭而不放回连接池
+pub async fn run_restore<F>(
+    profile: Option<&str>,
+    dbname: &str,
+    filename: &str,
+    mut progress: F,
+) -> Result<u64, String>
+where
+    F: FnMut(&RestoreProgress) -> Result<(), String>,
+{
+    let pool = mysql_pool(profile, true).await?;
+    let total_bytes = std::fs::metadata(filename).map_err(|e| e.to_string())?.len();
+
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?.detach();
+    let result = restore_on(&mut conn, dbname, filename, total_bytes, &mut progress).await;
+    if let Err(e) = conn.close().await {
+        warn!("关闭恢复连接失败: {}", e);
+    }
+    result
+}
+
+async fn restore_on<F>(
+    conn: &mut MySqlConnection,
+    dbname: &str,
+    filename: &str,
+    total_bytes: u64,
+    progress: &mut F,
+) -> Result<u64, String>
+where
+    F: FnMut(&RestoreProgress) -> Result<(), String>,
+{
+    conn.execute(format!("USE {}", quote_ident(dbname)).as_str())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 文件读取和解压在阻塞线程中进行，执行跟不上时读取等待
+    let (tx, mut rx) = mpsc::channel::<(String, u64)>(DUMP_CHANNEL_CHUNKS);
+    let path = filename.to_string();
+    let reader = tauri::async_runtime::spawn_blocking(move || {
+        read_dump(&path, |stmt, bytes| tx.blocking_send((stmt, bytes)).is_ok())
+    });
+
+    let mut executed: u64 = 0;
+    let mut report = RestoreProgress {
+        dbname: dbname.to_string(),
+        statements: 0,
+        bytes: 0,
+        total_bytes,
+    };
+    // 返回时 rx 被丢弃，读取线程在下一次发送时停止
+    while let Some((stmt, bytes)) = rx.recv().await {
+        conn.execute(stmt.as_str())
+            .await
+            .map_err(|e| format!("第 {} 条语句执行失败: {}", executed + 1, e))?;
+        executed += 1;
+        report.bytes = bytes;
+        if executed % RESTORE_PROGRESS_EVERY == 0 {
+            report.statements = executed;
+            progress(&report)?;
+        }
+    }
+    reader.await.map_err(|e| e.to_string())??;
+    report.statements = executed;
+    report.bytes = total_bytes;
+    progress(&report)?;
+    Ok(executed)
+}
+
+/// 备份数据库（不依赖 mysqldump），进度通过 backup_progress 事件推送
+/// gzip 为空时根据文件名是否以 .gz 结尾判断
+#[command]
+pub async fn backup_db_command(
+    app_handle: AppHandle,
+    dbname: &str,
+    filename: &str,
+    gzip: Option<bool>,
+    profile: Option<String>,
+) -> Result<String, String> {
+    let gzip = gzip.unwrap_or_else(|| filename.ends_with(".gz"));
+    let summary = run_backup(profile.as_deref(), dbname, filename, gzip, |p| {
+        app_handle.emit("backup_progress", p).map_err(|e| e.to_string())
+    })
+    .await?;
+    info!("Backup successful {:?}", summary);
+    serde_json::to_string(&summary).map_err(|e| e.to_string())
+}
+
+/// 恢复前检查备份文件：包含 DROP TABLE 等语句时返回确认令牌，恢复时需带上
+#[command]
+pub async fn check_restore(dbname: &str, filename: &str, profile: Option<String>) -> Result<SqlCheck, String> {
+    let sql = check_restore_file(dbname, filename)
+        .await?
+        .unwrap_or_default();
+    check_sql(sql, profile).await
+}
+
+/// 从备份文件恢复数据库（不依赖 mysql 客户端），进度通过 restore_progress 事件推送
+/// 备份文件会删除已有的表，需要先调用 check_restore 获取确认令牌
+#[command]
+pub async fn revert_db_command(
+    app_handle: AppHandle,
+    dbname: &str,
+    filename: &str,
+    profile: Option<String>,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    if let Some(sql) = restore_confirm_sql(dbname, filename).await? {
+        guard_sql(profile.as_deref(), &sql, confirm_token.as_deref()).await?;
+    }
+    let executed = run_restore(profile.as_deref(), dbname, filename, |p| {
+        app_handle.emit("restore_progress", p).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Failed to Revert DB: {}", e))?;
+    info!("Revert DB {} statements", executed);
+    Ok("ok".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_statements_from_gzip_dump() {
+        let path = std::env::temp_dir().join(format!("draft_dump_{}.sql.gz", std::process::id()));
+        let mut out = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        out.write_all(b"-- dump\nDROP TABLE IF EXISTS `a`;\nINSERT INTO `a` VALUES\n(1,'x;y');\n").unwrap();
+        out.finish().unwrap();
+
+        let mut statements = Vec::new();
+        read_dump(path.to_str().unwrap(), |stmt, _| {
+            statements.push(stmt);
+            true
+        })
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(statements.len(), 2);
+        assert!(statements[1].contains("'x;y'"));
+        assert!(classify_sql(ScriptDialect::MySql, &statements[0])[0].destructive);
+    }
+}
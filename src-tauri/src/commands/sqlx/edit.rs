@@ -0,0 +1,308 @@
+use crate::commands::sqlx::cell::{self, coerce_json, column_kind, Cell};
+use crate::commands::sqlx::{describe, explorer, get_open_pool, ColumnInfo, DbPool};
+use log::debug;
+use serde_json::{Map, Value};
+use tauri::command;
+
+// 按主键编辑的目标表
+struct EditTable {
+    pool: DbPool,
+    // 带库名并转义的表名
+    table: String,
+    columns: Vec<ColumnInfo>,
+    primary: Vec<String>,
+}
+
+impl EditTable {
+    fn quote(&self, name: &str) -> String {
+        explorer::quote_ident(&self.pool, name)
+    }
+
+    fn placeholder(&self, n: usize) -> String {
+        cell::placeholder(matches!(self.pool, DbPool::Postgres(_)), n)
+    }
+
+    // 主键条件，占位符从 start 开始编号
+    fn key_where(&self, start: usize) -> String {
+        self.primary
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{} = {}", self.quote(c), self.placeholder(start + i)))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    fn select_sql(&self) -> String {
+        format!("SELECT * FROM {} WHERE {}", self.table, self.key_where(1))
+    }
+
+    // 修改前锁定该行，SQLite 写事务本身锁整个库
+    fn select_for_update_sql(&self) -> String {
+        match self.pool {
+            DbPool::Sqlite(_) => self.select_sql(),
+            _ => format!("{} FOR UPDATE", self.select_sql()),
+        }
+    }
+
+    /// 按字段类型转换前端传来的值
+    fn typed(&self, values: &Map<String, Value>) -> Result<Vec<(String, Cell)>, String> {
+        values
+            .iter()
+            .map(|(name, value)| {
+                let column = self
+                    .columns
+                    .iter()
+                    .find(|c| &c.name == name)
+                    .ok_or_else(|| format!("表中没有字段 {}", name))?;
+                let cell = coerce_json(value, column_kind(&column.data_type))
+                    .map_err(|e| format!("字段 {}: {}", name, e))?;
+                Ok((name.clone(), cell))
+            })
+            .collect()
+    }
+
+    /// 主键值，按主键字段的顺序排列，必须给出全部主键字段
+    fn typed_key(&self, key: &Map<String, Value>) -> Result<Vec<Cell>, String> {
+        if let Some(extra) = key.keys().find(|k| !self.primary.contains(k)) {
+            return Err(format!("{} 不是主键字段", extra));
+        }
+        let mut typed = self.typed(key)?;
+        self.primary
+            .iter()
+            .map(|name| {
+                let i = typed
+                    .iter()
+                    .position(|(n, _)| n == name)
+                    .ok_or_else(|| format!("缺少主键字段 {}", name))?;
+                Ok(typed.swap_remove(i).1)
+            })
+            .collect()
+    }
+}
+
+async fn edit_table(profile: Option<&str>, db_name: &str, table_name: &str) -> Result<EditTable, String> {
+    let open = get_open_pool(profile)
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    if open.read_only {
+        return Err("只读连接不能修改数据".to_string());
+    }
+    let schema = describe(&open.pool, db_name, table_name, false).await?;
+    let primary: Vec<String> = match schema.indexes.iter().find(|i| i.primary) {
+        Some(index) => index.columns.clone(),
+        None => schema
+            .columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| c.name.clone())
+            .collect(),
+    };
+    if primary.is_empty() {
+        return Err(format!("表 {} 没有主键，不能按行编辑", table_name));
+    }
+    Ok(EditTable {
+        table: explorer::qualified_table(&open.pool, db_name, table_name).await?,
+        pool: open.pool,
+        columns: schema.columns,
+        primary,
+    })
+}
+
+macro_rules! bind_all {
+    ($query:expr, $bind:path, $cells:expr) => {{
+        let mut query = $query;
+        for cell in $cells {
+            query = $bind(query, cell);
+        }
+        query
+    }};
+}
+
+/// 插入一行，返回插入后的整行（含数据库生成的默认值和自增主键）
+/// MySQL 中主键既没有给出也不是自增字段时无法定位新行，返回 null
+#[command]
+pub async fn insert_table_row(
+    db_name: &str,
+    table_name: &str,
+    values: Map<String, Value>,
+    profile: Option<String>,
+) -> Result<Value, String> {
+    let t = edit_table(profile.as_deref(), db_name, table_name).await?;
+    let cells = t.typed(&values)?;
+    let sql = if cells.is_empty() {
+        match t.pool {
+            DbPool::MySql(_) => format!("INSERT INTO {} () VALUES ()", t.table),
+            _ => format!("INSERT INTO {} DEFAULT VALUES", t.table),
+        }
+    } else {
+        format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            t.table,
+            cells.iter().map(|(n, _)| t.quote(n)).collect::<Vec<_>>().join(", "),
+            (1..=cells.len()).map(|n| t.placeholder(n)).collect::<Vec<_>>().join(", ")
+        )
+    };
+    debug!("insert row: {}", sql);
+    let returning_sql = format!("{} RETURNING *", sql);
+    let select_sql = t.select_sql();
+
+    // PostgreSQL 和 SQLite 用 RETURNING 直接取回新行
+    macro_rules! insert_returning {
+        ($pool:expr, $bind:path, $to_json:path) => {{
+            let mut tx = $pool.begin().await.map_err(|e| e.to_string())?;
+            let row = bind_all!(sqlx::query(&returning_sql), $bind, cells.iter().map(|(_, c)| c))
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            tx.commit().await.map_err(|e| e.to_string())?;
+            Ok($to_json(&row))
+        }};
+    }
+    match &t.pool {
+        DbPool::MySql(pool) => {
+            let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+            let res = bind_all!(sqlx::query(&sql), cell::bind_mysql, cells.iter().map(|(_, c)| c))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            // 没有给出的主键只能是自增字段
+            let mut key = Vec::with_capacity(t.primary.len());
+            for name in &t.primary {
+                match cells.iter().find(|(n, _)| n == name) {
+                    Some((_, cell)) => key.push(cell.clone()),
+                    None if t.columns.iter().any(|c| &c.name == name && c.auto_increment) => {
+                        key.push(Cell::Int(Some(res.last_insert_id() as i64)))
+                    }
+                    None => break,
+                }
+            }
+            let row = if key.len() == t.primary.len() {
+                bind_all!(sqlx::query(&select_sql), cell::bind_mysql, key.iter())
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map(|r| explorer::mysql_row_to_json(&r))
+            } else {
+                None
+            };
+            tx.commit().await.map_err(|e| e.to_string())?;
+            Ok(row.unwrap_or(Value::Null))
+        }
+        DbPool::Postgres(pool) => insert_returning!(pool, cell::bind_pg, explorer::pg_row_to_json),
+        DbPool::Sqlite(pool) => insert_returning!(pool, cell::bind_sqlite, explorer::sqlite_row_to_json),
+    }
+}
+
+/// 按主键修改一行，key 为原主键值，返回修改后的整行
+#[command]
+pub async fn update_table_row(
+    db_name: &str,
+    table_name: &str,
+    key: Map<String, Value>,
+    values: Map<String, Value>,
+    profile: Option<String>,
+) -> Result<Value, String> {
+    let t = edit_table(profile.as_deref(), db_name, table_name).await?;
+    let key_cells = t.typed_key(&key)?;
+    let cells = t.typed(&values)?;
+    if cells.is_empty() {
+        return Err("没有需要修改的字段".to_string());
+    }
+    // 修改了主键时按新主键读取修改后的行
+    let new_key: Vec<Cell> = t
+        .primary
+        .iter()
+        .zip(&key_cells)
+        .map(|(name, old)| {
+            cells
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or_else(|| old.clone(), |(_, c)| c.clone())
+        })
+        .collect();
+    let sql = format!(
+        "UPDATE {} SET {} WHERE {}",
+        t.table,
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, (n, _))| format!("{} = {}", t.quote(n), t.placeholder(i + 1)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        t.key_where(cells.len() + 1)
+    );
+    debug!("update row: {}", sql);
+    let lock_sql = t.select_for_update_sql();
+    let select_sql = t.select_sql();
+
+    macro_rules! update {
+        ($pool:expr, $bind:path, $to_json:path) => {{
+            let mut tx = $pool.begin().await.map_err(|e| e.to_string())?;
+            bind_all!(sqlx::query(&lock_sql), $bind, key_cells.iter())
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "记录不存在或已被删除".to_string())?;
+            let res = bind_all!(sqlx::query(&sql), $bind, cells.iter().map(|(_, c)| c).chain(key_cells.iter()))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            if res.rows_affected() > 1 {
+                // 事务未提交，离开作用域时回滚
+                return Err(format!("主键条件匹配了 {} 行，已取消修改", res.rows_affected()));
+            }
+            let row = bind_all!(sqlx::query(&select_sql), $bind, new_key.iter())
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "修改后读取记录失败".to_string())?;
+            tx.commit().await.map_err(|e| e.to_string())?;
+            Ok($to_json(&row))
+        }};
+    }
+    match &t.pool {
+        DbPool::MySql(pool) => update!(pool, cell::bind_mysql, explorer::mysql_row_to_json),
+        DbPool::Postgres(pool) => update!(pool, cell::bind_pg, explorer::pg_row_to_json),
+        DbPool::Sqlite(pool) => update!(pool, cell::bind_sqlite, explorer::sqlite_row_to_json),
+    }
+}
+
+/// 按主键删除一行，返回被删除的行
+#[command]
+pub async fn delete_table_row(
+    db_name: &str,
+    table_name: &str,
+    key: Map<String, Value>,
+    profile: Option<String>,
+) -> Result<Value, String> {
+    let t = edit_table(profile.as_deref(), db_name, table_name).await?;
+    let key_cells = t.typed_key(&key)?;
+    let sql = format!("DELETE FROM {} WHERE {}", t.table, t.key_where(1));
+    debug!("delete row: {}", sql);
+    let lock_sql = t.select_for_update_sql();
+
+    macro_rules! delete {
+        ($pool:expr, $bind:path, $to_json:path) => {{
+            let mut tx = $pool.begin().await.map_err(|e| e.to_string())?;
+            let row = bind_all!(sqlx::query(&lock_sql), $bind, key_cells.iter())
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "记录不存在或已被删除".to_string())?;
+            let res = bind_all!(sqlx::query(&sql), $bind, key_cells.iter())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            if res.rows_affected() != 1 {
+                return Err(format!("主键条件匹配了 {} 行，已取消删除", res.rows_affected()));
+            }
+            tx.commit().await.map_err(|e| e.to_string())?;
+            Ok($to_json(&row))
+        }};
+    }
+    match &t.pool {
+        DbPool::MySql(pool) => delete!(pool, cell::bind_mysql, explorer::mysql_row_to_json),
+        DbPool::Postgres(pool) => delete!(pool, cell::bind_pg, explorer::pg_row_to_json),
+        DbPool::Sqlite(pool) => delete!(pool, cell::bind_sqlite, explorer::sqlite_row_to_json),
+    }
+}
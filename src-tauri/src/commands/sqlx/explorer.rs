@@ -0,0 +1,445 @@
+use crate::commands::sqlx::DbPool;
+use crate::dto::StringDB;
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde_json::{json, Value};
+use sqlx::mysql::types::MySqlTime;
+use sqlx::mysql::MySqlRow;
+use sqlx::postgres::PgRow;
+use sqlx::sqlite::SqliteRow;
+use sqlx::types::{Decimal, Json, Uuid};
+use sqlx::{Column, Executor, Row, Statement, TypeInfo, ValueRef};
+
+// 二进制数据不超过该大小时以 base64 返回，否则只返回大小
+const MAX_INLINE_BYTES: usize = 64 * 1024;
+
+// 数据库浏览器对 PostgreSQL / SQLite 的实现，以及各后端共用的结果集解码
+// MySQL 的库表查询沿用 commands::sqlx 中原有的实现
+
+/// 列出所有数据库
+pub async fn list_databases(pool: &DbPool) -> Result<Vec<StringDB>, String> {
+    match pool {
+        DbPool::Postgres(pool) => sqlx::query_as::<_, StringDB>(
+            "SELECT datname AS name FROM pg_database WHERE NOT datistemplate ORDER BY datname",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string()),
+        // SQLite 只有一个主库
+        DbPool::Sqlite(_) => Ok(vec![StringDB::new("main".to_string())]),
+        DbPool::MySql(_) => Err("MySQL 请使用原有实现".to_string()),
+    }
+}
+
+/// 列出当前数据库下的模式
+pub async fn list_schemas(pool: &DbPool, dbname: &str) -> Result<Vec<StringDB>, String> {
+    match pool {
+        DbPool::Postgres(pool) => sqlx::query_as::<_, StringDB>(
+            "SELECT schema_name::text AS name FROM information_schema.schemata
+             WHERE schema_name NOT LIKE 'pg\\_%' AND schema_name <> 'information_schema'
+             ORDER BY schema_name",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string()),
+        // MySQL 中数据库即模式
+        DbPool::MySql(_) | DbPool::Sqlite(_) => Ok(vec![StringDB::new(dbname.to_string())]),
+    }
+}
+
+// PostgreSQL 下 dbname 参数可以是 "库.模式" 或模式名，与当前库同名时使用 public
+pub async fn pg_schema(pool: &sqlx::PgPool, dbname: &str) -> Result<String, String> {
+    if let Some((_, schema)) = dbname.split_once('.') {
+        return Ok(schema.to_string());
+    }
+    let current: String = sqlx::query_scalar("SELECT current_database()::text")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if dbname.is_empty() || dbname == current {
+        Ok("public".to_string())
+    } else {
+        Ok(dbname.to_string())
+    }
+}
+
+/// 按后端转义标识符：MySQL 用反引号，PostgreSQL / SQLite 用双引号
+pub fn quote_ident(pool: &DbPool, name: &str) -> String {
+    match pool {
+        DbPool::MySql(_) => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// 字符串字面量，MySQL 默认把反斜杠作为转义符，PostgreSQL / SQLite 只需要双写单引号
+pub fn quote_str(pool: &DbPool, s: &str) -> String {
+    let escaped = match pool {
+        DbPool::MySql(_) => s.replace('\\', "\\\\").replace('\'', "''"),
+        _ => s.replace('\'', "''"),
+    };
+    format!("'{}'", escaped)
+}
+
+/// 带库名（PostgreSQL 为模式名）的表名，SQLite 只有表名
+pub async fn qualified_table(pool: &DbPool, dbname: &str, table_name: &str) -> Result<String, String> {
+    let qualifier = match pool {
+        DbPool::MySql(_) => dbname.to_string(),
+        DbPool::Postgres(pg) => pg_schema(pg, dbname).await?,
+        DbPool::Sqlite(_) => return Ok(quote_ident(pool, table_name)),
+    };
+    Ok(format!("{}.{}", quote_ident(pool, &qualifier), quote_ident(pool, table_name)))
+}
+
+/// 列出表
+pub async fn list_tables(pool: &DbPool, dbname: &str) -> Result<Vec<StringDB>, String> {
+    match pool {
+        DbPool::Postgres(pool) => {
+            let schema = pg_schema(pool, dbname).await?;
+            sqlx::query_as::<_, StringDB>(
+                "SELECT table_name::text AS name FROM information_schema.tables
+                 WHERE table_schema = $1 ORDER BY table_name",
+            )
+            .bind(schema)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())
+        }
+        DbPool::Sqlite(pool) => sqlx::query_as::<_, StringDB>(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string()),
+        DbPool::MySql(pool) => sqlx::query_as::<_, StringDB>(
+            "SELECT table_name AS `name` FROM information_schema.tables WHERE table_schema = ? ORDER BY table_name",
+        )
+        .bind(dbname)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string()),
+    }
+}
+
+/// 列出表的字段及类型（按字段顺序）
+pub async fn list_columns(
+    pool: &DbPool,
+    dbname: &str,
+    table_name: &str,
+) -> Result<Vec<(String, String)>, String> {
+    match pool {
+        DbPool::Postgres(pool) => {
+            let schema = pg_schema(pool, dbname).await?;
+            let rows = sqlx::query(
+                "SELECT column_name::text AS name, data_type::text AS type FROM information_schema.columns
+                 WHERE table_schema = $1 AND table_name = $2 ORDER BY ordinal_position",
+            )
+            .bind(schema)
+            .bind(table_name)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            Ok(rows
+                .iter()
+                .map(|r| (r.get::<String, _>("name"), r.get::<String, _>("type")))
+                .collect())
+        }
+        DbPool::Sqlite(pool) => {
+            let rows = sqlx::query("SELECT name, type FROM pragma_table_info(?) ORDER BY cid")
+                .bind(table_name)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(rows
+                .iter()
+                .map(|r| (r.get::<String, _>("name"), r.get::<String, _>("type")))
+                .collect())
+        }
+        DbPool::MySql(pool) => {
+            let rows = sqlx::query(
+                "SELECT COLUMN_NAME AS name, DATA_TYPE AS type FROM information_schema.columns
+                 WHERE table_schema = ? AND table_name = ? ORDER BY ordinal_position",
+            )
+            .bind(dbname)
+            .bind(table_name)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            Ok(rows
+                .iter()
+                .map(|r| (r.get::<String, _>("name"), r.get::<String, _>("type")))
+                .collect())
+        }
+    }
+}
+
+/// 执行语句，返回影响行数
+pub async fn execute(pool: &DbPool, sql: &str) -> Result<u64, String> {
+    let res = match pool {
+        DbPool::MySql(pool) => sqlx::query(sql).execute(pool).await.map(|r| r.rows_affected()),
+        DbPool::Postgres(pool) => sqlx::query(sql).execute(pool).await.map(|r| r.rows_affected()),
+        DbPool::Sqlite(pool) => sqlx::query(sql).execute(pool).await.map(|r| r.rows_affected()),
+    };
+    res.map_err(|e| e.to_string())
+}
+
+/// 查询数据，返回结构与 MySQL 的 query_table_data 一致：{ columns, total, data }
+pub async fn query_table_data(pool: &DbPool, sql: &str) -> Result<Value, String> {
+    let count_sql = count_sql(sql);
+    let total = match pool {
+        DbPool::MySql(pool) => sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool).await,
+        DbPool::Postgres(pool) => sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool).await,
+        DbPool::Sqlite(pool) => sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool).await,
+    }
+    .map_err(|e| e.to_string())?;
+    let mut result = query_rows(pool, sql).await?;
+    result["total"] = json!(total);
+    Ok(result)
+}
+
+/// 执行查询并解码全部行：{ columns, data }，列信息取自结果集本身
+pub async fn query_rows(pool: &DbPool, sql: &str) -> Result<Value, String> {
+    let (columns, data) = match pool {
+        DbPool::MySql(pool) => {
+            let rows = sqlx::query(sql).fetch_all(pool).await.map_err(|e| e.to_string())?;
+            let columns = match rows.first() {
+                Some(row) => column_json(row.columns()),
+                None => column_json(pool.prepare(sql).await.map_err(|e| e.to_string())?.columns()),
+            };
+            let data: Vec<Value> = rows.iter().map(mysql_row_to_json).collect();
+            (columns, data)
+        }
+        DbPool::Postgres(pool) => {
+            let rows = sqlx::query(sql).fetch_all(pool).await.map_err(|e| e.to_string())?;
+            let columns = match rows.first() {
+                Some(row) => column_json(row.columns()),
+                None => column_json(pool.prepare(sql).await.map_err(|e| e.to_string())?.columns()),
+            };
+            let data: Vec<Value> = rows.iter().map(pg_row_to_json).collect();
+            (columns, data)
+        }
+        DbPool::Sqlite(pool) => {
+            let rows = sqlx::query(sql).fetch_all(pool).await.map_err(|e| e.to_string())?;
+            let columns = match rows.first() {
+                Some(row) => column_json(row.columns()),
+                None => column_json(pool.prepare(sql).await.map_err(|e| e.to_string())?.columns()),
+            };
+            let data: Vec<Value> = rows.iter().map(sqlite_row_to_json).collect();
+            (columns, data)
+        }
+    };
+    Ok(json!({ "columns": columns, "data": data }))
+}
+
+/// 统计总数的语句：去掉末尾的分页子句后把原查询包成子查询
+pub fn count_sql(sql: &str) -> String {
+    format!("SELECT COUNT(*) FROM ({}) AS t", strip_trailing_limit(sql))
+}
+
+/// 去掉末尾形如 `LIMIT n`、`LIMIT m, n`、`LIMIT n OFFSET m` 的分页子句
+/// 只处理数字字面量，其它情况原样返回，不会截断子查询或字符串中的 LIMIT
+pub fn strip_trailing_limit(sql: &str) -> &str {
+    let sql = sql.trim_end().trim_end_matches(';').trim_end();
+    let upper = sql.to_ascii_uppercase();
+    let Some(pos) = upper.rfind("LIMIT") else {
+        return sql;
+    };
+    // LIMIT 前必须是空白
+    if !upper[..pos].ends_with(char::is_whitespace) {
+        return sql;
+    }
+    let tail = upper[pos + "LIMIT".len()..].replace(',', " ");
+    let mut tokens = tail.split_whitespace();
+    let is_num = |t: Option<&str>| t.is_some_and(|t| t.chars().all(|c| c.is_ascii_digit()));
+    let valid = match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+        (first, None, None, None) => is_num(first),
+        (first, Some("OFFSET"), third, None) => is_num(first) && is_num(third),
+        (first, second, None, None) => is_num(first) && is_num(second),
+        _ => false,
+    };
+    if valid {
+        sql[..pos].trim_end()
+    } else {
+        sql
+    }
+}
+
+/// 结果集的列信息：序号、列名、类型
+pub fn column_json<C: Column>(columns: &[C]) -> Value {
+    Value::Array(
+        columns
+            .iter()
+            .map(|c| json!({ "index": c.ordinal(), "name": c.name(), "type": c.type_info().name().to_lowercase() }))
+            .collect(),
+    )
+}
+
+/// 二进制数据转 JSON：小于 MAX_INLINE_BYTES 时为 base64，否则为大小摘要
+pub fn bytes_json(bytes: &[u8]) -> Value {
+    if bytes.len() <= MAX_INLINE_BYTES {
+        json!({ "base64": general_purpose::STANDARD.encode(bytes), "size": bytes.len() })
+    } else {
+        json!({ "size": bytes.len() })
+    }
+}
+
+/// MySQL 行转 JSON，按结果集自身的列类型解码
+/// 64 位整数和 DECIMAL 以字符串返回，避免前端精度丢失
+pub fn mysql_row_to_json(row: &MySqlRow) -> Value {
+    row_object(row.columns(), mysql_row_values(row))
+}
+
+/// MySQL 行按列顺序解码
+pub fn mysql_row_values(row: &MySqlRow) -> Vec<Value> {
+    row.columns()
+        .iter()
+        .map(|col| {
+            let i = col.ordinal();
+            let is_null = row.try_get_raw(i).map(|v| v.is_null()).unwrap_or(true);
+            if is_null {
+                return Value::Null;
+            }
+            decode_mysql(row, i, col.type_info().name()).unwrap_or_else(|e| {
+                eprintln!("解码失败 {} ({}): {}", col.name(), col.type_info().name(), e);
+                Value::Null
+            })
+        })
+        .collect()
+}
+
+// 按列名组成 JSON 对象
+fn row_object<C: Column>(columns: &[C], values: Vec<Value>) -> Value {
+    Value::Object(
+        columns
+            .iter()
+            .map(|c| c.name().to_string())
+            .zip(values)
+            .collect(),
+    )
+}
+
+fn decode_mysql(row: &MySqlRow, i: usize, type_name: &str) -> Result<Value, sqlx::Error> {
+    let value = match type_name {
+        "BOOLEAN" => json!(row.try_get::<bool, _>(i)?),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" => json!(row.try_get::<i32, _>(i)?),
+        "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" => {
+            json!(row.try_get::<u32, _>(i)?)
+        }
+        "BIGINT" => json!(row.try_get::<i64, _>(i)?.to_string()),
+        "BIGINT UNSIGNED" => json!(row.try_get::<u64, _>(i)?.to_string()),
+        // YEAR、BIT 的无符号标记不一定存在，不做类型检查
+        "YEAR" => json!(row.try_get_unchecked::<u16, _>(i)?),
+        "BIT" => json!(row.try_get_unchecked::<u64, _>(i)?),
+        "FLOAT" => json!(row.try_get::<f32, _>(i)?),
+        "DOUBLE" => json!(row.try_get::<f64, _>(i)?),
+        "DECIMAL" => json!(row.try_get::<Decimal, _>(i)?.to_string()),
+        "DATE" => json!(row.try_get::<NaiveDate, _>(i)?.to_string()),
+        // TIME 可以为负数或超过 24 小时
+        "TIME" => json!(row.try_get::<MySqlTime, _>(i)?.to_string()),
+        // TIMESTAMP 按会话时区（连接时设为 UTC）返回，与写入时的解释一致，不做本地时间换算
+        "DATETIME" | "TIMESTAMP" => json!(row.try_get::<NaiveDateTime, _>(i)?.to_string()),
+        "JSON" => row.try_get::<Json<Value>, _>(i)?.0,
+        "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" => {
+            json!(row.try_get::<String, _>(i)?)
+        }
+        "SET" => json!(row.try_get_unchecked::<String, _>(i)?),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => {
+            bytes_json(&row.try_get_unchecked::<Vec<u8>, _>(i)?)
+        }
+        "NULL" => Value::Null,
+        // 其它类型按原始字节尝试转为文本
+        _ => {
+            let bytes = row.try_get_unchecked::<Vec<u8>, _>(i)?;
+            json!(String::from_utf8_lossy(&bytes))
+        }
+    };
+    Ok(value)
+}
+
+/// PostgreSQL 行转 JSON，按结果集自身的列类型解码
+pub fn pg_row_to_json(row: &PgRow) -> Value {
+    row_object(row.columns(), pg_row_values(row))
+}
+
+/// PostgreSQL 行按列顺序解码
+pub fn pg_row_values(row: &PgRow) -> Vec<Value> {
+    let mut values = Vec::with_capacity(row.len());
+    for col in row.columns() {
+        let i = col.ordinal();
+        let is_null = row.try_get_raw(i).map(|v| v.is_null()).unwrap_or(true);
+        let value = if is_null {
+            Value::Null
+        } else {
+            match col.type_info().name() {
+                "INT2" => row.try_get::<i16, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "INT4" => row.try_get::<i32, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "INT8" => row.try_get::<i64, _>(i).map(|v| json!(v.to_string())).unwrap_or(Value::Null),
+                "FLOAT4" => row.try_get::<f32, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "FLOAT8" => row.try_get::<f64, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "NUMERIC" => row.try_get::<Decimal, _>(i).map(|v| json!(v.to_string())).unwrap_or(Value::Null),
+                "BOOL" => row.try_get::<bool, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "TIMESTAMP" => row.try_get::<NaiveDateTime, _>(i).map(|v| json!(v.to_string())).unwrap_or(Value::Null),
+                "TIMESTAMPTZ" => row.try_get::<DateTime<Utc>, _>(i).map(|v| json!(v.to_rfc3339())).unwrap_or(Value::Null),
+                "DATE" => row.try_get::<NaiveDate, _>(i).map(|v| json!(v.to_string())).unwrap_or(Value::Null),
+                "TIME" => row.try_get::<NaiveTime, _>(i).map(|v| json!(v.to_string())).unwrap_or(Value::Null),
+                "JSON" | "JSONB" => row.try_get::<Value, _>(i).unwrap_or(Value::Null),
+                "UUID" => row.try_get::<Uuid, _>(i).map(|v| json!(v.to_string())).unwrap_or(Value::Null),
+                "BYTEA" => row.try_get::<Vec<u8>, _>(i).map(|v| bytes_json(&v)).unwrap_or(Value::Null),
+                _ => row.try_get::<String, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+            }
+        };
+        values.push(value);
+    }
+    values
+}
+
+/// SQLite 行转 JSON，SQLite 是动态类型，按值的实际存储类型解码
+pub fn sqlite_row_to_json(row: &SqliteRow) -> Value {
+    row_object(row.columns(), sqlite_row_values(row))
+}
+
+/// SQLite 行按列顺序解码
+pub fn sqlite_row_values(row: &SqliteRow) -> Vec<Value> {
+    let mut values = Vec::with_capacity(row.len());
+    for col in row.columns() {
+        let i = col.ordinal();
+        let value = match row.try_get_raw(i) {
+            Ok(raw) if raw.is_null() => Value::Null,
+            Ok(raw) => match raw.type_info().name() {
+                "INTEGER" => row.try_get::<i64, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "REAL" => row.try_get::<f64, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+                "BLOB" => row.try_get::<Vec<u8>, _>(i).map(|v| bytes_json(&v)).unwrap_or(Value::Null),
+                _ => row.try_get::<String, _>(i).map(|v| json!(v)).unwrap_or(Value::Null),
+            },
+            Err(_) => Value::Null,
+        };
+        values.push(value);
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_numeric_trailing_limits() {
+        assert_eq!(strip_trailing_limit("SELECT * FROM t LIMIT 10"), "SELECT * FROM t");
+        assert_eq!(strip_trailing_limit("SELECT * FROM t limit 5, 10;"), "SELECT * FROM t");
+        assert_eq!(strip_trailing_limit("SELECT * FROM t LIMIT 10 OFFSET 20 ; "), "SELECT * FROM t");
+        assert_eq!(count_sql("SELECT 1 LIMIT 1"), "SELECT COUNT(*) FROM (SELECT 1) AS t");
+    }
+
+    #[test]
+    fn keeps_other_limits() {
+        for sql in [
+            "SELECT * FROM t",
+            "SELECT * FROM t LIMIT ?",
+            "SELECT * FROM (SELECT * FROM t LIMIT 1) x WHERE a = 1",
+            "SELECT 'LIMIT 1' AS unlimited",
+            "SELECT * FROM t LIMIT 1 OFFSET",
+        ] {
+            assert_eq!(strip_trailing_limit(sql), sql);
+        }
+    }
+}
@@ -0,0 +1,540 @@
+use crate::commands::sqlx::{ensure_read_only_sql, explorer, get_pool, DbPool};
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use futures_util::TryStreamExt;
+use log::{error, info};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{Column, Executor, Row, TypeInfo};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+// 每导出多少行上报一次进度
+const PROGRESS_EVERY: u64 = 1000;
+// INSERT 脚本中每条语句的默认行数
+const DEFAULT_INSERT_ROWS: usize = 500;
+// Excel 单个工作表的最大行数（含表头），超出后写入新的工作表
+const XLSX_MAX_ROWS: u32 = 1_048_576;
+// Excel 单元格文本的最大长度
+const XLSX_MAX_CHARS: usize = 32_767;
+// 读取与写入之间缓冲的最大行数，写入跟不上时读取等待
+const EXPORT_CHANNEL_ROWS: usize = 1000;
+
+// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    // 每行一个 JSON 对象
+    Jsonl,
+    // INSERT 语句脚本
+    Sql,
+}
+
+impl ExportFormat {
+    fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" | "tsv" | "txt" => Some(Self::Csv),
+            "xlsx" => Some(Self::Xlsx),
+            "jsonl" | "ndjson" | "json" => Some(Self::Jsonl),
+            "sql" => Some(Self::Sql),
+            _ => None,
+        }
+    }
+}
+
+// 导出参数：sql 与 table_name 二选一，都有时使用 sql
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportOptions {
+    pub path: String,
+    // 默认按文件扩展名判断
+    #[serde(default)]
+    pub format: Option<ExportFormat>,
+    #[serde(default)]
+    pub sql: Option<String>,
+    #[serde(default)]
+    pub db_name: Option<String>,
+    #[serde(default)]
+    pub table_name: Option<String>,
+    // CSV 分隔符，默认逗号（.tsv 为制表符）
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    // CSV / Excel 是否写表头，默认 true
+    #[serde(default)]
+    pub header: Option<bool>,
+    // INSERT 脚本的目标表名，默认为导出的表名
+    #[serde(default)]
+    pub target_table: Option<String>,
+    // INSERT 脚本中每条语句的行数
+    #[serde(default)]
+    pub insert_rows: Option<usize>,
+}
+
+// 导出进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub path: String,
+    pub rows: u64,
+    pub elapsed_ms: u64,
+}
+
+// 导出结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: ExportFormat,
+    pub rows: u64,
+    // 超过内联大小而没有导出内容的二进制值个数
+    pub truncated: u64,
+    pub elapsed_ms: u64,
+}
+
+// 结果集的列名和类型
+struct ExportColumn {
+    name: String,
+    type_name: String,
+}
+
+impl ExportColumn {
+    fn of<C: Column>(columns: &[C]) -> Vec<Self> {
+        columns
+            .iter()
+            .map(|c| ExportColumn {
+                name: c.name().to_string(),
+                type_name: c.type_info().name().to_lowercase(),
+            })
+            .collect()
+    }
+}
+
+// 读取端发给写入线程的内容
+enum ExportMessage {
+    Columns(Vec<ExportColumn>),
+    Row(Vec<Value>),
+}
+
+// 二进制列解码为 { base64, size }，超过内联大小时只有 size
+fn binary_value(value: &Value) -> Option<Option<Vec<u8>>> {
+    let obj = value.as_object()?;
+    if !obj.contains_key("size") || obj.keys().any(|k| k != "size" && k != "base64") {
+        return None;
+    }
+    Some(
+        obj.get("base64")
+            .and_then(Value::as_str)
+            .and_then(|s| general_purpose::STANDARD.decode(s).ok()),
+    )
+}
+
+fn is_binary_type(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "geometry" | "bytea"
+    )
+}
+
+// 单元格文本：NULL 为空，二进制为 base64，JSON 值为 JSON 文本
+fn value_text(value: &Value, binary: bool) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Object(obj) if binary => obj
+            .get("base64")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+        other => other.to_string(),
+    }
+}
+
+// 按后端生成 SQL 字面量
+fn value_literal(pool: &DbPool, value: &Value, binary: bool, truncated: &mut u64) -> String {
+    if binary {
+        if let Some(bytes) = binary_value(value) {
+            let Some(bytes) = bytes else {
+                *truncated += 1;
+                return "NULL".to_string();
+            };
+            let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            return match pool {
+                DbPool::Postgres(_) => format!("'\\x{}'", hex),
+                _ => format!("X'{}'", hex),
+            };
+        }
+    }
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => explorer::quote_str(pool, s),
+        other => explorer::quote_str(pool, &other.to_string()),
+    }
+}
+
+// 各格式的写入器
+enum ExportWriter {
+    Csv(Box<csv::Writer<BufWriter<File>>>),
+    Jsonl(BufWriter<File>),
+    Sql {
+        out: BufWriter<File>,
+        table: String,
+        rows: Vec<String>,
+        batch: usize,
+    },
+    Xlsx {
+        workbook: Box<Workbook>,
+        path: String,
+        sheet: usize,
+        row: u32,
+    },
+}
+
+struct Exporter {
+    writer: ExportWriter,
+    pool: DbPool,
+    header: bool,
+    columns: Vec<ExportColumn>,
+    bold: Format,
+    truncated: u64,
+}
+
+impl Exporter {
+    // path 为实际写入的文件，导出成功后再改名为 options.path
+    fn new(
+        pool: DbPool,
+        options: &ExportOptions,
+        path: &str,
+        format: ExportFormat,
+        table: String,
+    ) -> Result<Self, String> {
+        let create = || -> Result<BufWriter<File>, String> {
+            if let Some(dir) = Path::new(path).parent() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            File::create(path)
+                .map(BufWriter::new)
+                .map_err(|e| format!("创建文件失败: {}", e))
+        };
+        let writer = match format {
+            ExportFormat::Csv => {
+                let tsv = options.path.to_ascii_lowercase().ends_with(".tsv");
+                let delimiter = options.delimiter.unwrap_or(if tsv { '\t' } else { ',' });
+                if !delimiter.is_ascii() {
+                    return Err(format!("不支持的分隔符: {}", delimiter));
+                }
+                let mut out = create()?;
+                // 带 BOM，Excel 打开时能正确识别 UTF-8
+                out.write_all("\u{feff}".as_bytes()).map_err(|e| e.to_string())?;
+                ExportWriter::Csv(Box::new(
+                    csv::WriterBuilder::new()
+                        .delimiter(delimiter as u8)
+                        .from_writer(out),
+                ))
+            }
+            ExportFormat::Jsonl => ExportWriter::Jsonl(create()?),
+            ExportFormat::Sql => ExportWriter::Sql {
+                out: create()?,
+                table,
+                rows: Vec::new(),
+                batch: options.insert_rows.unwrap_or(DEFAULT_INSERT_ROWS).max(1),
+            },
+            ExportFormat::Xlsx => {
+                // 常量内存模式：写完一行即刷到临时文件
+                let mut workbook = Box::new(Workbook::new());
+                workbook.add_worksheet_with_constant_memory();
+                ExportWriter::Xlsx {
+                    workbook,
+                    path: path.to_string(),
+                    sheet: 0,
+                    row: 0,
+                }
+            }
+        };
+        Ok(Self {
+            writer,
+            pool,
+            header: options.header.unwrap_or(true),
+            columns: Vec::new(),
+            bold: Format::new().set_bold(),
+            truncated: 0,
+        })
+    }
+
+    fn set_columns(&mut self, columns: Vec<ExportColumn>) -> Result<(), String> {
+        self.columns = columns;
+        if !self.header {
+            return Ok(());
+        }
+        let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        match &mut self.writer {
+            ExportWriter::Csv(writer) => writer.write_record(&names).map_err(|e| e.to_string()),
+            ExportWriter::Xlsx { .. } => self.write_xlsx_header(),
+            _ => Ok(()),
+        }
+    }
+
+    fn write_xlsx_header(&mut self) -> Result<(), String> {
+        let ExportWriter::Xlsx { workbook, sheet, row, .. } = &mut self.writer else {
+            return Ok(());
+        };
+        let worksheet = workbook.worksheet_from_index(*sheet).map_err(|e| e.to_string())?;
+        for (i, column) in self.columns.iter().enumerate() {
+            worksheet
+                .write_string_with_format(*row, i as u16, &column.name, &self.bold)
+                .map_err(|e| e.to_string())?;
+        }
+        *row += 1;
+        Ok(())
+    }
+
+    // row 按列顺序排列，与 columns 一一对应
+    fn write_row(&mut self, row: &[Value]) -> Result<(), String> {
+        match &mut self.writer {
+            ExportWriter::Csv(writer) => {
+                let record: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(c, v)| value_text(v, is_binary_type(&c.type_name)))
+                    .collect();
+                writer.write_record(&record).map_err(|e| e.to_string())
+            }
+            ExportWriter::Jsonl(out) => {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .zip(row.iter().cloned())
+                    .collect();
+                serde_json::to_writer(&mut *out, &object).map_err(|e| e.to_string())?;
+                out.write_all(b"\n").map_err(|e| e.to_string())
+            }
+            ExportWriter::Sql { rows, batch, .. } => {
+                let values: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(c, v)| value_literal(&self.pool, v, is_binary_type(&c.type_name), &mut self.truncated))
+                    .collect();
+                rows.push(format!("({})", values.join(", ")));
+                if rows.len() >= *batch {
+                    self.flush_inserts()?;
+                }
+                Ok(())
+            }
+            ExportWriter::Xlsx { .. } => self.write_xlsx_row(row),
+        }
+    }
+
+    fn write_xlsx_row(&mut self, values: &[Value]) -> Result<(), String> {
+        let ExportWriter::Xlsx { workbook, sheet, row, .. } = &mut self.writer else {
+            return Ok(());
+        };
+        if *row >= XLSX_MAX_ROWS {
+            workbook.add_worksheet_with_constant_memory();
+            *sheet += 1;
+            *row = 0;
+            if self.header {
+                self.write_xlsx_header()?;
+            }
+            return self.write_xlsx_row(values);
+        }
+        let worksheet = workbook.worksheet_from_index(*sheet).map_err(|e| e.to_string())?;
+        for (i, (column, value)) in self.columns.iter().zip(values).enumerate() {
+            let col = i as u16;
+            let res = match value {
+                Value::Null => continue,
+                Value::Bool(b) => worksheet.write_boolean(*row, col, *b),
+                // 超出 f64 精度的整数按文本写入
+                Value::Number(n) => match n.as_f64() {
+                    Some(f) if n.is_f64() || f.abs() < 9_007_199_254_740_992.0 => {
+                        worksheet.write_number(*row, col, f)
+                    }
+                    _ => worksheet.write_string(*row, col, n.to_string()),
+                },
+                value => {
+                    let text = value_text(value, is_binary_type(&column.type_name));
+                    let text: String = text.chars().take(XLSX_MAX_CHARS).collect();
+                    worksheet.write_string(*row, col, text)
+                }
+            };
+            res.map_err(|e| e.to_string())?;
+        }
+        *row += 1;
+        Ok(())
+    }
+
+    fn flush_inserts(&mut self) -> Result<(), String> {
+        if let ExportWriter::Sql { out, table, rows, .. } = &mut self.writer {
+            if rows.is_empty() {
+                return Ok(());
+            }
+            let sql = format!("INSERT INTO {} VALUES\n{};\n", table, rows.join(",\n"));
+            rows.clear();
+            out.write_all(sql.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<u64, String> {
+        self.flush_inserts()?;
+        match self.writer {
+            ExportWriter::Csv(mut writer) => writer.flush().map_err(|e| e.to_string())?,
+            ExportWriter::Jsonl(mut out) | ExportWriter::Sql { mut out, .. } => {
+                out.flush().map_err(|e| e.to_string())?
+            }
+            ExportWriter::Xlsx { mut workbook, path, .. } => {
+                workbook.save(&path).map_err(|e| e.to_string())?
+            }
+        }
+        Ok(self.truncated)
+    }
+}
+
+/// 导出查询结果或整张表到文件，逐行读取写入，progress 返回错误时中止导出
+pub async fn run_export<F>(
+    profile: Option<&str>,
+    options: &ExportOptions,
+    mut progress: F,
+) -> Result<ExportSummary, String>
+where
+    F: FnMut(&ExportProgress) -> Result<(), String>,
+{
+    let start = Instant::now();
+    let format = match options.format {
+        Some(format) => format,
+        None => ExportFormat::from_path(&options.path)
+            .ok_or_else(|| format!("无法根据文件名判断导出格式: {}", options.path))?,
+    };
+
+    let (sql, table) = match (&options.sql, &options.table_name) {
+        (Some(sql), _) if !sql.trim().is_empty() => {
+            (sql.clone(), options.table_name.clone().unwrap_or_else(|| "export".to_string()))
+        }
+        (_, Some(table_name)) => {
+            let pool = get_pool(profile)
+                .await
+                .ok_or_else(|| "数据库连接未初始化".to_string())?;
+            let db_name = options.db_name.as_deref().unwrap_or("");
+            let table = explorer::qualified_table(&pool, db_name, table_name).await?;
+            (format!("SELECT * FROM {}", table), table_name.clone())
+        }
+        _ => return Err("请指定导出的查询语句或表名".to_string()),
+    };
+    let pool = ensure_read_only_sql(profile, &sql).await?;
+    let target = explorer::quote_ident(&pool, options.target_table.as_deref().unwrap_or(&table));
+    // 先写入临时文件，导出成功后再改名，失败或中止时不留下不完整的文件
+    let part_path = format!("{}.part", options.path);
+    let exporter = Exporter::new(pool.clone(), options, &part_path, format, target)?;
+    info!("export {:?} to {}: {}", format, options.path, sql);
+
+    // 文件写入在阻塞线程中进行，通过有界通道接收读取到的行
+    let (tx, mut rx) = mpsc::channel::<ExportMessage>(EXPORT_CHANNEL_ROWS);
+    let writer = tauri::async_runtime::spawn_blocking(move || -> Result<u64, String> {
+        let mut exporter = exporter;
+        while let Some(message) = rx.blocking_recv() {
+            match message {
+                ExportMessage::Columns(columns) => exporter.set_columns(columns)?,
+                ExportMessage::Row(values) => exporter.write_row(&values)?,
+            }
+        }
+        exporter.finish()
+    });
+
+    let mut rows: u64 = 0;
+    let read = async {
+        // 表头取自语句的列信息，结果为空时也有表头；无法预编译的语句取第一行的列
+        let mut has_columns = false;
+        macro_rules! export_rows {
+            ($pool:expr, $to_values:path) => {{
+                if let Ok(describe) = $pool.describe(&sql).await {
+                    has_columns = !describe.columns().is_empty();
+                    let columns = ExportColumn::of(describe.columns());
+                    if has_columns && tx.send(ExportMessage::Columns(columns)).await.is_err() {
+                        return Ok(());
+                    }
+                }
+                let mut stream = sqlx::query(&sql).fetch($pool);
+                while let Some(row) = stream.try_next().await.map_err(|e| e.to_string())? {
+                    if !has_columns {
+                        has_columns = true;
+                        if tx.send(ExportMessage::Columns(ExportColumn::of(row.columns()))).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                    // 写入线程出错退出时通道关闭，错误由写入线程返回
+                    if tx.send(ExportMessage::Row($to_values(&row))).await.is_err() {
+                        return Ok(());
+                    }
+                    rows += 1;
+                    if rows % PROGRESS_EVERY == 0 {
+                        progress(&ExportProgress {
+                            path: options.path.clone(),
+                            rows,
+                            elapsed_ms: start.elapsed().as_millis() as u64,
+                        })?;
+                    }
+                }
+            }};
+        }
+        match &pool {
+            DbPool::MySql(pool) => export_rows!(pool, explorer::mysql_row_values),
+            DbPool::Postgres(pool) => export_rows!(pool, explorer::pg_row_values),
+            DbPool::Sqlite(pool) => export_rows!(pool, explorer::sqlite_row_values),
+        }
+        Ok::<(), String>(())
+    }
+    .await;
+    drop(tx);
+    let written = writer.await.map_err(|e| e.to_string()).and_then(|r| r);
+
+    let truncated = match read.and(written) {
+        Ok(truncated) => std::fs::rename(&part_path, &options.path)
+            .map(|_| truncated)
+            .map_err(|e| format!("保存文件失败: {}", e)),
+        Err(e) => Err(e),
+    };
+    let truncated = match truncated {
+        Ok(truncated) => truncated,
+        Err(e) => {
+            let _ = std::fs::remove_file(&part_path);
+            error!("export to {} failed: {}", options.path, e);
+            return Err(e);
+        }
+    };
+    let summary = ExportSummary {
+        path: options.path.clone(),
+        format,
+        rows,
+        truncated,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    };
+    progress(&ExportProgress {
+        path: summary.path.clone(),
+        rows,
+        elapsed_ms: summary.elapsed_ms,
+    })?;
+    Ok(summary)
+}
+
+/// 导出为 CSV / XLSX / JSON Lines / INSERT 脚本，进度通过 export_progress 事件推送
+#[command]
+pub async fn export_query_data(
+    app_handle: AppHandle,
+    options: ExportOptions,
+    profile: Option<String>,
+) -> Result<ExportSummary, String> {
+    let summary = run_export(profile.as_deref(), &options, |p| {
+        app_handle.emit("export_progress", p).map_err(|e| e.to_string())
+    })
+    .await?;
+    info!("export done {:?}", summary);
+    Ok(summary)
+}
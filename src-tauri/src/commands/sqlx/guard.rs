@@ -0,0 +1,403 @@
+use crate::commands::sqlx::{get_open_pool, split_statements, DbPool, OpenPool, ScriptDialect};
+use crate::util::uuid_util;
+use log::debug;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlparser::ast::{AlterTableOperation, SetExpr, Statement};
+use sqlparser::dialect::{Dialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::command;
+
+// 确认令牌有效期
+const CONFIRM_TOKEN_TTL: Duration = Duration::from_secs(60);
+
+// 语句类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementKind {
+    // 查询：SELECT / SHOW / DESCRIBE / EXPLAIN
+    Read,
+    // 数据修改：INSERT / UPDATE / DELETE
+    Dml,
+    // 结构修改：CREATE / ALTER / DROP / TRUNCATE
+    Ddl,
+    // 事务控制和会话设置：BEGIN / COMMIT / SET / USE
+    Session,
+    // 其它：权限、用户、KILL 等
+    Admin,
+}
+
+// 单条语句的分类结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementInfo {
+    pub kind: StatementKind,
+    // 语句开头的关键字，如 SELECT、DROP
+    pub keyword: String,
+    // 是否需要确认（DROP、TRUNCATE、删除列、不带 WHERE 的 DELETE/UPDATE）
+    pub destructive: bool,
+    // 解析失败时按关键字保守分类，此时为 false
+    pub parsed: bool,
+}
+
+// check_sql 的返回结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SqlCheck {
+    pub statements: Vec<StatementInfo>,
+    pub read_only: bool,
+    // 只读连接中包含修改语句时为 false
+    pub allowed: bool,
+    // 包含危险语句时返回确认令牌，执行时需带上
+    pub confirm_token: Option<String>,
+}
+
+struct PendingConfirm {
+    sql: String,
+    profile: String,
+    expires: Instant,
+}
+
+// 已签发、尚未使用的确认令牌
+static CONFIRM_TOKENS: Lazy<Mutex<HashMap<String, PendingConfirm>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn dialect_for(dialect: ScriptDialect) -> Box<dyn Dialect> {
+    match dialect {
+        ScriptDialect::MySql => Box::new(MySqlDialect {}),
+        ScriptDialect::Postgres => Box::new(PostgreSqlDialect {}),
+        ScriptDialect::Sqlite => Box::new(SQLiteDialect {}),
+    }
+}
+
+/// 对 SQL 中的每条语句分类，先按分隔符拆分（支持 DELIMITER），解析失败的语句按首个关键字分类
+pub fn classify(pool: &DbPool, sql: &str) -> Vec<StatementInfo> {
+    classify_sql(ScriptDialect::of(pool), sql)
+}
+
+/// 按指定方言对 SQL 分类，用于没有连接池时（如检查备份文件）
+pub fn classify_sql(script_dialect: ScriptDialect, sql: &str) -> Vec<StatementInfo> {
+    let dialect = dialect_for(script_dialect);
+    split_statements(sql, script_dialect)
+        .iter()
+        .flat_map(|stmt| match Parser::parse_sql(dialect.as_ref(), stmt) {
+            Ok(statements) => statements.iter().map(classify_statement).collect(),
+            Err(e) => {
+                debug!("SQL 解析失败，按关键字分类: {}", e);
+                vec![classify_keyword(stmt)]
+            }
+        })
+        .collect()
+}
+
+// 跳过开头的空白和注释
+fn skip_leading_comments(mut sql: &str) -> &str {
+    loop {
+        sql = sql.trim_start();
+        if sql.starts_with("--") || sql.starts_with('#') {
+            sql = sql.find('\n').map_or("", |i| &sql[i + 1..]);
+        } else if let Some(rest) = sql.strip_prefix("/*") {
+            sql = rest.find("*/").map_or("", |i| &rest[i + 2..]);
+        } else {
+            return sql;
+        }
+    }
+}
+
+/// 语句开头的关键字（大写），跳过前面的注释
+pub fn first_keyword(sql: &str) -> String {
+    skip_leading_comments(sql)
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .trim_matches(|c: char| !c.is_ascii_alphabetic())
+        .to_ascii_uppercase()
+}
+
+fn classify_statement(stmt: &Statement) -> StatementInfo {
+    let keyword = first_keyword(&stmt.to_string());
+    let (kind, destructive) = match stmt {
+        Statement::Query(query) => match query.body.as_ref() {
+            // 带数据修改的 WITH 语句
+            SetExpr::Insert(_) => (StatementKind::Dml, false),
+            SetExpr::Update(inner) => return classify_statement(inner),
+            _ => (StatementKind::Read, false),
+        },
+        // EXPLAIN ANALYZE 会真正执行语句
+        Statement::Explain {
+            analyze: true,
+            statement,
+            ..
+        } => {
+            let inner = classify_statement(statement);
+            (inner.kind, inner.destructive)
+        }
+        Statement::Explain { .. }
+        | Statement::ExplainTable { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowStatus { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowDatabases { .. }
+        | Statement::ShowSchemas { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowViews { .. }
+        | Statement::ShowCollation { .. } => (StatementKind::Read, false),
+        Statement::Insert(_) | Statement::Merge { .. } | Statement::Copy { .. } => {
+            (StatementKind::Dml, false)
+        }
+        Statement::Update { selection, .. } => (StatementKind::Dml, selection.is_none()),
+        Statement::Delete(delete) => (StatementKind::Dml, delete.selection.is_none()),
+        Statement::Truncate { .. }
+        | Statement::Drop { .. }
+        | Statement::DropFunction { .. }
+        | Statement::DropProcedure { .. }
+        | Statement::DropTrigger { .. }
+        | Statement::DropPolicy { .. }
+        | Statement::DropSecret { .. } => (StatementKind::Ddl, true),
+        Statement::CreateTable(_)
+        | Statement::CreateView { .. }
+        | Statement::CreateIndex(_)
+        | Statement::CreateSchema { .. }
+        | Statement::CreateDatabase { .. }
+        | Statement::CreateFunction(_)
+        | Statement::CreateTrigger { .. }
+        | Statement::CreateProcedure { .. }
+        | Statement::CreateSequence { .. }
+        | Statement::CreateType { .. }
+        | Statement::AlterIndex { .. }
+        | Statement::AlterView { .. }
+        | Statement::Comment { .. } => (StatementKind::Ddl, false),
+        // 删除列或分区会丢失数据
+        Statement::AlterTable { operations, .. } => (
+            StatementKind::Ddl,
+            operations.iter().any(|op| {
+                matches!(
+                    op,
+                    AlterTableOperation::DropColumn { .. } | AlterTableOperation::DropPartitions { .. }
+                )
+            }),
+        ),
+        Statement::StartTransaction { .. }
+        | Statement::SetTransaction { .. }
+        | Statement::Commit { .. }
+        | Statement::Rollback { .. }
+        | Statement::Savepoint { .. }
+        | Statement::ReleaseSavepoint { .. }
+        | Statement::SetVariable { .. }
+        | Statement::SetNames { .. }
+        | Statement::SetNamesDefault { .. }
+        | Statement::SetTimeZone { .. }
+        | Statement::Use(_)
+        | Statement::LockTables { .. }
+        | Statement::UnlockTables => (StatementKind::Session, false),
+        _ => (StatementKind::Admin, false),
+    };
+    StatementInfo {
+        kind,
+        keyword,
+        destructive,
+        parsed: true,
+    }
+}
+
+// 无法解析时的保守分类：无法确认有 WHERE 的 UPDATE/DELETE、可能修改数据的 WITH、
+// ALTER 中的 DROP 以及无法识别开头关键字的语句都按危险语句处理
+fn classify_keyword(sql: &str) -> StatementInfo {
+    let keyword = first_keyword(sql);
+    let (kind, destructive) = match keyword.as_str() {
+        "SELECT" | "SHOW" | "DESCRIBE" | "DESC" | "EXPLAIN" => (StatementKind::Read, false),
+        "INSERT" | "REPLACE" | "MERGE" => (StatementKind::Dml, false),
+        "UPDATE" | "DELETE" | "WITH" => (StatementKind::Dml, true),
+        "DROP" | "TRUNCATE" => (StatementKind::Ddl, true),
+        "ALTER" => (
+            StatementKind::Ddl,
+            sql.split_whitespace().any(|w| w.eq_ignore_ascii_case("DROP")),
+        ),
+        "CREATE" | "RENAME" | "COMMENT" => (StatementKind::Ddl, false),
+        "BEGIN" | "START" | "COMMIT" | "ROLLBACK" | "SAVEPOINT" | "SET" | "USE" | "LOCK" | "UNLOCK" => {
+            (StatementKind::Session, false)
+        }
+        "" => (StatementKind::Admin, true),
+        _ => (StatementKind::Admin, false),
+    };
+    StatementInfo {
+        kind,
+        keyword,
+        destructive,
+        parsed: false,
+    }
+}
+
+fn profile_id(profile: Option<&str>) -> String {
+    profile
+        .filter(|p| !p.is_empty())
+        .unwrap_or(crate::commands::sqlx::DEFAULT_PROFILE)
+        .to_string()
+}
+
+async fn open_pool(profile: Option<&str>) -> Result<OpenPool, String> {
+    get_open_pool(profile)
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())
+}
+
+/// 只读连接中不允许的语句：除查询外都算，会话语句也可能修改服务器状态（SET GLOBAL、LOCK TABLES）
+pub fn is_write(kind: StatementKind) -> bool {
+    kind != StatementKind::Read
+}
+
+/// 查询类命令使用：只允许读语句
+pub async fn ensure_read_only_sql(profile: Option<&str>, sql: &str) -> Result<DbPool, String> {
+    let open = open_pool(profile).await?;
+    let statements = classify(&open.pool, sql);
+    if let Some(stmt) = statements.iter().find(|s| s.kind != StatementKind::Read) {
+        return Err(format!("查询命令只能执行查询语句，不能执行 {}", stmt.keyword));
+    }
+    Ok(open.pool)
+}
+
+/// 执行类命令使用：检查只读连接，危险语句需要有效的确认令牌
+pub async fn guard_sql(
+    profile: Option<&str>,
+    sql: &str,
+    confirm_token: Option<&str>,
+) -> Result<DbPool, String> {
+    let open = open_pool(profile).await?;
+    let statements = classify(&open.pool, sql);
+    if open.read_only {
+        if let Some(stmt) = statements.iter().find(|s| is_write(s.kind)) {
+            return Err(format!("只读连接不能执行 {}", stmt.keyword));
+        }
+    }
+    if statements.iter().any(|s| s.destructive) {
+        let token = confirm_token.ok_or_else(|| {
+            "危险语句需要确认，请先调用 check_sql 获取确认令牌".to_string()
+        })?;
+        take_confirm_token(token, &profile_id(profile), sql)?;
+    }
+    Ok(open.pool)
+}
+
+// 校验并作废确认令牌，令牌只能对签发时的同一条 SQL 使用一次
+fn take_confirm_token(token: &str, profile: &str, sql: &str) -> Result<(), String> {
+    let mut tokens = CONFIRM_TOKENS.lock().unwrap();
+    tokens.retain(|_, p| p.expires > Instant::now());
+    match tokens.remove(token) {
+        Some(p) if p.sql == sql && p.profile == profile => Ok(()),
+        Some(_) => Err("确认令牌与语句不匹配".to_string()),
+        None => Err("确认令牌无效或已过期".to_string()),
+    }
+}
+
+/// 执行前检查 SQL：返回分类结果，危险语句附带确认令牌
+#[command]
+pub async fn check_sql(sql: String, profile: Option<String>) -> Result<SqlCheck, String> {
+    let open = open_pool(profile.as_deref()).await?;
+    let statements = classify(&open.pool, &sql);
+    let allowed = !open.read_only || !statements.iter().any(|s| is_write(s.kind));
+    let confirm_token = if allowed && statements.iter().any(|s| s.destructive) {
+        let token = uuid_util::new_id();
+        let mut tokens = CONFIRM_TOKENS.lock().unwrap();
+        tokens.retain(|_, p| p.expires > Instant::now());
+        tokens.insert(
+            token.clone(),
+            PendingConfirm {
+                sql,
+                profile: profile_id(profile.as_deref()),
+                expires: Instant::now() + CONFIRM_TOKEN_TTL,
+            },
+        );
+        Some(token)
+    } else {
+        None
+    };
+    Ok(SqlCheck {
+        statements,
+        read_only: open.read_only,
+        allowed,
+        confirm_token,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one(dialect: ScriptDialect, sql: &str) -> StatementInfo {
+        let mut infos = classify_sql(dialect, sql);
+        assert_eq!(infos.len(), 1, "{}", sql);
+        infos.remove(0)
+    }
+
+    fn mysql(sql: &str) -> StatementInfo {
+        one(ScriptDialect::MySql, sql)
+    }
+
+    #[test]
+    fn classifies_reads() {
+        for sql in [
+            "SELECT * FROM book",
+            "WITH t AS (SELECT 1) SELECT * FROM t",
+            "SHOW TABLES",
+            "EXPLAIN SELECT 1",
+        ] {
+            let info = mysql(sql);
+            assert_eq!(info.kind, StatementKind::Read, "{}", sql);
+            assert!(!info.destructive, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn where_clause_comes_from_the_ast() {
+        assert!(mysql("DELETE FROM book").destructive);
+        assert!(!mysql("DELETE FROM book WHERE id = 1").destructive);
+        // 字符串中的 where 不算条件
+        assert!(mysql("UPDATE book SET title = 'where'").destructive);
+        assert!(!mysql("UPDATE book SET title = 'x' WHERE id = 1").destructive);
+    }
+
+    #[test]
+    fn dropping_data_is_destructive() {
+        assert!(mysql("DROP TABLE book").destructive);
+        assert!(mysql("TRUNCATE TABLE book").destructive);
+        let info = mysql("ALTER TABLE book DROP COLUMN img");
+        assert_eq!((info.kind, info.destructive), (StatementKind::Ddl, true));
+        assert!(!mysql("ALTER TABLE book ADD COLUMN isbn VARCHAR(20)").destructive);
+    }
+
+    #[test]
+    fn unparsed_statements_are_conservative() {
+        let info = mysql("WITH t AS (SELECT 1) DELETE FROM book WHERE id IN (SELECT * FROM t) ???");
+        assert!(!info.parsed);
+        assert_eq!((info.kind, info.destructive), (StatementKind::Dml, true));
+        let info = mysql("UPDATE book SET ??? WHERE id = 1");
+        assert_eq!((info.kind, info.destructive), (StatementKind::Dml, true));
+        let info = mysql("/* 注释 */ DROP ??? book");
+        assert_eq!((info.keyword.as_str(), info.destructive), ("DROP", true));
+        let info = mysql("??? book");
+        assert!(info.destructive);
+    }
+
+    #[test]
+    fn session_statements_are_writes() {
+        assert_eq!(mysql("SET GLOBAL max_connections = 10").kind, StatementKind::Session);
+        assert!(is_write(StatementKind::Session));
+        assert!(is_write(mysql("LOCK TABLES book WRITE").kind));
+        assert!(!is_write(mysql("SELECT 1").kind));
+    }
+
+    #[test]
+    fn postgres_function_bodies_stay_in_one_statement() {
+        let infos = classify_sql(
+            ScriptDialect::Postgres,
+            "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql; SELECT f()",
+        );
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].kind, StatementKind::Ddl);
+        assert_eq!(infos[1].kind, StatementKind::Read);
+    }
+}
@@ -0,0 +1,395 @@
+use crate::commands::sqlx::{
+    connect_profile, connect_sqlite_file, data_pool_generation, get_data_pool, open_draft_db, sqlite_path, DbPool,
+};
+use crate::commands::{load_secret, AppState};
+use crate::util::{DbProfile, Secret, APP_DB_CREDENTIAL};
+use log::info;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, Notify};
+
+// 重连等待时间从 1 秒开始翻倍，最长 60 秒
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// 连接正常时的检查间隔
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+// 单次检查的超时时间
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+// 连续检查失败多少次后认为连接断开
+const MAX_PING_FAILURES: u32 = 2;
+// 取连接耗时按最近多少次计算平均值
+const LATENCY_WINDOW: usize = 20;
+
+// 业务数据库的连接目标，MySQL 密码在每次连接时重新读取
+#[derive(Debug, Clone)]
+pub enum DbTarget {
+    MySql {
+        ip: String,
+        username: String,
+    },
+    Sqlite {
+        path: PathBuf,
+    },
+}
+
+// 连接状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DbStatus {
+    // 首次连接中
+    Connecting,
+    Connected,
+    // 连接断开后重连中
+    Reconnecting,
+    // 连接失败，等待下次重试
+    Disconnected,
+}
+
+// 连接池统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+    pub max_connections: u32,
+    // 最近一次取连接耗时
+    pub last_acquire_ms: Option<f64>,
+    // 最近 LATENCY_WINDOW 次取连接的平均耗时和最大耗时
+    pub avg_acquire_ms: Option<f64>,
+    pub max_acquire_ms: Option<f64>,
+}
+
+// db_status 事件和 get_db_status 返回的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct DbHealth {
+    pub status: DbStatus,
+    pub backend: Option<String>,
+    // 本轮连续失败的次数
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    // 断开时下次重试的等待秒数
+    pub retry_in_secs: Option<u64>,
+    // 状态变化时间和最近一次检查时间（unix 秒）
+    pub changed_at: u64,
+    pub checked_at: Option<u64>,
+    pub pool: PoolStats,
+}
+
+struct HealthState {
+    health: DbHealth,
+    latencies: Vec<f64>,
+    running: bool,
+}
+
+static DB_HEALTH: Lazy<Mutex<HealthState>> = Lazy::new(|| {
+    Mutex::new(HealthState {
+        health: DbHealth {
+            status: DbStatus::Disconnected,
+            backend: None,
+            attempts: 0,
+            last_error: None,
+            retry_in_secs: None,
+            changed_at: now_secs(),
+            checked_at: None,
+            pool: PoolStats::default(),
+        },
+        latencies: Vec::new(),
+        running: false,
+    })
+});
+
+// reconnect_db 用来打断等待，立即检查或重连
+static RECONNECT: Lazy<Notify> = Lazy::new(Notify::new);
+// 密码或连接配置修改后，即使连接正常也按新配置重新连接
+static FORCE_RECONNECT: Lazy<Notify> = Lazy::new(Notify::new);
+
+// monitor 结束的原因
+enum MonitorEnd {
+    // 连续检查失败，附带最后的错误
+    Failed(String),
+    // 业务数据连接池被其它地方替换，如界面上切换了数据库
+    Replaced,
+    // 要求按新配置重新连接
+    Reconfigure,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// 修改状态，状态有变化时发送 db_status 事件
+async fn update_health(app: &AppHandle, f: impl FnOnce(&mut DbHealth)) {
+    let (changed, health) = {
+        let mut state = DB_HEALTH.lock().await;
+        let before = state.health.status;
+        f(&mut state.health);
+        let changed = before != state.health.status;
+        if changed {
+            state.health.changed_at = now_secs();
+        }
+        (changed, state.health.clone())
+    };
+    if changed {
+        info!("数据库状态: {:?} {:?}", health.status, health.last_error);
+        let _ = app.emit("db_status", &health);
+    }
+}
+
+// 记录一次检查结果和连接池统计
+async fn record_check(pool: &DbPool, acquire: Option<Duration>) {
+    let mut state = DB_HEALTH.lock().await;
+    if let Some(acquire) = acquire {
+        let ms = acquire.as_secs_f64() * 1000.0;
+        if state.latencies.len() >= LATENCY_WINDOW {
+            state.latencies.remove(0);
+        }
+        state.latencies.push(ms);
+        state.health.pool.last_acquire_ms = Some(ms);
+    }
+    let count = state.latencies.len();
+    let avg = (count > 0).then(|| state.latencies.iter().sum::<f64>() / count as f64);
+    let max = state.latencies.iter().copied().reduce(f64::max);
+    let stats = &mut state.health.pool;
+    stats.size = pool.size();
+    stats.idle = pool.num_idle();
+    stats.max_connections = pool.max_connections();
+    stats.avg_acquire_ms = avg;
+    stats.max_acquire_ms = max;
+    state.health.checked_at = Some(now_secs());
+}
+
+// 读取业务数据库密码：优先使用凭据库，凭据库打开失败时使用配置中保留的明文密码
+// 两者都没有时返回错误，而不是用空密码连接
+fn db_password(app: &AppHandle) -> Result<Secret, String> {
+    let configured = app
+        .state::<AppState>()
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .password
+        .clone();
+    match load_secret(app, APP_DB_CREDENTIAL) {
+        Ok(password) if !password.is_empty() => Ok(password),
+        Ok(_) => Ok(configured),
+        Err(_) if !configured.is_empty() => Ok(configured),
+        Err(e) => Err(format!("读取数据库密码失败: {}", e)),
+    }
+}
+
+/// 按当前配置确定业务数据库的连接目标，每次连接时重新读取
+pub fn db_target(app: &AppHandle) -> Result<DbTarget, String> {
+    let (backend, sqlite, ip, username) = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (
+            config.db_backend.clone(),
+            config.sqlite_path.clone(),
+            config.ip.clone(),
+            config.username.clone(),
+        )
+    };
+    if backend == "sqlite" {
+        Ok(DbTarget::Sqlite {
+            path: sqlite_path(app, Some(sqlite))?,
+        })
+    } else {
+        Ok(DbTarget::MySql { ip, username })
+    }
+}
+
+// 连接业务数据库并设为业务数据连接池
+async fn connect_target(app: &AppHandle, target: &DbTarget) -> Result<DbPool, String> {
+    match target {
+        DbTarget::MySql { ip, username } => {
+            // 连接守护自己的服务器连接只用来创建业务库，不登记到 POOLS，
+            // 避免替换掉数据库浏览器正在使用的默认连接
+            let profile = DbProfile {
+                host: ip.clone(),
+                username: username.clone(),
+                password: db_password(app)?,
+                pool_size: 1,
+                ..Default::default()
+            };
+            let DbPool::MySql(server) = connect_profile(&profile).await? else {
+                return Err("业务数据库只支持 MySQL".to_string());
+            };
+            let result = open_draft_db(&server, "draft").await;
+            server.close().await;
+            result?;
+            get_data_pool().await.map_err(|e| e.to_string())
+        }
+        DbTarget::Sqlite { path } => connect_sqlite_file(path).await.map_err(|e| format!("{:?}", e)),
+    }
+}
+
+// 等待 delay，期间收到 reconnect_db 或重连请求时提前返回
+async fn wait_or_reconnect(delay: Duration) {
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => {}
+        _ = RECONNECT.notified() => {}
+        _ = FORCE_RECONNECT.notified() => {}
+    }
+}
+
+// 定期检查当前的业务数据连接池，generation 为开始检查时连接池的代数
+// 连接池被替换、连续失败 MAX_PING_FAILURES 次或要求重连时返回
+async fn monitor(generation: u64) -> MonitorEnd {
+    let mut failures = 0;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(PING_INTERVAL) => {}
+            _ = RECONNECT.notified() => {}
+            _ = FORCE_RECONNECT.notified() => return MonitorEnd::Reconfigure,
+        }
+        if data_pool_generation() != generation {
+            return MonitorEnd::Replaced;
+        }
+        let pool = match get_data_pool().await {
+            Ok(pool) => pool,
+            Err(e) => return MonitorEnd::Failed(e.to_string()),
+        };
+        let error = match tokio::time::timeout(PING_TIMEOUT, pool.ping()).await {
+            Ok(Ok(acquire)) => {
+                failures = 0;
+                record_check(&pool, Some(acquire)).await;
+                continue;
+            }
+            Ok(Err(e)) => format!("{:#}", e),
+            Err(_) => "数据库检查超时".to_string(),
+        };
+        record_check(&pool, None).await;
+        failures += 1;
+        eprintln!("数据库检查失败 ({}/{}): {}", failures, MAX_PING_FAILURES, error);
+        if failures >= MAX_PING_FAILURES {
+            return MonitorEnd::Failed(error);
+        }
+    }
+}
+
+async fn connect_current(app: &AppHandle) -> Result<DbPool, String> {
+    let target = db_target(app)?;
+    connect_target(app, &target).await
+}
+
+async fn supervise(app: AppHandle) {
+    let mut delay = INITIAL_BACKOFF;
+    let mut connected_once = false;
+    update_health(&app, |h| {
+        h.status = DbStatus::Connecting;
+        h.attempts = 0;
+    })
+    .await;
+    loop {
+        match connect_current(&app).await {
+            Ok(mut pool) => {
+                delay = INITIAL_BACKOFF;
+                connected_once = true;
+                let mut generation = data_pool_generation();
+                // 连接池被替换时改为检查新的连接池，不重连配置中的数据库
+                let error = loop {
+                    record_check(&pool, None).await;
+                    update_health(&app, |h| {
+                        h.status = DbStatus::Connected;
+                        h.backend = Some(pool.backend().to_string());
+                        h.attempts = 0;
+                        h.last_error = None;
+                        h.retry_in_secs = None;
+                    })
+                    .await;
+                    match monitor(generation).await {
+                        MonitorEnd::Replaced => {
+                            generation = data_pool_generation();
+                            match get_data_pool().await {
+                                Ok(current) => pool = current,
+                                Err(e) => break Some(e.to_string()),
+                            }
+                        }
+                        MonitorEnd::Failed(error) => break Some(error),
+                        MonitorEnd::Reconfigure => break None,
+                    }
+                };
+                update_health(&app, |h| {
+                    h.status = DbStatus::Reconnecting;
+                    h.last_error = error;
+                })
+                .await;
+            }
+            Err(e) => {
+                eprintln!("数据库连接失败: {}", e);
+                update_health(&app, |h| {
+                    h.status = if connected_once {
+                        DbStatus::Reconnecting
+                    } else {
+                        DbStatus::Disconnected
+                    };
+                    h.attempts += 1;
+                    h.last_error = Some(e);
+                    h.retry_in_secs = Some(delay.as_secs());
+                })
+                .await;
+                wait_or_reconnect(delay).await;
+                delay = (delay * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// 启动业务数据库的连接守护：失败时按退避时间重试，连接后定期检查，断开时按当前配置重连
+/// 只会启动一次
+pub fn start_db_supervisor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        {
+            let mut state = DB_HEALTH.lock().await;
+            if state.running {
+                return;
+            }
+            state.running = true;
+        }
+        match db_target(&app) {
+            Ok(target) => info!("数据库连接守护启动 {:?}", target_name(&target)),
+            Err(e) => eprintln!("数据库连接配置错误: {}", e),
+        }
+        supervise(app).await;
+    });
+}
+
+// 日志中只显示地址，不显示密码
+fn target_name(target: &DbTarget) -> String {
+    match target {
+        DbTarget::MySql { ip, username, .. } => format!("mysql://{}@{}", username, ip),
+        DbTarget::Sqlite { path } => format!("sqlite://{}", path.display()),
+    }
+}
+
+/// 获取业务数据库的连接状态和连接池统计
+#[command]
+pub async fn get_db_status() -> Result<DbHealth, String> {
+    let pool = get_data_pool().await;
+    let mut state = DB_HEALTH.lock().await;
+    if let Ok(pool) = pool {
+        state.health.pool.size = pool.size();
+        state.health.pool.idle = pool.num_idle();
+        state.health.pool.max_connections = pool.max_connections();
+    }
+    Ok(state.health.clone())
+}
+
+/// 让连接守护按当前配置立即重新连接，连接正常时也会重连，用于数据库密码、连接配置修改或凭据库解锁之后
+pub fn request_db_reconnect() {
+    FORCE_RECONNECT.notify_one();
+}
+
+/// 立即检查连接：断开时跳过等待立即重连，连接正常时立即 ping 一次
+#[command]
+pub async fn reconnect_db() -> Result<(), String> {
+    if !DB_HEALTH.lock().await.running {
+        return Err("数据库连接守护未启动".to_string());
+    }
+    RECONNECT.notify_one();
+    Ok(())
+}
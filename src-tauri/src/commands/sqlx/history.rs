@@ -0,0 +1,372 @@
+use crate::commands::sqlx::cell::{self, coerce_json, column_kind, Cell};
+use crate::commands::sqlx::{
+    classify, explorer, get_open_pool, guard_sql, split_statements, DbPool, ScriptDialect, StatementKind,
+    DEFAULT_PROFILE,
+};
+use crate::dao::{QueryHistoryDao, SavedQueryDao};
+use crate::dto::{HistoryQuery, Page, QueryHistory, QueryParam, SavedQuery};
+use chrono::{Local, NaiveDateTime};
+use log::{debug, warn};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::types::Json;
+use sqlx::{Executor, Row, Statement};
+use std::collections::HashSet;
+use std::time::Instant;
+use tauri::command;
+
+// 执行计划
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainPlan {
+    pub backend: String,
+    pub sql: String,
+    // MySQL 为 EXPLAIN FORMAT=JSON 的结果，PostgreSQL 为 EXPLAIN (FORMAT JSON) 的结果，
+    // SQLite 为按 parent 组装的 EXPLAIN QUERY PLAN 节点树
+    pub plan: Value,
+}
+
+/// 记录查询历史，outcome 为返回或影响的行数；业务库未连接或写入失败时只打印日志，不影响查询本身
+pub async fn record_history(
+    profile: Option<&str>,
+    db_name: Option<&str>,
+    sql: &str,
+    start: Instant,
+    outcome: Result<u64, &str>,
+) {
+    let history = QueryHistory {
+        id: None,
+        profile: profile
+            .filter(|p| !p.is_empty())
+            .unwrap_or(DEFAULT_PROFILE)
+            .to_string(),
+        db_name: db_name.filter(|d| !d.is_empty()).map(str::to_string),
+        sql_text: sql.to_string(),
+        duration_ms: start.elapsed().as_millis() as i64,
+        row_count: outcome.ok().map(|n| n as i64),
+        error: outcome.err().map(str::to_string),
+        created_at: Local::now().naive_local(),
+    };
+    if let Err(e) = QueryHistoryDao::record(&history).await {
+        warn!("记录查询历史失败: {:?}", e);
+    }
+}
+
+/// 搜索查询历史
+#[command]
+pub async fn search_query_history(
+    query: HistoryQuery,
+    current_page: i32,
+    page_size: i32,
+) -> Result<Page<QueryHistory>, String> {
+    QueryHistoryDao::search(&query, current_page.max(1), page_size.max(1))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 删除指定的查询历史
+#[command]
+pub async fn delete_query_history(ids: Vec<i64>) -> Result<u64, String> {
+    QueryHistoryDao::deletes(&ids).await.map_err(|e| e.to_string())
+}
+
+/// 清除 before 之前的查询历史，before 为空时全部清除
+#[command]
+pub async fn clear_query_history(before: Option<NaiveDateTime>) -> Result<u64, String> {
+    QueryHistoryDao::clear(before).await.map_err(|e| e.to_string())
+}
+
+/// 找出 SQL 中的 :name 参数，返回替换为占位符后的 SQL 和按出现顺序排列的参数名
+/// 引号、注释中的内容以及 PostgreSQL 的 :: 类型转换不作为参数
+fn named_params(sql: &str, postgres: bool) -> (String, Vec<String>) {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                // 原样复制到配对的引号，两个连续引号为转义
+                out.push(c);
+                i += 1;
+                while i < chars.len() {
+                    out.push(chars[i]);
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            out.push(c);
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    if chars[i] == '\\' && c != '`' {
+                        if let Some(&next) = chars.get(i + 1) {
+                            out.push(next);
+                            i += 1;
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let end = (i + 2..chars.len().saturating_sub(1))
+                    .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                    .map_or(chars.len(), |j| j + 2);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            ':' if chars.get(i + 1) == Some(&':') => {
+                out.push_str("::");
+                i += 2;
+            }
+            ':' if chars
+                .get(i + 1)
+                .is_some_and(|n| n.is_alphabetic() || *n == '_') =>
+            {
+                let end = (i + 1..chars.len())
+                    .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+                    .unwrap_or(chars.len());
+                names.push(chars[i + 1..end].iter().collect());
+                out.push_str(&cell::placeholder(postgres, names.len()));
+                i = end;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    (out, names)
+}
+
+// 检查 SQL 中引用的参数都已定义，参数名不能重复
+fn check_params(sql: &str, params: &[QueryParam]) -> Result<(), String> {
+    let mut defined = HashSet::new();
+    for param in params {
+        if !defined.insert(param.name.as_str()) {
+            return Err(format!("参数 {} 重复定义", param.name));
+        }
+    }
+    let (_, names) = named_params(sql, false);
+    if let Some(name) = names.iter().find(|n| !defined.contains(n.as_str())) {
+        return Err(format!("参数 :{} 没有定义", name));
+    }
+    Ok(())
+}
+
+/// 保存查询，id 为空时新建，返回查询 id
+#[command]
+pub async fn save_query(mut query: SavedQuery) -> Result<i64, String> {
+    if query.name.trim().is_empty() {
+        return Err("查询名称不能为空".to_string());
+    }
+    // 按默认执行连接的数据库拆分，连接未打开时按 MySQL 拆分
+    let dialect = get_open_pool(query.profile.as_deref())
+        .await
+        .map_or(ScriptDialect::MySql, |open| ScriptDialect::of(&open.pool));
+    if split_statements(&query.sql_text, dialect).len() != 1 {
+        return Err("保存的查询只能包含一条语句".to_string());
+    }
+    check_params(&query.sql_text, &query.params)?;
+    query.name = query.name.trim().to_string();
+    query.updated_at = Local::now().naive_local();
+    SavedQueryDao::save(&query).await.map_err(|e| e.to_string())
+}
+
+/// 列出保存的查询
+#[command]
+pub async fn list_saved_queries(keyword: Option<String>) -> Result<Vec<SavedQuery>, String> {
+    SavedQueryDao::list(keyword.as_deref()).await.map_err(|e| e.to_string())
+}
+
+/// 删除保存的查询
+#[command]
+pub async fn delete_saved_query(id: i64) -> Result<bool, String> {
+    SavedQueryDao::delete(id).await.map_err(|e| e.to_string())
+}
+
+// 按参数定义转换参数值，未传值时使用默认值
+fn param_cells(defs: &[QueryParam], names: &[String], values: &Map<String, Value>) -> Result<Vec<Cell>, String> {
+    names
+        .iter()
+        .map(|name| {
+            let def = defs
+                .iter()
+                .find(|p| &p.name == name)
+                .ok_or_else(|| format!("参数 :{} 没有定义", name))?;
+            let value = match values.get(name).or(def.default.as_ref()) {
+                Some(value) => value,
+                None if def.required => return Err(format!("缺少参数 {}", name)),
+                None => &Value::Null,
+            };
+            coerce_json(value, column_kind(&def.kind)).map_err(|e| format!("参数 {}: {}", name, e))
+        })
+        .collect()
+}
+
+/// 执行保存的查询，params 为参数值；查询返回 { columns, data }，修改语句返回 { num }
+/// profile 为空时使用查询保存的连接配置
+#[command]
+pub async fn run_saved_query(
+    name: &str,
+    params: Map<String, Value>,
+    profile: Option<String>,
+    confirm_token: Option<String>,
+) -> Result<Value, String> {
+    let saved = SavedQueryDao::get_by_name(name)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("保存的查询 {} 不存在", name))?;
+    let profile = profile.or(saved.profile.clone());
+    let start = Instant::now();
+    let pool = guard_sql(profile.as_deref(), &saved.sql_text, confirm_token.as_deref()).await?;
+    let read = classify(&pool, &saved.sql_text)
+        .iter()
+        .all(|s| s.kind == StatementKind::Read);
+    let (sql, names) = named_params(&saved.sql_text, matches!(pool, DbPool::Postgres(_)));
+    let cells = param_cells(&saved.params, &names, &params)?;
+    debug!("run saved query {}: {}", name, sql);
+
+    macro_rules! run {
+        ($pool:expr, $bind:path, $to_json:path) => {{
+            let mut query = sqlx::query(&sql);
+            for cell in &cells {
+                query = $bind(query, cell);
+            }
+            if read {
+                let rows = query.fetch_all($pool).await.map_err(|e| e.to_string())?;
+                let columns = match rows.first() {
+                    Some(row) => explorer::column_json(row.columns()),
+                    None => explorer::column_json($pool.prepare(&sql).await.map_err(|e| e.to_string())?.columns()),
+                };
+                let data: Vec<Value> = rows.iter().map($to_json).collect();
+                Ok(json!({ "columns": columns, "data": data }))
+            } else {
+                let res = query.execute($pool).await.map_err(|e| e.to_string())?;
+                Ok(json!({ "num": res.rows_affected() }))
+            }
+        }};
+    }
+    let result: Result<Value, String> = match &pool {
+        DbPool::MySql(pool) => run!(pool, cell::bind_mysql, explorer::mysql_row_to_json),
+        DbPool::Postgres(pool) => run!(pool, cell::bind_pg, explorer::pg_row_to_json),
+        DbPool::Sqlite(pool) => run!(pool, cell::bind_sqlite, explorer::sqlite_row_to_json),
+    };
+    let outcome = match &result {
+        Ok(value) => Ok(value["data"]
+            .as_array()
+            .map_or_else(|| value["num"].as_u64().unwrap_or(0), |d| d.len() as u64)),
+        Err(e) => Err(e.as_str()),
+    };
+    record_history(profile.as_deref(), None, &saved.sql_text, start, outcome).await;
+    result
+}
+
+// 把 EXPLAIN QUERY PLAN 的 (id, parent, detail) 行组装为节点树
+fn sqlite_plan_tree(rows: &[(i64, i64, String)], parent: i64) -> Value {
+    Value::Array(
+        rows.iter()
+            .filter(|(_, p, _)| *p == parent)
+            .map(|(id, _, detail)| {
+                json!({ "id": id, "detail": detail, "children": sqlite_plan_tree(rows, *id) })
+            })
+            .collect(),
+    )
+}
+
+/// 返回语句的执行计划，只解析计划不执行语句
+#[command]
+pub async fn explain_query(sql: &str, profile: Option<String>) -> Result<ExplainPlan, String> {
+    let open = get_open_pool(profile.as_deref())
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    let statements = split_statements(sql, ScriptDialect::of(&open.pool));
+    let [stmt] = statements.as_slice() else {
+        return Err("只能查看单条语句的执行计划".to_string());
+    };
+    let info = classify(&open.pool, stmt);
+    if let Some(s) = info
+        .iter()
+        .find(|s| !matches!(s.kind, StatementKind::Read | StatementKind::Dml))
+    {
+        return Err(format!("不能查看 {} 语句的执行计划", s.keyword));
+    }
+    let plan = match &open.pool {
+        DbPool::MySql(pool) => {
+            let row = sqlx::query(&format!("EXPLAIN FORMAT=JSON {}", stmt))
+                .fetch_one(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            let text: String = row.try_get(0).map_err(|e| e.to_string())?;
+            serde_json::from_str(&text).map_err(|e| e.to_string())?
+        }
+        DbPool::Postgres(pool) => {
+            let row = sqlx::query(&format!("EXPLAIN (FORMAT JSON) {}", stmt))
+                .fetch_one(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            row.try_get::<Json<Value>, _>(0).map_err(|e| e.to_string())?.0
+        }
+        DbPool::Sqlite(pool) => {
+            let rows = sqlx::query(&format!("EXPLAIN QUERY PLAN {}", stmt))
+                .fetch_all(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            let nodes = rows
+                .iter()
+                .map(|r| Ok((r.try_get(0)?, r.try_get(1)?, r.try_get(3)?)))
+                .collect::<Result<Vec<(i64, i64, String)>, sqlx::Error>>()
+                .map_err(|e| e.to_string())?;
+            sqlite_plan_tree(&nodes, 0)
+        }
+    };
+    Ok(ExplainPlan {
+        backend: open.pool.backend().to_string(),
+        sql: stmt.clone(),
+        plan,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_params_become_placeholders_in_order() {
+        let (sql, names) = named_params("SELECT * FROM t WHERE a = :a AND b > :min_b OR a = :a", false);
+        assert_eq!(sql, "SELECT * FROM t WHERE a = ? AND b > ? OR a = ?");
+        assert_eq!(names, vec!["a", "min_b", "a"]);
+
+        let (sql, names) = named_params("SELECT :id::int", true);
+        assert_eq!(sql, "SELECT $1::int");
+        assert_eq!(names, vec!["id"]);
+    }
+
+    #[test]
+    fn named_params_skip_strings_and_comments() {
+        let input = "SELECT ':a', \"x:b\", 'it''s :c', 'e\\':d' -- :e\n/* :f */ FROM t WHERE x = :g";
+        let (sql, names) = named_params(input, false);
+        assert_eq!(names, vec!["g"]);
+        assert_eq!(sql, input.replace(":g", "?"));
+    }
+
+    #[test]
+    fn check_params_requires_definitions() {
+        let param = |name: &str| QueryParam {
+            name: name.to_string(),
+            kind: "varchar".to_string(),
+            default: None,
+            required: false,
+        };
+        assert!(check_params("SELECT :a", &[param("a")]).is_ok());
+        assert!(check_params("SELECT :a, :b", &[param("a")]).is_err());
+        assert!(check_params("SELECT 1", &[param("a"), param("a")]).is_err());
+    }
+}
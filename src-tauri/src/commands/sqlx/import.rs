@@ -0,0 +1,546 @@
+use crate::commands::sqlx::cell::{self, coerce, column_kind, Cell, ColumnKind};
+use crate::commands::sqlx::{explorer, get_open_pool, DbPool};
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::{NaiveDate, NaiveTime};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sqlx::Acquire;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+// 每个事务默认插入的行数
+const DEFAULT_BATCH_SIZE: usize = 500;
+// 单条语句的参数个数上限（MySQL 65535，SQLite 32766，留出余量）
+const MAX_PARAMS: usize = 30000;
+// 报告中最多保留的错误数，超出部分只计数
+const MAX_ERRORS: usize = 1000;
+// 读取线程与插入之间缓冲的最大行数
+const READ_CHANNEL_ROWS: usize = 2000;
+
+// 导入参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportOptions {
+    // CSV（.csv/.tsv/.txt）或 Excel（.xlsx/.xlsm/.xls/.ods）文件
+    pub path: String,
+    pub db_name: String,
+    pub table_name: String,
+    // Excel 工作表名，默认第一个
+    #[serde(default)]
+    pub sheet: Option<String>,
+    // 第一行是否为表头，默认 true
+    #[serde(default)]
+    pub has_header: Option<bool>,
+    // 文件列名到表字段的映射（无表头时键为从 1 开始的列序号），值为空表示忽略该列
+    // 未指定的列按表头与字段名匹配（忽略大小写），无表头时按位置对应
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    // CSV 分隔符，默认逗号（.tsv 为制表符）
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    // 空单元格是否作为 NULL，默认 true；非文本字段的空单元格总是 NULL
+    #[serde(default)]
+    pub empty_as_null: Option<bool>,
+    // 试运行：完整执行类型转换和插入，最后回滚
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// 文件列与表字段的对应关系
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnMapping {
+    pub source: String,
+    pub column: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+}
+
+// 出错的行，row 为文件中的行号（从 1 开始，含表头）
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowError {
+    pub row: u64,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+// 导入进度，文件逐行读取，总行数未知，按已读取的字节数估算进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub table: String,
+    pub rows: u64,
+    pub imported: u64,
+    pub failed: u64,
+    pub bytes: u64,
+    pub total_bytes: u64,
+}
+
+// 文件中的一行：行号（从 1 开始，含表头）、已读取的字节数、单元格文本
+struct SourceRow {
+    row: u64,
+    bytes: u64,
+    values: Vec<String>,
+}
+
+// 与表字段对应的文件列
+struct MappedColumn {
+    // 文件列序号
+    index: usize,
+    column: String,
+    data_type: String,
+}
+
+// 导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub table: String,
+    pub columns: Vec<ColumnMapping>,
+    // 没有对应字段而被忽略的文件列
+    pub ignored: Vec<String>,
+    pub total: u64,
+    pub imported: u64,
+    pub failed: u64,
+    pub errors: Vec<ImportRowError>,
+    pub dry_run: bool,
+    pub elapsed_ms: u64,
+}
+
+/// 在阻塞线程中逐行读取文件，单元格统一为文本，接收端关闭时停止读取
+fn read_rows(
+    path: &str,
+    sheet: Option<&str>,
+    delimiter: Option<char>,
+    tx: mpsc::Sender<SourceRow>,
+) -> Result<(), String> {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => read_excel(path, sheet, tx),
+        _ => {
+            let delimiter = delimiter.unwrap_or(if ext == "tsv" { '\t' } else { ',' });
+            read_csv(path, delimiter, tx)
+        }
+    }
+}
+
+fn read_csv(path: &str, delimiter: char, tx: mpsc::Sender<SourceRow>) -> Result<(), String> {
+    if !delimiter.is_ascii() {
+        return Err(format!("不支持的分隔符: {}", delimiter));
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter as u8)
+        .from_path(path)
+        .map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut record = csv::StringRecord::new();
+    let mut row: u64 = 0;
+    while reader
+        .read_record(&mut record)
+        .map_err(|e| format!("读取 CSV 失败: {}", e))?
+    {
+        row += 1;
+        let mut values: Vec<String> = record.iter().map(|s| s.to_string()).collect();
+        // 去掉 Excel 导出的 UTF-8 BOM
+        if row == 1 {
+            if let Some(first) = values.first_mut() {
+                if let Some(stripped) = first.strip_prefix('\u{feff}') {
+                    *first = stripped.to_string();
+                }
+            }
+        }
+        let bytes = reader.position().byte();
+        if tx.blocking_send(SourceRow { row, bytes, values }).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// 工作表只能整体加载，加载后逐行发送
+fn read_excel(path: &str, sheet: Option<&str>, tx: mpsc::Sender<SourceRow>) -> Result<(), String> {
+    let mut workbook = open_workbook_auto(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    let range = match sheet {
+        Some(name) => workbook.worksheet_range(name),
+        None => workbook
+            .worksheet_range_at(0)
+            .ok_or_else(|| "文件中没有工作表".to_string())?,
+    }
+    .map_err(|e| format!("读取工作表失败: {}", e))?;
+    let bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    for (i, cells) in range.rows().enumerate() {
+        let values = cells.iter().map(excel_cell_text).collect();
+        if tx.blocking_send(SourceRow { row: i as u64 + 1, bytes, values }).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn excel_cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Int(v) => v.to_string(),
+        Data::Float(v) => v.to_string(),
+        Data::Bool(v) => v.to_string(),
+        Data::DateTime(v) => match v.as_datetime() {
+            Some(d) if d.time() == NaiveTime::MIN => d.format("%Y-%m-%d").to_string(),
+            Some(d) if d.date() <= NaiveDate::from_ymd_opt(1899, 12, 31).unwrap() => {
+                // 只有时间的单元格
+                d.format("%H:%M:%S").to_string()
+            }
+            Some(d) => d.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => v.as_f64().to_string(),
+        },
+        Data::Error(e) => e.to_string(),
+    }
+}
+
+// 文件列到表字段的映射，返回对应的列和被忽略的列
+fn map_columns(
+    headers: &[String],
+    has_header: bool,
+    mapping: &HashMap<String, String>,
+    table_columns: &[(String, String)],
+) -> Result<(Vec<MappedColumn>, Vec<String>), String> {
+    let find = |name: &str| {
+        table_columns
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(name.trim()))
+    };
+    let mut mapped: Vec<MappedColumn> = Vec::new();
+    let mut ignored = Vec::new();
+    for (i, header) in headers.iter().enumerate() {
+        let target = match mapping.get(header) {
+            Some(target) if target.is_empty() => None,
+            Some(target) => Some(
+                find(target).ok_or_else(|| format!("表中没有字段 {}", target))?,
+            ),
+            None if has_header => find(header),
+            None => table_columns.get(i),
+        };
+        match target {
+            Some((column, data_type)) => {
+                if mapped.iter().any(|m| &m.column == column) {
+                    return Err(format!("字段 {} 被映射了多次", column));
+                }
+                mapped.push(MappedColumn {
+                    index: i,
+                    column: column.clone(),
+                    data_type: data_type.clone(),
+                });
+            }
+            None => ignored.push(header.clone()),
+        }
+    }
+    if mapped.is_empty() {
+        return Err("文件中没有与表字段对应的列".to_string());
+    }
+    Ok((mapped, ignored))
+}
+
+// 多行 INSERT 语句
+fn insert_sql(pool: &DbPool, table: &str, columns: &[String], rows: usize) -> String {
+    let names: Vec<String> = columns.iter().map(|c| explorer::quote_ident(pool, c)).collect();
+    let mut values = Vec::with_capacity(rows);
+    let mut n = 0;
+    for _ in 0..rows {
+        let placeholders: Vec<String> = columns
+            .iter()
+            .map(|_| {
+                n += 1;
+                match pool {
+                    DbPool::Postgres(_) => cell::placeholder(true, n),
+                    _ => cell::placeholder(false, n),
+                }
+            })
+            .collect();
+        values.push(format!("({})", placeholders.join(", ")));
+    }
+    format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        names.join(", "),
+        values.join(", ")
+    )
+}
+
+fn record_error(report: &mut ImportReport, error: ImportRowError) {
+    report.failed += 1;
+    if report.errors.len() < MAX_ERRORS {
+        report.errors.push(error);
+    }
+}
+
+/// 导入 CSV / Excel 到表，进度通过 progress 回调上报
+/// 文件边读边导入，读取中途出错时已提交的批次不会回滚
+pub async fn run_import<F>(
+    profile: Option<&str>,
+    options: &ImportOptions,
+    mut progress: F,
+) -> Result<ImportReport, String>
+where
+    F: FnMut(&ImportProgress) -> Result<(), String>,
+{
+    let start = Instant::now();
+    let open = get_open_pool(profile)
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    if open.read_only {
+        return Err("只读连接不能导入数据".to_string());
+    }
+    let pool = open.pool;
+
+    let table_columns = explorer::list_columns(&pool, &options.db_name, &options.table_name).await?;
+    if table_columns.is_empty() {
+        return Err(format!("表 {} 不存在或没有字段", options.table_name));
+    }
+    let table = explorer::qualified_table(&pool, &options.db_name, &options.table_name).await?;
+
+    let path = options.path.clone();
+    let sheet = options.sheet.clone();
+    let delimiter = options.delimiter;
+    let total_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let (tx, mut rx) = mpsc::channel::<SourceRow>(READ_CHANNEL_ROWS);
+    let reader = tauri::async_runtime::spawn_blocking(move || read_rows(&path, sheet.as_deref(), delimiter, tx));
+
+    let has_header = options.has_header.unwrap_or(true);
+    let Some(first) = rx.recv().await else {
+        reader.await.map_err(|e| e.to_string())??;
+        return Err("文件中没有数据".to_string());
+    };
+    // 无表头时按列序号命名，宽度取第一行和映射中最大的序号
+    let (headers, mut pending) = if has_header {
+        (first.values, None)
+    } else {
+        let width = options
+            .mapping
+            .keys()
+            .filter_map(|k| k.parse::<usize>().ok())
+            .fold(first.values.len(), usize::max);
+        ((1..=width).map(|i| i.to_string()).collect(), Some(first))
+    };
+    let (mapped, ignored) = map_columns(&headers, has_header, &options.mapping, &table_columns)?;
+    let kinds: Vec<ColumnKind> = mapped.iter().map(|m| column_kind(&m.data_type)).collect();
+    let columns: Vec<String> = mapped.iter().map(|m| m.column.clone()).collect();
+    let empty_as_null = options.empty_as_null.unwrap_or(true);
+    let batch_size = options
+        .batch_size
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+        .clamp(1, (MAX_PARAMS / columns.len()).max(1));
+
+    let mut report = ImportReport {
+        table: options.table_name.clone(),
+        columns: mapped
+            .iter()
+            .map(|m| ColumnMapping {
+                source: headers[m.index].clone(),
+                column: m.column.clone(),
+                data_type: m.data_type.clone(),
+            })
+            .collect(),
+        ignored,
+        total: 0,
+        imported: 0,
+        failed: 0,
+        errors: Vec::new(),
+        dry_run: options.dry_run,
+        elapsed_ms: 0,
+    };
+    let batch_sql = insert_sql(&pool, &table, &columns, batch_size);
+    let single_sql = insert_sql(&pool, &table, &columns, 1);
+
+    // 每批一个事务；整批插入失败时在保存点中逐行插入，找出出错的行
+    macro_rules! insert_batch {
+        ($pool:expr, $batch:expr, $bind:path) => {{
+            let batch: &[(u64, Vec<Cell>)] = $batch;
+            let mut tx = $pool.begin().await.map_err(|e| e.to_string())?;
+            let sql = if batch.len() == batch_size {
+                batch_sql.clone()
+            } else {
+                insert_sql(&pool, &table, &columns, batch.len())
+            };
+            let mut savepoint = tx.begin().await.map_err(|e| e.to_string())?;
+            let mut query = sqlx::query(&sql);
+            for cell in batch.iter().flat_map(|(_, c)| c) {
+                query = $bind(query, cell);
+            }
+            match query.execute(&mut *savepoint).await {
+                Ok(_) => {
+                    savepoint.commit().await.map_err(|e| e.to_string())?;
+                    report.imported += batch.len() as u64;
+                }
+                Err(e) => {
+                    warn!("批量插入 {} 行失败，逐行重试: {}", batch.len(), e);
+                    savepoint.rollback().await.map_err(|e| e.to_string())?;
+                    for (row, cells) in batch {
+                        let mut savepoint = tx.begin().await.map_err(|e| e.to_string())?;
+                        let mut query = sqlx::query(&single_sql);
+                        for cell in cells {
+                            query = $bind(query, cell);
+                        }
+                        match query.execute(&mut *savepoint).await {
+                            Ok(_) => {
+                                savepoint.commit().await.map_err(|e| e.to_string())?;
+                                report.imported += 1;
+                            }
+                            Err(e) => {
+                                savepoint.rollback().await.map_err(|e| e.to_string())?;
+                                record_error(
+                                    &mut report,
+                                    ImportRowError {
+                                        row: *row,
+                                        column: None,
+                                        message: e.to_string(),
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            if options.dry_run {
+                tx.rollback().await.map_err(|e| e.to_string())?;
+            } else {
+                tx.commit().await.map_err(|e| e.to_string())?;
+            }
+        }};
+    }
+
+    let mut processed: u64 = 0;
+    let mut bytes: u64 = 0;
+    loop {
+        // 凑满一批，跳过整行为空的行（常见于文件末尾）
+        let mut chunk: Vec<SourceRow> = Vec::with_capacity(batch_size);
+        while chunk.len() < batch_size {
+            let next = match pending.take() {
+                Some(source) => Some(source),
+                None => rx.recv().await,
+            };
+            let Some(source) = next else {
+                break;
+            };
+            bytes = source.bytes;
+            if source.values.iter().any(|c| !c.trim().is_empty()) {
+                chunk.push(source);
+            }
+        }
+        if chunk.is_empty() {
+            break;
+        }
+        report.total += chunk.len() as u64;
+
+        // 先转换类型，转换失败的行直接记为错误
+        let mut batch: Vec<(u64, Vec<Cell>)> = Vec::with_capacity(chunk.len());
+        for source in &chunk {
+            let mut cells = Vec::with_capacity(mapped.len());
+            let mut error = None;
+            for (m, kind) in mapped.iter().zip(&kinds) {
+                let value = source.values.get(m.index).map(String::as_str).unwrap_or("");
+                match coerce(value, *kind, empty_as_null) {
+                    Ok(cell) => cells.push(cell),
+                    Err(message) => {
+                        error = Some(ImportRowError {
+                            row: source.row,
+                            column: Some(m.column.clone()),
+                            message,
+                        });
+                        break;
+                    }
+                }
+            }
+            match error {
+                Some(error) => record_error(&mut report, error),
+                None => batch.push((source.row, cells)),
+            }
+        }
+
+        if !batch.is_empty() {
+            match &pool {
+                DbPool::MySql(p) => insert_batch!(p, &batch, cell::bind_mysql),
+                DbPool::Postgres(p) => insert_batch!(p, &batch, cell::bind_pg),
+                DbPool::Sqlite(p) => insert_batch!(p, &batch, cell::bind_sqlite),
+            }
+        }
+
+        processed += chunk.len() as u64;
+        progress(&ImportProgress {
+            table: options.table_name.clone(),
+            rows: processed,
+            imported: report.imported,
+            failed: report.failed,
+            bytes,
+            total_bytes,
+        })?;
+    }
+    // 接收端已读完，读取线程的错误在这里返回
+    reader.await.map_err(|e| e.to_string())??;
+
+    report.elapsed_ms = start.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// 从 CSV / Excel 导入数据到表，进度通过 import_progress 事件推送
+/// dry_run 为 true 时只校验，不保留数据
+#[command]
+pub async fn import_table_data(
+    app_handle: AppHandle,
+    options: ImportOptions,
+    profile: Option<String>,
+) -> Result<ImportReport, String> {
+    let report = run_import(profile.as_deref(), &options, |p| {
+        app_handle.emit("import_progress", p).map_err(|e| e.to_string())
+    })
+    .await?;
+    info!(
+        "import {} total {} imported {} failed {} dry_run {}",
+        report.table, report.total, report.imported, report.failed, report.dry_run
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_csv_rows_one_by_one() {
+        let path = std::env::temp_dir().join(format!("draft_import_{}.csv", std::process::id()));
+        std::fs::write(&path, "\u{feff}title,price\nrust,12.5\n\n\"a,b\",3\n").unwrap();
+        let (tx, mut rx) = mpsc::channel(READ_CHANNEL_ROWS);
+        read_rows(path.to_str().unwrap(), None, None, tx).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut rows = Vec::new();
+        while let Ok(source) = rx.try_recv() {
+            rows.push((source.row, source.values));
+        }
+        assert_eq!(rows[0], (1, vec!["title".to_string(), "price".to_string()]));
+        assert_eq!(rows.last().unwrap().1, vec!["a,b".to_string(), "3".to_string()]);
+    }
+
+    #[test]
+    fn maps_columns_by_header_or_position() {
+        let table = vec![
+            ("title".to_string(), "varchar".to_string()),
+            ("price".to_string(), "double".to_string()),
+        ];
+        let headers = vec!["Title".to_string(), "note".to_string(), "cost".to_string()];
+        let mapping = HashMap::from([("cost".to_string(), "price".to_string())]);
+        let (mapped, ignored) = map_columns(&headers, true, &mapping, &table).unwrap();
+        let pairs: Vec<(usize, &str)> = mapped.iter().map(|m| (m.index, m.column.as_str())).collect();
+        assert_eq!(pairs, vec![(0, "title"), (2, "price")]);
+        assert_eq!(ignored, vec!["note".to_string()]);
+
+        let positional = vec!["1".to_string(), "2".to_string()];
+        let (mapped, _) = map_columns(&positional, false, &HashMap::new(), &table).unwrap();
+        assert_eq!(mapped[1].column, "price");
+    }
+}
@@ -0,0 +1,116 @@
+use crate::commands::sqlx::{get_data_pool, DbPool};
+use log::info;
+use serde::Serialize;
+use sqlx::migrate::{Migrate, Migrator};
+use tauri::command;
+
+// 迁移文件位于 src-tauri/migrations/<后端>/，文件名为 <版本>_<描述>.up.sql / .down.sql
+// 0001 为基线迁移（<版本>_<描述>.sql，没有回滚脚本），其中的表在已有数据库中可能早已存在，不允许回滚
+// 已执行的迁移及其校验和记录在 _sqlx_migrations 表中，已执行的文件被修改时迁移会报错
+static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("./migrations/mysql");
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+// 迁移状态
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+    pub checksum: String,
+    pub applied: bool,
+    // 已执行的迁移与当前文件的校验和是否一致
+    pub checksum_ok: bool,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 执行全部未执行的迁移
+pub async fn run_migrations(pool: &DbPool) -> Result<(), String> {
+    let res = match pool {
+        DbPool::MySql(pool) => MYSQL_MIGRATOR.run(pool).await,
+        DbPool::Sqlite(pool) => SQLITE_MIGRATOR.run(pool).await,
+        DbPool::Postgres(_) => return Err("业务数据暂不支持 PostgreSQL".to_string()),
+    };
+    res.map_err(|e| format!("数据库迁移失败: {}", e))?;
+    info!("数据库迁移完成 ({})", pool.backend());
+    Ok(())
+}
+
+// 列出迁移，applied 为已执行的版本及校验和
+async fn migration_status(pool: &DbPool) -> Result<(Vec<MigrationInfo>, &'static Migrator), String> {
+    let (applied, migrator) = match pool {
+        DbPool::MySql(pool) => {
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            conn.ensure_migrations_table().await.map_err(|e| e.to_string())?;
+            let applied = conn.list_applied_migrations().await.map_err(|e| e.to_string())?;
+            (applied, &MYSQL_MIGRATOR)
+        }
+        DbPool::Sqlite(pool) => {
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            conn.ensure_migrations_table().await.map_err(|e| e.to_string())?;
+            let applied = conn.list_applied_migrations().await.map_err(|e| e.to_string())?;
+            (applied, &SQLITE_MIGRATOR)
+        }
+        DbPool::Postgres(_) => return Err("业务数据暂不支持 PostgreSQL".to_string()),
+    };
+    let list = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| {
+            let applied = applied.iter().find(|a| a.version == m.version);
+            MigrationInfo {
+                version: m.version,
+                description: m.description.to_string(),
+                checksum: hex(&m.checksum),
+                applied: applied.is_some(),
+                checksum_ok: applied.map_or(true, |a| a.checksum == m.checksum),
+            }
+        })
+        .collect();
+    Ok((list, migrator))
+}
+
+/// 列出业务库的迁移及执行状态
+#[command]
+pub async fn list_migrations() -> Result<Vec<MigrationInfo>, String> {
+    let pool = get_data_pool().await.map_err(|e| e.to_string())?;
+    Ok(migration_status(&pool).await?.0)
+}
+
+/// 回滚最后一个已执行的迁移，返回被回滚的迁移
+#[command]
+pub async fn rollback_migration() -> Result<Option<MigrationInfo>, String> {
+    let pool = get_data_pool().await.map_err(|e| e.to_string())?;
+    let (list, migrator) = migration_status(&pool).await?;
+    let applied: Vec<&MigrationInfo> = list.iter().filter(|m| m.applied).collect();
+    let Some(last) = applied.last() else {
+        return Ok(None);
+    };
+    // 没有回滚脚本的迁移（基线）不能回滚，否则会误删迁移前就存在的表
+    let reversible = migrator
+        .iter()
+        .any(|m| m.version == last.version && m.migration_type.is_down_migration());
+    if !reversible {
+        return Err(format!(
+            "迁移 {} {} 没有回滚脚本，不能回滚",
+            last.version, last.description
+        ));
+    }
+    // undo 会回滚版本号大于 target 的全部迁移
+    let target = applied
+        .len()
+        .checked_sub(2)
+        .map_or(0, |i| applied[i].version);
+    let res = match &pool {
+        DbPool::MySql(pool) => migrator.undo(pool, target).await,
+        DbPool::Sqlite(pool) => migrator.undo(pool, target).await,
+        DbPool::Postgres(_) => return Err("业务数据暂不支持 PostgreSQL".to_string()),
+    };
+    res.map_err(|e| format!("回滚迁移失败: {}", e))?;
+    info!("已回滚迁移 {} {}", last.version, last.description);
+    Ok(Some(MigrationInfo {
+        applied: false,
+        ..(*last).clone()
+    }))
+}
@@ -0,0 +1,150 @@
+use crate::commands::sqlx::run_migrations;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use sqlx::mysql::MySqlPool;
+use sqlx::postgres::PgPool;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use sqlx::Connection;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+// 连接池，可以是 MySQL、PostgreSQL 或嵌入式 SQLite
+// 业务数据（DAO）只支持 MySQL 和 SQLite，PostgreSQL 目前只用于数据库浏览器
+#[derive(Debug, Clone)]
+pub enum DbPool {
+    MySql(MySqlPool),
+    Postgres(PgPool),
+    Sqlite(SqlitePool),
+}
+
+impl DbPool {
+    pub fn backend(&self) -> &'static str {
+        match self {
+            DbPool::MySql(_) => "mysql",
+            DbPool::Postgres(_) => "postgres",
+            DbPool::Sqlite(_) => "sqlite",
+        }
+    }
+
+    /// 当前连接数
+    pub fn size(&self) -> u32 {
+        match self {
+            DbPool::MySql(p) => p.size(),
+            DbPool::Postgres(p) => p.size(),
+            DbPool::Sqlite(p) => p.size(),
+        }
+    }
+
+    /// 空闲连接数
+    pub fn num_idle(&self) -> usize {
+        match self {
+            DbPool::MySql(p) => p.num_idle(),
+            DbPool::Postgres(p) => p.num_idle(),
+            DbPool::Sqlite(p) => p.num_idle(),
+        }
+    }
+
+    /// 最大连接数
+    pub fn max_connections(&self) -> u32 {
+        match self {
+            DbPool::MySql(p) => p.options().get_max_connections(),
+            DbPool::Postgres(p) => p.options().get_max_connections(),
+            DbPool::Sqlite(p) => p.options().get_max_connections(),
+        }
+    }
+
+    /// 关闭连接池，等待借出的连接归还后返回
+    pub async fn close(&self) {
+        match self {
+            DbPool::MySql(p) => p.close().await,
+            DbPool::Postgres(p) => p.close().await,
+            DbPool::Sqlite(p) => p.close().await,
+        }
+    }
+
+    /// 从连接池取一个连接并 ping，返回取连接的耗时
+    pub async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        macro_rules! ping {
+            ($pool:expr) => {{
+                let mut conn = $pool.acquire().await.context("Failed to acquire connection")?;
+                let elapsed = start.elapsed();
+                conn.ping().await.context("Failed to ping database")?;
+                elapsed
+            }};
+        }
+        let elapsed = match self {
+            DbPool::MySql(p) => ping!(p),
+            DbPool::Postgres(p) => ping!(p),
+            DbPool::Sqlite(p) => ping!(p),
+        };
+        Ok(elapsed)
+    }
+}
+
+// DAO 使用的全局连接池
+pub static DATA_POOL: Lazy<Mutex<Option<DbPool>>> = Lazy::new(|| Mutex::new(None));
+// 业务数据连接池每替换一次加一，内存中的缓存据此判断是否来自当前数据库
+static DATA_POOL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 当前业务数据连接池的代数
+pub fn data_pool_generation() -> u64 {
+    DATA_POOL_GENERATION.load(Ordering::SeqCst)
+}
+
+/// 按后端分发执行同一段代码，两个分支里的 `$p` 分别是 MySqlPool 和 SqlitePool
+/// 业务 SQL 不兼容 PostgreSQL，遇到时直接返回错误（所在函数需返回 anyhow::Result）
+#[macro_export]
+macro_rules! with_pool {
+    ($pool:expr, $p:ident => $body:expr) => {
+        match $pool {
+            $crate::commands::sqlx::DbPool::MySql($p) => $body,
+            $crate::commands::sqlx::DbPool::Sqlite($p) => $body,
+            $crate::commands::sqlx::DbPool::Postgres(_) => {
+                anyhow::bail!("业务数据暂不支持 PostgreSQL")
+            }
+        }
+    };
+}
+
+/// 获取业务数据连接池
+pub async fn get_data_pool() -> Result<DbPool> {
+    let guard = DATA_POOL.lock().await;
+    let pool = guard
+        .as_ref()
+        .context("Database connection not initialized")?
+        .clone();
+    Ok(pool)
+}
+
+/// 替换业务数据连接池，旧连接池在后台关闭（等待正在执行的查询结束）
+pub async fn set_data_pool(pool: DbPool) {
+    let old = {
+        let mut guard = DATA_POOL.lock().await;
+        DATA_POOL_GENERATION.fetch_add(1, Ordering::SeqCst);
+        guard.replace(pool)
+    };
+    if let Some(old) = old {
+        tokio::spawn(async move { old.close().await });
+    }
+}
+
+/// 打开（不存在时创建）SQLite 数据库文件，执行迁移后设为业务数据连接池
+pub async fn connect_sqlite_file(path: &Path) -> Result<DbPool> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create sqlite directory")?;
+    }
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .foreign_keys(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .context("Failed to open sqlite database")?;
+    let pool = DbPool::Sqlite(pool);
+    run_migrations(&pool).await.map_err(anyhow::Error::msg)?;
+    set_data_pool(pool.clone()).await;
+    Ok(pool)
+}
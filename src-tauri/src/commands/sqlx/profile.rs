@@ -0,0 +1,204 @@
+use crate::commands::sqlx::DbPool;
+use crate::commands::{load_secret, AppState, CredentialState};
+use crate::util::{profile_credential, uuid_util, DbProfile};
+use log::info;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use std::collections::HashMap;
+use tauri::{command, AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+// 未指定连接配置时使用的 id（connect_db 打开的连接）
+pub const DEFAULT_PROFILE: &str = "default";
+
+// 已打开的连接
+#[derive(Debug, Clone)]
+pub struct OpenPool {
+    pub pool: DbPool,
+    // 只读连接只允许执行查询语句
+    pub read_only: bool,
+}
+
+// 已打开的连接池，按连接配置 id 区分，数据库浏览器使用
+pub static POOLS: Lazy<Mutex<HashMap<String, OpenPool>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 返回给前端的已打开连接信息
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenProfile {
+    pub id: String,
+    pub backend: String,
+    pub read_only: bool,
+    pub size: u32,
+    pub idle: usize,
+}
+
+/// 获取连接配置对应的连接池，profile 为空时使用默认连接
+pub async fn get_pool(profile: Option<&str>) -> Option<DbPool> {
+    get_open_pool(profile).await.map(|p| p.pool)
+}
+
+/// 获取已打开的连接（含只读标记）
+pub async fn get_open_pool(profile: Option<&str>) -> Option<OpenPool> {
+    let id = profile.filter(|p| !p.is_empty()).unwrap_or(DEFAULT_PROFILE);
+    POOLS.lock().await.get(id).cloned()
+}
+
+/// 按连接配置创建连接池，不登记到 POOLS
+pub async fn connect_profile(profile: &DbProfile) -> Result<DbPool, String> {
+    let pool_size = profile.pool_size.max(1);
+    let pool = match profile.dbtype.as_str() {
+        "mysql" => {
+            let mut options = MySqlConnectOptions::new()
+                .host(&profile.host)
+                .port(if profile.port == 0 { 3306 } else { profile.port })
+                .username(&profile.username)
+                .password(&profile.password);
+            options = options.database(if profile.database.is_empty() {
+                "mysql"
+            } else {
+                &profile.database
+            });
+            let pool = MySqlPoolOptions::new()
+                .max_connections(pool_size)
+                .connect_with(options)
+                .await
+                .map_err(|e| e.to_string())?;
+            DbPool::MySql(pool)
+        }
+        "postgres" | "postgresql" => {
+            let mut options = PgConnectOptions::new()
+                .host(&profile.host)
+                .port(if profile.port == 0 { 5432 } else { profile.port })
+                .username(&profile.username)
+                .password(&profile.password);
+            options = options.database(if profile.database.is_empty() {
+                "postgres"
+            } else {
+                &profile.database
+            });
+            let pool = PgPoolOptions::new()
+                .max_connections(pool_size)
+                .connect_with(options)
+                .await
+                .map_err(|e| e.to_string())?;
+            DbPool::Postgres(pool)
+        }
+        other => return Err(format!("不支持的数据库类型: {}", other)),
+    };
+    Ok(pool)
+}
+
+/// 按连接配置创建连接池并以配置 id 登记，同 id 的旧连接池会被关闭
+pub async fn open_profile(profile: &DbProfile) -> Result<DbPool, String> {
+    let pool = connect_profile(profile).await?;
+    info!(
+        "open profile {} : {}://{}@{}",
+        profile.id, profile.dbtype, profile.username, profile.host
+    );
+    let open = OpenPool {
+        pool: pool.clone(),
+        read_only: profile.read_only,
+    };
+    let old = POOLS.lock().await.insert(profile.id.clone(), open);
+    if let Some(old) = old {
+        old.pool.close().await;
+    }
+    Ok(pool)
+}
+
+// 查找连接配置，密码从凭据库读取
+fn find_profile(app: &AppHandle, id: &str) -> Result<DbProfile, String> {
+    let mut profile = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        config
+            .db_profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| format!("连接配置 {} 不存在", id))?
+    };
+    profile.password = load_secret(app, &profile_credential(id))?;
+    Ok(profile)
+}
+
+#[command]
+pub fn list_db_profiles(state: State<'_, AppState>) -> Result<Vec<DbProfile>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    Ok(config.db_profiles.clone())
+}
+
+// 新增或更新连接配置（id 为空时新增）
+#[command]
+pub fn save_db_profile(app: AppHandle, mut profile: DbProfile) -> Result<DbProfile, String> {
+    if profile.id.is_empty() {
+        profile.id = uuid_util::new_id();
+    }
+    // 密码为空时保留原来保存的密码
+    if !profile.password.is_empty() {
+        let password = std::mem::take(&mut profile.password);
+        app.state::<CredentialState>()
+            .with_store(|store| store.set(&profile_credential(&profile.id), &password))?;
+    }
+    let state = app.state::<AppState>();
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    match config.db_profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile.clone(),
+        None => config.db_profiles.push(profile.clone()),
+    }
+    config.save(&app)?;
+    Ok(profile)
+}
+
+#[command]
+pub async fn delete_db_profile(app: AppHandle, id: String) -> Result<(), String> {
+    {
+        let state = app.state::<AppState>();
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.db_profiles.retain(|p| p.id != id);
+        config.save(&app)?;
+    }
+    app.state::<CredentialState>()
+        .with_store(|store| store.remove(&profile_credential(&id)))?;
+    close_db_profile(id).await
+}
+
+// 打开连接配置，返回数据库类型
+#[command]
+pub async fn open_db_profile(app: AppHandle, id: String) -> Result<String, String> {
+    let profile = find_profile(&app, &id)?;
+    let pool = open_profile(&profile).await?;
+    Ok(pool.backend().to_string())
+}
+
+#[command]
+pub async fn close_db_profile(id: String) -> Result<(), String> {
+    let open = POOLS.lock().await.remove(&id);
+    if let Some(open) = open {
+        open.pool.close().await;
+    }
+    Ok(())
+}
+
+// 列出已打开的连接
+#[command]
+pub async fn list_open_profiles() -> Vec<OpenProfile> {
+    let pools = POOLS.lock().await;
+    let mut list: Vec<OpenProfile> = pools
+        .iter()
+        .map(|(id, open)| {
+            let pool = &open.pool;
+            OpenProfile {
+                id: id.clone(),
+                backend: pool.backend().to_string(),
+                read_only: open.read_only,
+                size: pool.size(),
+                idle: pool.num_idle(),
+            }
+        })
+        .collect();
+    list.sort_by(|a, b| a.id.cmp(&b.id));
+    list
+}
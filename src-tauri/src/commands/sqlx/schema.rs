@@ -0,0 +1,962 @@
+use crate::commands::sqlx::{explorer, get_pool, DbPool};
+use log::info;
+use serde::Serialize;
+use sqlx::mysql::MySqlPool;
+use sqlx::postgres::PgPool;
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use std::collections::BTreeMap;
+use tauri::command;
+
+// 字段
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ColumnInfo {
+    pub name: String,
+    // 从 1 开始
+    pub ordinal: i64,
+    // 类型名，如 varchar
+    pub data_type: String,
+    // 完整类型，如 varchar(20)、decimal(10,2) unsigned
+    pub column_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub primary_key: bool,
+    pub auto_increment: bool,
+    pub comment: Option<String>,
+}
+
+// 索引，columns 按索引中的顺序
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct IndexInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub primary: bool,
+    pub index_type: Option<String>,
+}
+
+// 外键，SQLite 的外键没有名称
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ForeignKeyInfo {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    pub ref_schema: Option<String>,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+// 表结构
+#[derive(Debug, Clone, Serialize)]
+pub struct TableSchema {
+    pub name: String,
+    pub comment: Option<String>,
+    pub columns: Vec<ColumnInfo>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    // 建表语句：MySQL 为 SHOW CREATE TABLE，SQLite 为 sqlite_master 中的原文，PostgreSQL 由系统表生成
+    pub ddl: Option<String>,
+    // 估算行数（MySQL / PostgreSQL 来自统计信息，SQLite 为精确计数）
+    pub row_estimate: Option<i64>,
+    // 数据和索引占用的字节数
+    pub data_size: Option<i64>,
+    pub index_size: Option<i64>,
+}
+
+// 字段差异
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnChange {
+    pub name: String,
+    pub source: ColumnInfo,
+    pub target: ColumnInfo,
+}
+
+// 单表差异，added 为源库有而目标库没有，removed 相反
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct TableDiff {
+    pub table: String,
+    pub added_columns: Vec<ColumnInfo>,
+    pub removed_columns: Vec<ColumnInfo>,
+    pub changed_columns: Vec<ColumnChange>,
+    pub added_indexes: Vec<IndexInfo>,
+    pub removed_indexes: Vec<IndexInfo>,
+    pub changed_indexes: Vec<IndexInfo>,
+    pub added_foreign_keys: Vec<ForeignKeyInfo>,
+    pub removed_foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+impl TableDiff {
+    fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+            && self.added_indexes.is_empty()
+            && self.removed_indexes.is_empty()
+            && self.changed_indexes.is_empty()
+            && self.added_foreign_keys.is_empty()
+            && self.removed_foreign_keys.is_empty()
+    }
+}
+
+// 两个库的结构差异，以 source 为准比较 target
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaDiff {
+    pub source: String,
+    pub target: String,
+    pub only_in_source: Vec<String>,
+    pub only_in_target: Vec<String>,
+    pub changed: Vec<TableDiff>,
+}
+
+/// 读取表结构，full 为 false 时不读取建表语句和统计信息（用于结构对比）
+pub async fn describe(pool: &DbPool, dbname: &str, table_name: &str, full: bool) -> Result<TableSchema, String> {
+    let mut schema = match pool {
+        DbPool::MySql(pool) => mysql_describe(pool, dbname, table_name, full).await?,
+        DbPool::Postgres(pool) => {
+            let schema = explorer::pg_schema(pool, dbname).await?;
+            pg_describe(pool, &schema, table_name, full).await?
+        }
+        DbPool::Sqlite(pool) => sqlite_describe(pool, table_name, full).await?,
+    };
+    if schema.columns.is_empty() {
+        return Err(format!("表 {} 不存在", table_name));
+    }
+    // 主键字段以主键索引为准
+    if let Some(primary) = schema.indexes.iter().find(|i| i.primary) {
+        for column in schema.columns.iter_mut() {
+            column.primary_key = column.primary_key || primary.columns.contains(&column.name);
+        }
+    }
+    Ok(schema)
+}
+
+// 同一索引 / 外键的多行合并为一项，保持出现顺序
+fn push_index(indexes: &mut Vec<IndexInfo>, name: String, column: String, unique: bool, index_type: Option<String>) {
+    match indexes.iter_mut().find(|i| i.name == name) {
+        Some(index) => index.columns.push(column),
+        None => indexes.push(IndexInfo {
+            primary: name == "PRIMARY",
+            name,
+            columns: vec![column],
+            unique,
+            index_type,
+        }),
+    }
+}
+
+async fn mysql_describe(pool: &MySqlPool, dbname: &str, table_name: &str, full: bool) -> Result<TableSchema, String> {
+    // information_schema 的部分字段在 MySQL 8 中是二进制串，统一转为字符
+    let rows = sqlx::query(
+        "SELECT CAST(COLUMN_NAME AS CHAR) AS name, CAST(ORDINAL_POSITION AS SIGNED) AS ordinal,
+                CAST(DATA_TYPE AS CHAR) AS data_type, CAST(COLUMN_TYPE AS CHAR) AS column_type,
+                CAST(IS_NULLABLE = 'YES' AS SIGNED) AS nullable, CAST(COLUMN_DEFAULT AS CHAR) AS default_value,
+                CAST(COLUMN_KEY = 'PRI' AS SIGNED) AS primary_key,
+                CAST(EXTRA LIKE '%auto_increment%' AS SIGNED) AS auto_increment,
+                CAST(COLUMN_COMMENT AS CHAR) AS comment
+         FROM information_schema.columns WHERE table_schema = ? AND table_name = ?
+         ORDER BY ORDINAL_POSITION",
+    )
+    .bind(dbname)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let columns = rows
+        .iter()
+        .map(|r| ColumnInfo {
+            name: r.get("name"),
+            ordinal: r.get("ordinal"),
+            data_type: r.get("data_type"),
+            column_type: r.get("column_type"),
+            nullable: r.get::<i64, _>("nullable") != 0,
+            default: r.get("default_value"),
+            primary_key: r.get::<i64, _>("primary_key") != 0,
+            auto_increment: r.get::<i64, _>("auto_increment") != 0,
+            comment: r.get::<Option<String>, _>("comment").filter(|c| !c.is_empty()),
+        })
+        .collect();
+
+    let rows = sqlx::query(
+        "SELECT CAST(INDEX_NAME AS CHAR) AS name, CAST(COLUMN_NAME AS CHAR) AS column_name,
+                CAST(NON_UNIQUE AS SIGNED) AS non_unique, CAST(INDEX_TYPE AS CHAR) AS index_type
+         FROM information_schema.statistics WHERE table_schema = ? AND table_name = ?
+         ORDER BY INDEX_NAME = 'PRIMARY' DESC, INDEX_NAME, SEQ_IN_INDEX",
+    )
+    .bind(dbname)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut indexes = Vec::new();
+    for r in &rows {
+        // 函数索引没有字段名
+        let column = r
+            .get::<Option<String>, _>("column_name")
+            .unwrap_or_else(|| "(expression)".to_string());
+        push_index(
+            &mut indexes,
+            r.get("name"),
+            column,
+            r.get::<i64, _>("non_unique") == 0,
+            r.get("index_type"),
+        );
+    }
+
+    let rows = sqlx::query(
+        "SELECT CAST(k.CONSTRAINT_NAME AS CHAR) AS name, CAST(k.COLUMN_NAME AS CHAR) AS column_name,
+                CAST(k.REFERENCED_TABLE_SCHEMA AS CHAR) AS ref_schema, CAST(k.REFERENCED_TABLE_NAME AS CHAR) AS ref_table,
+                CAST(k.REFERENCED_COLUMN_NAME AS CHAR) AS ref_column,
+                CAST(r.UPDATE_RULE AS CHAR) AS on_update, CAST(r.DELETE_RULE AS CHAR) AS on_delete
+         FROM information_schema.key_column_usage k
+         JOIN information_schema.referential_constraints r
+           ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME
+         WHERE k.table_schema = ? AND k.table_name = ? AND k.REFERENCED_TABLE_NAME IS NOT NULL
+         ORDER BY k.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+    )
+    .bind(dbname)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut foreign_keys: Vec<ForeignKeyInfo> = Vec::new();
+    for r in &rows {
+        let name: String = r.get("name");
+        let column: String = r.get("column_name");
+        let ref_column: String = r.get("ref_column");
+        match foreign_keys.iter_mut().find(|f| f.name.as_deref() == Some(name.as_str())) {
+            Some(fk) => {
+                fk.columns.push(column);
+                fk.ref_columns.push(ref_column);
+            }
+            None => foreign_keys.push(ForeignKeyInfo {
+                name: Some(name),
+                columns: vec![column],
+                ref_schema: r.get("ref_schema"),
+                ref_table: r.get("ref_table"),
+                ref_columns: vec![ref_column],
+                on_update: r.get("on_update"),
+                on_delete: r.get("on_delete"),
+            }),
+        }
+    }
+
+    let table = sqlx::query(
+        "SELECT CAST(TABLE_COMMENT AS CHAR) AS comment, CAST(TABLE_ROWS AS SIGNED) AS row_estimate,
+                CAST(DATA_LENGTH AS SIGNED) AS data_size, CAST(INDEX_LENGTH AS SIGNED) AS index_size
+         FROM information_schema.tables WHERE table_schema = ? AND table_name = ?",
+    )
+    .bind(dbname)
+    .bind(table_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut schema = TableSchema {
+        name: table_name.to_string(),
+        comment: table
+            .as_ref()
+            .and_then(|r| r.get::<Option<String>, _>("comment"))
+            .filter(|c| !c.is_empty()),
+        columns,
+        indexes,
+        foreign_keys,
+        ddl: None,
+        row_estimate: None,
+        data_size: None,
+        index_size: None,
+    };
+    if full {
+        let qualified = explorer::qualified_table(&DbPool::MySql(pool.clone()), dbname, table_name).await?;
+        let row = sqlx::query(&format!("SHOW CREATE TABLE {}", qualified))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        // 表和视图的第二列都是建表语句
+        schema.ddl = Some(row.try_get::<String, _>(1).map_err(|e| e.to_string())?);
+        if let Some(r) = &table {
+            schema.row_estimate = r.get("row_estimate");
+            schema.data_size = r.get("data_size");
+            schema.index_size = r.get("index_size");
+        }
+    }
+    Ok(schema)
+}
+
+async fn pg_describe(pool: &PgPool, schema_name: &str, table_name: &str, full: bool) -> Result<TableSchema, String> {
+    let rows = sqlx::query(
+        "SELECT a.attname::text AS name, a.attnum::bigint AS ordinal,
+                t.typname::text AS data_type, format_type(a.atttypid, a.atttypmod) AS column_type,
+                NOT a.attnotnull AS nullable, pg_get_expr(d.adbin, d.adrelid) AS default_value,
+                (a.attidentity <> '' OR coalesce(pg_get_expr(d.adbin, d.adrelid), '') LIKE 'nextval(%') AS auto_increment,
+                col_description(a.attrelid, a.attnum) AS comment
+         FROM pg_attribute a
+         JOIN pg_class c ON c.oid = a.attrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         JOIN pg_type t ON t.oid = a.atttypid
+         LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+         WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped
+         ORDER BY a.attnum",
+    )
+    .bind(schema_name)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let columns = rows
+        .iter()
+        .enumerate()
+        .map(|(i, r)| ColumnInfo {
+            name: r.get("name"),
+            // attnum 在删除字段后会有空洞，按顺序重新编号
+            ordinal: i as i64 + 1,
+            data_type: r.get("data_type"),
+            column_type: r.get("column_type"),
+            nullable: r.get("nullable"),
+            default: r.get("default_value"),
+            primary_key: false,
+            auto_increment: r.get("auto_increment"),
+            comment: r.get("comment"),
+        })
+        .collect();
+
+    let rows = sqlx::query(
+        "SELECT i.relname::text AS name, ix.indisunique AS is_unique, ix.indisprimary AS is_primary,
+                am.amname::text AS index_type,
+                ARRAY(SELECT pg_get_indexdef(ix.indexrelid, k, true)
+                      FROM generate_series(1, ix.indnkeyatts) AS k ORDER BY k) AS columns
+         FROM pg_index ix
+         JOIN pg_class t ON t.oid = ix.indrelid
+         JOIN pg_class i ON i.oid = ix.indexrelid
+         JOIN pg_namespace n ON n.oid = t.relnamespace
+         JOIN pg_am am ON am.oid = i.relam
+         WHERE n.nspname = $1 AND t.relname = $2
+         ORDER BY ix.indisprimary DESC, i.relname",
+    )
+    .bind(schema_name)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let indexes = rows
+        .iter()
+        .map(|r| IndexInfo {
+            name: r.get("name"),
+            columns: r.get("columns"),
+            unique: r.get("is_unique"),
+            primary: r.get("is_primary"),
+            index_type: r.get("index_type"),
+        })
+        .collect();
+
+    let rows = sqlx::query(
+        "SELECT con.conname::text AS name,
+                ARRAY(SELECT a.attname::text FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+                      JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum ORDER BY k.ord) AS columns,
+                rn.nspname::text AS ref_schema, rt.relname::text AS ref_table,
+                ARRAY(SELECT a.attname::text FROM unnest(con.confkey) WITH ORDINALITY AS k(attnum, ord)
+                      JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum ORDER BY k.ord) AS ref_columns,
+                CASE con.confupdtype WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT'
+                     WHEN 'r' THEN 'RESTRICT' ELSE 'NO ACTION' END AS on_update,
+                CASE con.confdeltype WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT'
+                     WHEN 'r' THEN 'RESTRICT' ELSE 'NO ACTION' END AS on_delete
+         FROM pg_constraint con
+         JOIN pg_class t ON t.oid = con.conrelid
+         JOIN pg_namespace n ON n.oid = t.relnamespace
+         JOIN pg_class rt ON rt.oid = con.confrelid
+         JOIN pg_namespace rn ON rn.oid = rt.relnamespace
+         WHERE con.contype = 'f' AND n.nspname = $1 AND t.relname = $2
+         ORDER BY con.conname",
+    )
+    .bind(schema_name)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let foreign_keys = rows
+        .iter()
+        .map(|r| ForeignKeyInfo {
+            name: r.get("name"),
+            columns: r.get("columns"),
+            ref_schema: r.get("ref_schema"),
+            ref_table: r.get("ref_table"),
+            ref_columns: r.get("ref_columns"),
+            on_update: r.get("on_update"),
+            on_delete: r.get("on_delete"),
+        })
+        .collect();
+
+    let table = sqlx::query(
+        "SELECT c.oid::bigint AS oid, c.relkind::text AS kind, obj_description(c.oid, 'pg_class') AS comment,
+                c.reltuples::bigint AS row_estimate, pg_table_size(c.oid) AS data_size,
+                pg_indexes_size(c.oid) AS index_size
+         FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1 AND c.relname = $2",
+    )
+    .bind(schema_name)
+    .bind(table_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut schema = TableSchema {
+        name: table_name.to_string(),
+        comment: table.as_ref().and_then(|r| r.get("comment")),
+        columns,
+        indexes,
+        foreign_keys,
+        ddl: None,
+        row_estimate: None,
+        data_size: None,
+        index_size: None,
+    };
+    if let (true, Some(r)) = (full, &table) {
+        // 从未 ANALYZE 过的表 reltuples 为 -1
+        schema.row_estimate = r.get::<Option<i64>, _>("row_estimate").filter(|n| *n >= 0);
+        schema.data_size = r.get("data_size");
+        schema.index_size = r.get("index_size");
+        schema.ddl = Some(pg_ddl(pool, schema_name, &schema, r.get("oid"), &r.get::<String, _>("kind")).await?);
+    }
+    Ok(schema)
+}
+
+// PostgreSQL 没有 SHOW CREATE TABLE，由字段、约束、索引和注释拼出建表语句
+async fn pg_ddl(pool: &PgPool, schema_name: &str, schema: &TableSchema, oid: i64, kind: &str) -> Result<String, String> {
+    let pg = DbPool::Postgres(pool.clone());
+    let qualified = format!(
+        "{}.{}",
+        explorer::quote_ident(&pg, schema_name),
+        explorer::quote_ident(&pg, &schema.name)
+    );
+    if kind == "v" || kind == "m" {
+        let definition: String = sqlx::query_scalar("SELECT pg_get_viewdef($1::bigint::oid, true)")
+            .bind(oid)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let create = if kind == "m" { "CREATE MATERIALIZED VIEW" } else { "CREATE VIEW" };
+        return Ok(format!("{} {} AS\n{}", create, qualified, definition.trim_end()));
+    }
+
+    let mut lines: Vec<String> = schema
+        .columns
+        .iter()
+        .map(|c| {
+            let mut line = format!("    {} {}", explorer::quote_ident(&pg, &c.name), c.column_type);
+            if let Some(default) = &c.default {
+                line.push_str(&format!(" DEFAULT {}", default));
+            }
+            if !c.nullable {
+                line.push_str(" NOT NULL");
+            }
+            line
+        })
+        .collect();
+    let constraints = sqlx::query(
+        "SELECT conname::text AS name, pg_get_constraintdef(oid, true) AS definition
+         FROM pg_constraint WHERE conrelid = $1::bigint::oid
+         ORDER BY CASE contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'f' THEN 2 ELSE 3 END, conname",
+    )
+    .bind(oid)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for r in &constraints {
+        lines.push(format!(
+            "    CONSTRAINT {} {}",
+            explorer::quote_ident(&pg, &r.get::<String, _>("name")),
+            r.get::<String, _>("definition")
+        ));
+    }
+    let mut ddl = format!("CREATE TABLE {} (\n{}\n);", qualified, lines.join(",\n"));
+
+    // 约束自带的索引已包含在建表语句中
+    let indexes: Vec<String> = sqlx::query_scalar(
+        "SELECT pg_get_indexdef(i.indexrelid) FROM pg_index i
+         WHERE i.indrelid = $1::bigint::oid
+           AND NOT EXISTS (SELECT 1 FROM pg_constraint c WHERE c.conindid = i.indexrelid)
+         ORDER BY i.indexrelid",
+    )
+    .bind(oid)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for index in indexes {
+        ddl.push_str(&format!("\n{};", index));
+    }
+    if let Some(comment) = &schema.comment {
+        ddl.push_str(&format!("\nCOMMENT ON TABLE {} IS '{}';", qualified, comment.replace('\'', "''")));
+    }
+    for c in &schema.columns {
+        if let Some(comment) = &c.comment {
+            ddl.push_str(&format!(
+                "\nCOMMENT ON COLUMN {}.{} IS '{}';",
+                qualified,
+                explorer::quote_ident(&pg, &c.name),
+                comment.replace('\'', "''")
+            ));
+        }
+    }
+    Ok(ddl)
+}
+
+async fn sqlite_describe(pool: &SqlitePool, table_name: &str, full: bool) -> Result<TableSchema, String> {
+    let table_sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE name = ? AND type IN ('table', 'view')")
+        .bind(table_name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .flatten();
+    let autoincrement = table_sql
+        .as_deref()
+        .is_some_and(|sql| sql.to_ascii_uppercase().contains("AUTOINCREMENT"));
+
+    let rows = sqlx::query(
+        "SELECT cid, name, type, \"notnull\" AS not_null, dflt_value, pk FROM pragma_table_info(?) ORDER BY cid",
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let pk_count = rows.iter().filter(|r| r.get::<i64, _>("pk") > 0).count();
+    let mut pk_columns: Vec<(i64, String)> = Vec::new();
+    let columns = rows
+        .iter()
+        .map(|r| {
+            let name: String = r.get("name");
+            let column_type: String = r.get("type");
+            let pk: i64 = r.get("pk");
+            if pk > 0 {
+                pk_columns.push((pk, name.clone()));
+            }
+            // 单字段 INTEGER 主键是 rowid 的别名，插入时自动分配
+            let auto_increment = pk > 0 && pk_count == 1 && column_type.eq_ignore_ascii_case("INTEGER");
+            ColumnInfo {
+                ordinal: r.get::<i64, _>("cid") + 1,
+                data_type: column_type
+                    .split('(')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_ascii_lowercase(),
+                column_type,
+                nullable: r.get::<i64, _>("not_null") == 0,
+                default: r.get("dflt_value"),
+                primary_key: pk > 0,
+                auto_increment: auto_increment || (pk > 0 && autoincrement),
+                comment: None,
+                name,
+            }
+        })
+        .collect();
+
+    let index_rows = sqlx::query("SELECT name, \"unique\" AS is_unique, origin FROM pragma_index_list(?) ORDER BY seq")
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut indexes = Vec::new();
+    for r in &index_rows {
+        let name: String = r.get("name");
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT coalesce(name, '(expression)') FROM pragma_index_info(?) ORDER BY seqno",
+        )
+        .bind(&name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        indexes.push(IndexInfo {
+            primary: r.get::<String, _>("origin") == "pk",
+            unique: r.get::<i64, _>("is_unique") != 0,
+            name,
+            columns,
+            index_type: None,
+        });
+    }
+    // rowid 主键没有对应的索引
+    if !pk_columns.is_empty() && !indexes.iter().any(|i| i.primary) {
+        pk_columns.sort();
+        indexes.insert(
+            0,
+            IndexInfo {
+                name: "PRIMARY".to_string(),
+                columns: pk_columns.into_iter().map(|(_, c)| c).collect(),
+                unique: true,
+                primary: true,
+                index_type: None,
+            },
+        );
+    }
+
+    let fk_rows = sqlx::query(
+        "SELECT id, \"table\" AS ref_table, \"from\" AS column_name, \"to\" AS ref_column, on_update, on_delete
+         FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut grouped: BTreeMap<i64, ForeignKeyInfo> = BTreeMap::new();
+    for r in &fk_rows {
+        let fk = grouped.entry(r.get("id")).or_insert_with(|| ForeignKeyInfo {
+            name: None,
+            columns: Vec::new(),
+            ref_schema: None,
+            ref_table: r.get("ref_table"),
+            ref_columns: Vec::new(),
+            on_update: r.get("on_update"),
+            on_delete: r.get("on_delete"),
+        });
+        fk.columns.push(r.get("column_name"));
+        // 省略被引用字段时引用的是对方的主键
+        if let Some(ref_column) = r.get::<Option<String>, _>("ref_column") {
+            fk.ref_columns.push(ref_column);
+        }
+    }
+
+    let mut schema = TableSchema {
+        name: table_name.to_string(),
+        comment: None,
+        columns,
+        indexes,
+        foreign_keys: grouped.into_values().collect(),
+        ddl: None,
+        row_estimate: None,
+        data_size: None,
+        index_size: None,
+    };
+    if full {
+        let extra: Vec<String> = sqlx::query_scalar(
+            "SELECT sql FROM sqlite_master WHERE tbl_name = ? AND type IN ('index', 'trigger') AND sql IS NOT NULL ORDER BY type, name",
+        )
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        schema.ddl = table_sql.map(|sql| {
+            std::iter::once(sql)
+                .chain(extra)
+                .map(|s| format!("{};", s))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        let qualified = explorer::qualified_table(&DbPool::Sqlite(pool.clone()), "main", table_name).await?;
+        schema.row_estimate = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", qualified))
+            .fetch_one(pool)
+            .await
+            .ok();
+        // dbstat 虚拟表需要编译选项支持，没有时不返回大小
+        schema.data_size = sqlx::query_scalar("SELECT SUM(pgsize) FROM dbstat WHERE name = ?")
+            .bind(table_name)
+            .fetch_one(pool)
+            .await
+            .ok()
+            .flatten();
+        let mut index_size: Option<i64> = None;
+        for index in schema.indexes.iter().filter(|i| i.name != "PRIMARY") {
+            let size: Option<i64> = sqlx::query_scalar("SELECT SUM(pgsize) FROM dbstat WHERE name = ?")
+                .bind(&index.name)
+                .fetch_one(pool)
+                .await
+                .ok()
+                .flatten();
+            if let Some(size) = size {
+                index_size = Some(index_size.unwrap_or(0) + size);
+            }
+        }
+        schema.index_size = index_size;
+    }
+    Ok(schema)
+}
+
+// 外键没有名称时按字段和被引用表匹配
+fn fk_key(fk: &ForeignKeyInfo) -> String {
+    fk.name
+        .clone()
+        .unwrap_or_else(|| format!("{}->{}", fk.columns.join(","), fk.ref_table))
+}
+
+fn diff_table(table: &str, source: &TableSchema, target: &TableSchema) -> TableDiff {
+    let mut diff = TableDiff {
+        table: table.to_string(),
+        ..Default::default()
+    };
+    for column in &source.columns {
+        match target.columns.iter().find(|c| c.name == column.name) {
+            None => diff.added_columns.push(column.clone()),
+            Some(other) => {
+                // 字段顺序不参与比较
+                let same = ColumnInfo {
+                    ordinal: column.ordinal,
+                    ..other.clone()
+                } == *column;
+                if !same {
+                    diff.changed_columns.push(ColumnChange {
+                        name: column.name.clone(),
+                        source: column.clone(),
+                        target: other.clone(),
+                    });
+                }
+            }
+        }
+    }
+    diff.removed_columns = target
+        .columns
+        .iter()
+        .filter(|c| !source.columns.iter().any(|s| s.name == c.name))
+        .cloned()
+        .collect();
+
+    for index in &source.indexes {
+        match target.indexes.iter().find(|i| i.name == index.name) {
+            None => diff.added_indexes.push(index.clone()),
+            Some(other) if other != index => diff.changed_indexes.push(index.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed_indexes = target
+        .indexes
+        .iter()
+        .filter(|i| !source.indexes.iter().any(|s| s.name == i.name))
+        .cloned()
+        .collect();
+
+    // 外键有任何变化都视为删除后重建
+    diff.added_foreign_keys = source
+        .foreign_keys
+        .iter()
+        .filter(|f| !target.foreign_keys.iter().any(|t| fk_key(t) == fk_key(f) && t == *f))
+        .cloned()
+        .collect();
+    diff.removed_foreign_keys = target
+        .foreign_keys
+        .iter()
+        .filter(|f| !source.foreign_keys.iter().any(|s| fk_key(s) == fk_key(f) && s == *f))
+        .cloned()
+        .collect();
+    diff
+}
+
+/// 读取表结构：有序字段（含可空、默认值、主键、自增、注释）、索引、外键、建表语句和统计信息
+#[command]
+pub async fn describe_table(
+    db_name: &str,
+    table_name: &str,
+    profile: Option<String>,
+) -> Result<TableSchema, String> {
+    let pool = get_pool(profile.as_deref())
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    describe(&pool, db_name, table_name, true).await
+}
+
+/// 对比两个库的表结构，以 source 为准；两个库可以来自不同的连接
+#[command]
+pub async fn diff_schema(
+    source_db: &str,
+    target_db: &str,
+    profile: Option<String>,
+    target_profile: Option<String>,
+) -> Result<SchemaDiff, String> {
+    let source_pool = get_pool(profile.as_deref())
+        .await
+        .ok_or_else(|| "数据库连接未初始化".to_string())?;
+    let target_pool = match target_profile.as_deref() {
+        Some(_) => get_pool(target_profile.as_deref())
+            .await
+            .ok_or_else(|| "目标数据库连接未初始化".to_string())?,
+        None => source_pool.clone(),
+    };
+
+    let source_tables: Vec<String> = explorer::list_tables(&source_pool, source_db)
+        .await?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    let target_tables: Vec<String> = explorer::list_tables(&target_pool, target_db)
+        .await?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+
+    let mut diff = SchemaDiff {
+        source: source_db.to_string(),
+        target: target_db.to_string(),
+        only_in_source: source_tables
+            .iter()
+            .filter(|t| !target_tables.contains(t))
+            .cloned()
+            .collect(),
+        only_in_target: target_tables
+            .iter()
+            .filter(|t| !source_tables.contains(t))
+            .cloned()
+            .collect(),
+        changed: Vec::new(),
+    };
+    for table in source_tables.iter().filter(|t| target_tables.contains(t)) {
+        let source = describe(&source_pool, source_db, table, false).await?;
+        let target = describe(&target_pool, target_db, table, false).await?;
+        let table_diff = diff_table(table, &source, &target);
+        if !table_diff.is_empty() {
+            diff.changed.push(table_diff);
+        }
+    }
+    info!(
+        "diff schema {} -> {}: {} changed, {} only in source, {} only in target",
+        source_db,
+        target_db,
+        diff.changed.len(),
+        diff.only_in_source.len(),
+        diff.only_in_target.len()
+    );
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, ordinal: i64, column_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            ordinal,
+            data_type: column_type.split('(').next().unwrap().to_string(),
+            column_type: column_type.to_string(),
+            nullable: true,
+            default: None,
+            primary_key: false,
+            auto_increment: false,
+            comment: None,
+        }
+    }
+
+    fn index(name: &str, columns: &[&str], unique: bool) -> IndexInfo {
+        IndexInfo {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            unique,
+            primary: false,
+            index_type: Some("BTREE".to_string()),
+        }
+    }
+
+    fn foreign_key(name: Option<&str>, column: &str, ref_table: &str, on_delete: &str) -> ForeignKeyInfo {
+        ForeignKeyInfo {
+            name: name.map(|n| n.to_string()),
+            columns: vec![column.to_string()],
+            ref_schema: None,
+            ref_table: ref_table.to_string(),
+            ref_columns: vec!["id".to_string()],
+            on_update: "NO ACTION".to_string(),
+            on_delete: on_delete.to_string(),
+        }
+    }
+
+    fn table(columns: Vec<ColumnInfo>, indexes: Vec<IndexInfo>, foreign_keys: Vec<ForeignKeyInfo>) -> TableSchema {
+        TableSchema {
+            name: "t".to_string(),
+            comment: None,
+            columns,
+            indexes,
+            foreign_keys,
+            ddl: None,
+            row_estimate: None,
+            data_size: None,
+            index_size: None,
+        }
+    }
+
+    fn names<T>(items: &[T], name: impl Fn(&T) -> String) -> Vec<String> {
+        items.iter().map(name).collect()
+    }
+
+    #[test]
+    fn diffs_columns() {
+        let source = table(
+            vec![column("id", 1, "int"), column("name", 2, "varchar(50)"), column("age", 3, "int")],
+            vec![],
+            vec![],
+        );
+        let target = table(
+            vec![column("id", 1, "int"), column("name", 2, "varchar(20)"), column("email", 3, "varchar(100)")],
+            vec![],
+            vec![],
+        );
+        let diff = diff_table("t", &source, &target);
+        assert_eq!(names(&diff.added_columns, |c| c.name.clone()), ["age"]);
+        assert_eq!(names(&diff.removed_columns, |c| c.name.clone()), ["email"]);
+        assert_eq!(names(&diff.changed_columns, |c| c.name.clone()), ["name"]);
+        assert_eq!(diff.changed_columns[0].source.column_type, "varchar(50)");
+        assert_eq!(diff.changed_columns[0].target.column_type, "varchar(20)");
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn ignores_column_order() {
+        let source = table(vec![column("id", 1, "int"), column("name", 2, "varchar(50)")], vec![], vec![]);
+        let target = table(vec![column("name", 1, "varchar(50)"), column("id", 2, "int")], vec![], vec![]);
+        assert!(diff_table("t", &source, &target).is_empty());
+    }
+
+    #[test]
+    fn diffs_indexes() {
+        let source = table(
+            vec![],
+            vec![
+                index("idx_name", &["name"], true),
+                index("idx_age", &["age"], false),
+                index("idx_a_b", &["a", "b"], false),
+            ],
+            vec![],
+        );
+        let target = table(
+            vec![],
+            vec![
+                index("idx_name", &["name"], false),
+                index("idx_email", &["email"], false),
+                index("idx_a_b", &["a", "b"], false),
+            ],
+            vec![],
+        );
+        let diff = diff_table("t", &source, &target);
+        assert_eq!(names(&diff.added_indexes, |i| i.name.clone()), ["idx_age"]);
+        assert_eq!(names(&diff.removed_indexes, |i| i.name.clone()), ["idx_email"]);
+        assert_eq!(names(&diff.changed_indexes, |i| i.name.clone()), ["idx_name"]);
+        assert!(diff.changed_indexes[0].unique);
+
+        // 索引中字段的顺序有意义
+        let target = table(vec![], vec![index("idx_a_b", &["b", "a"], false)], vec![]);
+        let source = table(vec![], vec![index("idx_a_b", &["a", "b"], false)], vec![]);
+        let diff = diff_table("t", &source, &target);
+        assert_eq!(names(&diff.changed_indexes, |i| i.name.clone()), ["idx_a_b"]);
+    }
+
+    #[test]
+    fn diffs_foreign_keys() {
+        let source = table(
+            vec![],
+            vec![],
+            vec![
+                foreign_key(Some("fk_user"), "user_id", "users", "CASCADE"),
+                foreign_key(Some("fk_order"), "order_id", "orders", "NO ACTION"),
+                foreign_key(None, "tag_id", "tags", "NO ACTION"),
+            ],
+        );
+        let target = table(
+            vec![],
+            vec![],
+            vec![
+                foreign_key(Some("fk_user"), "user_id", "users", "RESTRICT"),
+                foreign_key(Some("fk_item"), "item_id", "items", "NO ACTION"),
+                foreign_key(None, "tag_id", "tags", "NO ACTION"),
+            ],
+        );
+        let diff = diff_table("t", &source, &target);
+        // 变化的外键既在 added 中也在 removed 中，删除后重建
+        assert_eq!(names(&diff.added_foreign_keys, fk_key), ["fk_user", "fk_order"]);
+        assert_eq!(names(&diff.removed_foreign_keys, fk_key), ["fk_user", "fk_item"]);
+        assert_eq!(diff.added_foreign_keys[0].on_delete, "CASCADE");
+        assert_eq!(diff.removed_foreign_keys[0].on_delete, "RESTRICT");
+    }
+}
@@ -0,0 +1,235 @@
+use crate::commands::sqlx::DbPool;
+
+// SQL 脚本拆分：按分隔符把脚本拆成单条语句
+// 识别字符串、行注释、块注释，各数据库的差异：
+// MySQL：反引号、反斜杠转义、# 行注释、mysql 客户端的 DELIMITER 指令
+// PostgreSQL：$$...$$ 和 $tag$...$tag$ 美元符号引用（函数体）
+// SQLite：反引号
+
+/// 脚本所属的数据库，决定拆分时识别哪些语法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScriptDialect {
+    #[default]
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl ScriptDialect {
+    pub fn of(pool: &DbPool) -> Self {
+        match pool {
+            DbPool::MySql(_) => ScriptDialect::MySql,
+            DbPool::Postgres(_) => ScriptDialect::Postgres,
+            DbPool::Sqlite(_) => ScriptDialect::Sqlite,
+        }
+    }
+}
+
+/// 增量拆分器，可以逐行输入，适合读取大文件
+pub struct StatementSplitter {
+    dialect: ScriptDialect,
+    buf: String,
+    quote: Option<char>,
+    // 美元符号引用的标记（含两端的 $），在引用内时为 Some
+    dollar_tag: Option<String>,
+    in_block_comment: bool,
+    delimiter: String,
+}
+
+// 匹配 rest 开头的美元符号引用标记：$$ 或 $tag$，tag 不能以数字开头（$1 是参数）
+fn dollar_tag(rest: &str) -> Option<&str> {
+    let body = rest.strip_prefix('$')?;
+    let end = body.find('$')?;
+    let tag = &body[..end];
+    let valid = !tag.starts_with(|c: char| c.is_ascii_digit()) && tag.chars().all(|c| c.is_alphanumeric() || c == '_');
+    valid.then(|| &rest[..end + 2])
+}
+
+impl StatementSplitter {
+    pub fn new(dialect: ScriptDialect) -> Self {
+        Self {
+            dialect,
+            buf: String::new(),
+            quote: None,
+            dollar_tag: None,
+            in_block_comment: false,
+            delimiter: ";".to_string(),
+        }
+    }
+
+    /// 输入一行（不含换行符），返回这一行中结束的语句
+    pub fn push_line(&mut self, line: &str) -> Vec<String> {
+        let mut done = Vec::new();
+
+        // DELIMITER 指令只能单独成行出现在语句之间
+        if self.dialect == ScriptDialect::MySql
+            && self.quote.is_none()
+            && !self.in_block_comment
+            && self.buf.trim().is_empty()
+        {
+            let trimmed = line.trim();
+            if trimmed.len() > 10 && trimmed.get(..10).is_some_and(|p| p.eq_ignore_ascii_case("DELIMITER ")) {
+                self.delimiter = trimmed[10..].trim().to_string();
+                return done;
+            }
+        }
+
+        let mut chars = line.char_indices().peekable();
+        while let Some((pos, c)) = chars.next() {
+            if self.in_block_comment {
+                self.buf.push(c);
+                if c == '*' && chars.peek().map(|(_, n)| *n) == Some('/') {
+                    chars.next();
+                    self.buf.push('/');
+                    self.in_block_comment = false;
+                }
+                continue;
+            }
+            if let Some(tag) = &self.dollar_tag {
+                if line[pos..].starts_with(tag.as_str()) {
+                    for _ in 1..tag.len() {
+                        chars.next();
+                    }
+                    self.buf.push_str(tag);
+                    self.dollar_tag = None;
+                } else {
+                    self.buf.push(c);
+                }
+                continue;
+            }
+            if let Some(q) = self.quote {
+                self.buf.push(c);
+                if c == '\\' && q != '`' && self.dialect == ScriptDialect::MySql {
+                    // 转义字符原样保留
+                    if let Some((_, n)) = chars.next() {
+                        self.buf.push(n);
+                    }
+                } else if c == q {
+                    // 连续两个引号表示引号本身
+                    if chars.peek().map(|(_, n)| *n) == Some(q) {
+                        chars.next();
+                        self.buf.push(q);
+                    } else {
+                        self.quote = None;
+                    }
+                }
+                continue;
+            }
+
+            let rest = &line[pos..];
+            let hash_comment = c == '#' && self.dialect == ScriptDialect::MySql;
+            if hash_comment || (rest.starts_with("--") && rest[2..].chars().next().map_or(true, char::is_whitespace)) {
+                // 行注释直接丢弃
+                break;
+            }
+            if c == '$' && self.dialect == ScriptDialect::Postgres {
+                if let Some(tag) = dollar_tag(rest) {
+                    for _ in 1..tag.len() {
+                        chars.next();
+                    }
+                    self.buf.push_str(tag);
+                    self.dollar_tag = Some(tag.to_string());
+                    continue;
+                }
+            }
+            if rest.starts_with("/*") {
+                // 块注释保留，MySQL 的 /*!...*/ 条件注释是有效语句
+                chars.next();
+                self.buf.push_str("/*");
+                self.in_block_comment = true;
+                continue;
+            }
+            if rest.starts_with(self.delimiter.as_str()) {
+                for _ in 1..self.delimiter.chars().count() {
+                    chars.next();
+                }
+                self.take_statement(&mut done);
+                continue;
+            }
+            if c == '\'' || c == '"' || (c == '`' && self.dialect != ScriptDialect::Postgres) {
+                self.quote = Some(c);
+            }
+            self.buf.push(c);
+        }
+        self.buf.push('\n');
+        done
+    }
+
+    /// 输入结束，返回最后一条没有分隔符的语句
+    pub fn finish(&mut self) -> Option<String> {
+        let mut done = Vec::new();
+        self.take_statement(&mut done);
+        done.pop()
+    }
+
+    fn take_statement(&mut self, done: &mut Vec<String>) {
+        let stmt = self.buf.trim();
+        if !stmt.is_empty() {
+            done.push(stmt.to_string());
+        }
+        self.buf.clear();
+    }
+}
+
+/// 拆分整段脚本
+pub fn split_statements(sql: &str, dialect: ScriptDialect) -> Vec<String> {
+    let mut splitter = StatementSplitter::new(dialect);
+    let mut statements = Vec::new();
+    for line in sql.lines() {
+        statements.extend(splitter.push_line(line));
+    }
+    statements.extend(splitter.finish());
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_delimiters_outside_strings_and_comments() {
+        let sql = "INSERT INTO t VALUES ('a;b', \"c;d\", `e;f`); -- x;y\nSELECT 1; /* ; */ SELECT 2";
+        assert_eq!(
+            split_statements(sql, ScriptDialect::MySql),
+            vec![
+                "INSERT INTO t VALUES ('a;b', \"c;d\", `e;f`)",
+                "SELECT 1",
+                "/* ; */ SELECT 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn mysql_delimiter_hash_comments_and_escapes() {
+        let sql = "DELIMITER $$\nCREATE PROCEDURE p() BEGIN SELECT 1; END$$\nDELIMITER ;\n# 注释;\nSELECT 'it\\'s;'";
+        assert_eq!(
+            split_statements(sql, ScriptDialect::MySql),
+            vec!["CREATE PROCEDURE p() BEGIN SELECT 1; END", "SELECT 'it\\'s;'"]
+        );
+    }
+
+    #[test]
+    fn postgres_dollar_quotes() {
+        let sql = "CREATE FUNCTION f() RETURNS int AS $body$\nBEGIN\n  RETURN 1;\nEND;\n$body$ LANGUAGE plpgsql;\nSELECT $1::int; SELECT $$a;b$$";
+        let statements = split_statements(sql, ScriptDialect::Postgres);
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].ends_with("$body$ LANGUAGE plpgsql"));
+        assert_eq!(statements[1], "SELECT $1::int");
+        assert_eq!(statements[2], "SELECT $$a;b$$");
+    }
+
+    #[test]
+    fn dialect_specific_syntax_is_not_applied_elsewhere() {
+        // PostgreSQL 中 # 是运算符，反斜杠不是转义字符
+        assert_eq!(
+            split_statements("SELECT 5 # 3; SELECT 'a\\'; SELECT 2", ScriptDialect::Postgres),
+            vec!["SELECT 5 # 3", "SELECT 'a\\'", "SELECT 2"]
+        );
+        // MySQL 没有美元符号引用
+        assert_eq!(split_statements("SELECT $$; SELECT 1", ScriptDialect::MySql), vec!["SELECT $$", "SELECT 1"]);
+        assert_eq!(
+            split_statements("DELIMITER $$\nSELECT 1", ScriptDialect::Sqlite),
+            vec!["DELIMITER $$\nSELECT 1"]
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::commands::sqlx::{ensure_read_only_sql, explorer, DbPool};
+use crate::util::uuid_util;
+use futures_util::TryStreamExt;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{Connection, Row};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::{Notify, Semaphore};
+
+// 每批默认行数
+const DEFAULT_BATCH_SIZE: usize = 500;
+// 前端未确认的最大批次数，超过后暂停读取，等待 ack_query_batch
+const MAX_PENDING_BATCHES: usize = 4;
+// 等待确认的最长时间，前端不再确认（页面关闭、未调用 ack_query_batch）时取消查询
+const ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 正在执行的流式查询
+struct RunningQuery {
+    cancelled: AtomicBool,
+    // 取消时通知读取任务
+    cancel: Notify,
+    // 每推送一批占用一个，前端确认后归还
+    credits: Semaphore,
+}
+
+static RUNNING_QUERIES: Lazy<Mutex<HashMap<String, Arc<RunningQuery>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 一批数据，columns 只在第一批中携带
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryBatch {
+    pub id: String,
+    pub offset: u64,
+    pub columns: Option<Value>,
+    pub rows: Vec<Value>,
+}
+
+// 查询结束
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryDone {
+    pub id: String,
+    pub rows: u64,
+    pub cancelled: bool,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// 流式查询：立即返回查询 id，数据通过 query_batch 事件分批推送，结束时发送 query_done
+/// 前端处理完每批后调用 ack_query_batch，未确认的批次过多时暂停推送，长时间未确认时查询被取消
+#[command]
+pub async fn stream_query(
+    app_handle: AppHandle,
+    sql: String,
+    batch_size: Option<usize>,
+    profile: Option<String>,
+) -> Result<String, String> {
+    let pool = ensure_read_only_sql(profile.as_deref(), &sql).await?;
+    let id = uuid_util::new_id();
+    let query = Arc::new(RunningQuery {
+        cancelled: AtomicBool::new(false),
+        cancel: Notify::new(),
+        credits: Semaphore::new(MAX_PENDING_BATCHES),
+    });
+    RUNNING_QUERIES
+        .lock()
+        .unwrap()
+        .insert(id.clone(), Arc::clone(&query));
+
+    let query_id = id.clone();
+    tauri::async_runtime::spawn(async move {
+        let start = Instant::now();
+        let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+        let mut sent: u64 = 0;
+        let result = run_stream(&app_handle, &query_id, &pool, &sql, batch_size, &query, &mut sent).await;
+        RUNNING_QUERIES.lock().unwrap().remove(&query_id);
+        let done = QueryDone {
+            id: query_id,
+            rows: sent,
+            cancelled: query.cancelled.load(Ordering::SeqCst),
+            error: result.err(),
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        };
+        info!("stream query done {} rows, cancelled: {}", done.rows, done.cancelled);
+        if let Err(e) = app_handle.emit("query_done", &done) {
+            eprintln!("Failed to emit event: {}", e);
+        }
+    });
+    Ok(id)
+}
+
+// 执行 work 直到完成或查询被取消，取消时返回 Ok(false)，work 随之被丢弃
+async fn until_cancelled<F>(query: &RunningQuery, work: F) -> Result<bool, String>
+where
+    F: Future<Output = Result<(), String>>,
+{
+    tokio::select! {
+        res = work => res.map(|_| true),
+        _ = query.cancel.notified() => Ok(false),
+    }
+}
+
+// 在独立的连接上逐行读取，每 batch_size 行推送一次，sent 为已推送的行数
+// 取消时除了停止读取，还要让服务端中止仍在执行的语句
+async fn run_stream(
+    app_handle: &AppHandle,
+    id: &str,
+    pool: &DbPool,
+    sql: &str,
+    batch_size: usize,
+    query: &RunningQuery,
+    sent: &mut u64,
+) -> Result<(), String> {
+    // 三种后端的行类型不同，读取过程相同
+    macro_rules! stream_rows {
+        ($conn:expr, $to_json:path) => {
+            async {
+                let mut batch: Vec<Value> = Vec::with_capacity(batch_size);
+                let mut columns: Option<Value> = None;
+                let mut rows = sqlx::query(sql).fetch($conn);
+                while let Some(row) = rows.try_next().await.map_err(|e| e.to_string())? {
+                    if columns.is_none() && *sent == 0 && batch.is_empty() {
+                        columns = Some(explorer::column_json(row.columns()));
+                    }
+                    batch.push($to_json(&row));
+                    if batch.len() >= batch_size {
+                        *sent += emit_batch(app_handle, id, query, *sent, &mut columns, &mut batch).await?;
+                    }
+                }
+                if !batch.is_empty() {
+                    *sent += emit_batch(app_handle, id, query, *sent, &mut columns, &mut batch).await?;
+                }
+                Ok(())
+            }
+        };
+    }
+
+    match pool {
+        DbPool::MySql(pool) => {
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            let conn_id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !until_cancelled(query, stream_rows!(&mut *conn, explorer::mysql_row_to_json)).await? {
+                // 连接停在结果集中间，不再放回连接池
+                let conn = conn.detach();
+                if let Err(e) = sqlx::query(&format!("KILL QUERY {}", conn_id)).execute(pool).await {
+                    warn!("KILL QUERY {} failed: {}", conn_id, e);
+                }
+                let _ = conn.close().await;
+            }
+        }
+        DbPool::Postgres(pool) => {
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !until_cancelled(query, stream_rows!(&mut *conn, explorer::pg_row_to_json)).await? {
+                let conn = conn.detach();
+                if let Err(e) = sqlx::query("SELECT pg_cancel_backend($1)").bind(pid).execute(pool).await {
+                    warn!("pg_cancel_backend({}) failed: {}", pid, e);
+                }
+                let _ = conn.close().await;
+            }
+        }
+        // SQLite 在本进程内执行，停止读取即可
+        DbPool::Sqlite(pool) => {
+            until_cancelled(query, stream_rows!(pool, explorer::sqlite_row_to_json)).await?;
+        }
+    }
+    Ok(())
+}
+
+// 推送一批数据，未确认的批次达到上限时等待前端确认
+// 等待超时后按取消处理：通知 run_stream 走取消流程中止服务端语句，本次推送不再返回
+async fn emit_batch(
+    app_handle: &AppHandle,
+    id: &str,
+    query: &RunningQuery,
+    offset: u64,
+    columns: &mut Option<Value>,
+    batch: &mut Vec<Value>,
+) -> Result<u64, String> {
+    match tokio::time::timeout(ACK_TIMEOUT, query.credits.acquire()).await {
+        Ok(permit) => permit.map_err(|e| e.to_string())?.forget(),
+        Err(_) => {
+            warn!("stream query {} not acknowledged in {:?}, cancelling", id, ACK_TIMEOUT);
+            query.cancelled.store(true, Ordering::SeqCst);
+            query.cancel.notify_one();
+            std::future::pending::<()>().await;
+        }
+    }
+    let event = QueryBatch {
+        id: id.to_string(),
+        offset,
+        // 第一批之后不再携带列信息
+        columns: if offset == 0 { columns.take() } else { None },
+        rows: std::mem::take(batch),
+    };
+    let count = event.rows.len() as u64;
+    app_handle
+        .emit("query_batch", &event)
+        .map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+fn running_query(id: &str) -> Result<Arc<RunningQuery>, String> {
+    RUNNING_QUERIES
+        .lock()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("查询 {} 不存在或已结束", id))
+}
+
+/// 确认已处理完一批数据，允许继续推送
+#[command]
+pub fn ack_query_batch(id: String) -> Result<(), String> {
+    let query = running_query(&id)?;
+    if query.credits.available_permits() < MAX_PENDING_BATCHES {
+        query.credits.add_permits(1);
+    }
+    Ok(())
+}
+
+/// 取消流式查询，服务端仍在执行的语句会被中止
+#[command]
+pub fn cancel_query(id: String) -> Result<(), String> {
+    let query = running_query(&id)?;
+    query.cancelled.store(true, Ordering::SeqCst);
+    // notify_one 会保留通知，读取任务稍后才等待时也能收到
+    query.cancel.notify_one();
+    Ok(())
+}
+
+/// 统计查询结果总数（原查询作为子查询，末尾的分页子句会被去掉）
+#[command]
+pub async fn count_query(sql: String, profile: Option<String>) -> Result<i64, String> {
+    let pool = ensure_read_only_sql(profile.as_deref(), &sql).await?;
+    count_rows(&pool, &sql).await
+}
+
+async fn count_rows(pool: &DbPool, sql: &str) -> Result<i64, String> {
+    let count_sql = explorer::count_sql(sql);
+    debug!("query count: {:?}", count_sql);
+    let total = match pool {
+        DbPool::MySql(pool) => sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool).await,
+        DbPool::Postgres(pool) => sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool).await,
+        DbPool::Sqlite(pool) => sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool).await,
+    };
+    total.map_err(|e| e.to_string())
+}
+
+/// 游标分页：按 key_column 排序，返回 cursor 之后的 page_size 行，不使用 OFFSET
+/// key_column 应唯一且不为空；返回 { columns, total, data, page_size, next_cursor }
+/// next_cursor 为本页最后一行的 key_column 值，没有下一页时为 null
+/// total 只在第一页（cursor 为空）或 with_total 为 true 时计算
+#[command]
+pub async fn query_page(
+    sql: String,
+    key_column: String,
+    cursor: Option<Value>,
+    page_size: u32,
+    desc: Option<bool>,
+    with_total: Option<bool>,
+    profile: Option<String>,
+) -> Result<Value, String> {
+    let pool = ensure_read_only_sql(profile.as_deref(), &sql).await?;
+    let base = explorer::strip_trailing_limit(&sql);
+    let page_size = page_size.max(1);
+    let paged_sql = page_sql(&pool, base, &key_column, cursor.as_ref(), page_size, desc.unwrap_or(false))?;
+    let total = if with_total.unwrap_or(cursor.is_none()) {
+        Some(count_rows(&pool, base).await?)
+    } else {
+        None
+    };
+    let mut result = explorer::query_rows(&pool, &paged_sql).await?;
+    let next_cursor = match result["data"].as_array() {
+        Some(data) if data.len() >= page_size as usize => {
+            data.last().map(|row| row[&key_column].clone()).unwrap_or(Value::Null)
+        }
+        _ => Value::Null,
+    };
+    result["total"] = json!(total);
+    result["page_size"] = json!(page_size);
+    result["next_cursor"] = next_cursor;
+    Ok(result)
+}
+
+// 生成游标分页语句，游标值按字面量写入，便于各后端按列类型比较
+fn page_sql(
+    pool: &DbPool,
+    base: &str,
+    key_column: &str,
+    cursor: Option<&Value>,
+    page_size: u32,
+    desc: bool,
+) -> Result<String, String> {
+    let key = format!("t.{}", explorer::quote_ident(pool, key_column));
+    let filter = match cursor {
+        None | Some(Value::Null) => String::new(),
+        Some(value) => {
+            let literal = match value {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => explorer::quote_str(pool, s),
+                Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+                other => return Err(format!("不支持的游标值: {}", other)),
+            };
+            format!(" WHERE {} {} {}", key, if desc { "<" } else { ">" }, literal)
+        }
+    };
+    Ok(format!(
+        "SELECT * FROM ({}) AS t{} ORDER BY {} {} LIMIT {}",
+        base,
+        filter,
+        key,
+        if desc { "DESC" } else { "ASC" },
+        page_size
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn page_sql_uses_the_cursor_instead_of_offset() {
+        let pool = DbPool::Sqlite(SqlitePoolOptions::new().connect_lazy("sqlite::memory:").unwrap());
+        let first = page_sql(&pool, "SELECT * FROM book", "id", None, 20, false).unwrap();
+        assert_eq!(first, "SELECT * FROM (SELECT * FROM book) AS t ORDER BY t.\"id\" ASC LIMIT 20");
+        let next = page_sql(&pool, "SELECT * FROM book", "title", Some(&json!("it's")), 20, true).unwrap();
+        assert_eq!(
+            next,
+            "SELECT * FROM (SELECT * FROM book) AS t WHERE t.\"title\" < 'it''s' ORDER BY t.\"title\" DESC LIMIT 20"
+        );
+        assert!(page_sql(&pool, "SELECT 1", "id", Some(&json!([1])), 20, false).is_err());
+    }
+}
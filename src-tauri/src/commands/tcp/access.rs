@@ -0,0 +1,66 @@
+use crate::commands::AppState;
+use std::net::IpAddr;
+use tauri::{AppHandle, Manager};
+
+// 比较耗时只与长度有关，避免通过响应时间逐字节猜出令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = a.len() ^ b.len();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= usize::from(x ^ y);
+    }
+    diff == 0
+}
+
+// 服务端访问控制（TCP 服务与 WebSocket 服务共用同一份配置）
+#[derive(Debug, Clone, Default)]
+pub struct ServerAccess {
+    pub token: String,
+    pub allow_list: Vec<String>,
+}
+
+impl ServerAccess {
+    // 从应用配置中读取访问控制参数
+    pub fn from_app(app_handle: &AppHandle) -> Self {
+        match app_handle.try_state::<AppState>() {
+            Some(state) => match state.config.lock() {
+                Ok(config) => Self {
+                    token: config.server_token.clone(),
+                    allow_list: config.server_allow_list.clone(),
+                },
+                Err(_) => Self::default(),
+            },
+            None => Self::default(),
+        }
+    }
+
+    // 是否需要令牌认证
+    pub fn requires_token(&self) -> bool {
+        !self.token.is_empty()
+    }
+
+    // 校验客户端提交的令牌
+    pub fn check_token(&self, token: Option<&str>) -> bool {
+        if !self.requires_token() {
+            return true;
+        }
+        token.is_some_and(|t| constant_time_eq(t.as_bytes(), self.token.as_bytes()))
+    }
+
+    // 校验客户端 IP 是否在白名单内
+    // 白名单条目支持完整 IP（"192.168.1.10"）、前缀（"192.168.1."）以及 "*"
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        if self.allow_list.is_empty() {
+            return true;
+        }
+        let ip_str = ip.to_string();
+        self.allow_list.iter().any(|entry| {
+            let entry = entry.trim();
+            entry == "*"
+                || entry == ip_str
+                || (entry.ends_with('.') && ip_str.starts_with(entry))
+                || (entry == "localhost" && ip.is_loopback())
+        })
+    }
+}
@@ -2,3 +2,5 @@ pub mod server;
 pub use server::*;
 pub mod client;
 pub use client::*;
+pub mod access;
+pub use access::*;
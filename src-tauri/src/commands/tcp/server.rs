@@ -1,12 +1,21 @@
+use super::ServerAccess;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{broadcast, mpsc, RwLock};
 
+// 认证连续失败多少次后断开连接
+const MAX_AUTH_FAILURES: u32 = 3;
+// 连接后必须在该时间内完成认证
+const AUTH_TIMEOUT: Duration = Duration::from_secs(30);
+// 认证消息的最大长度
+const MAX_AUTH_MESSAGE: usize = 4096;
+
 // 服务器状态
 pub struct TcpServerState {
     pub running: bool,
@@ -77,6 +86,7 @@ pub async fn start_tcp_server(
     };
 
     let state_clone = Arc::clone(&state);
+    let access = ServerAccess::from_app(&app_handle);
     tokio::spawn(async move {
         // 获取监听器
         let listener = {
@@ -91,7 +101,7 @@ pub async fn start_tcp_server(
         if let Err(ea) = app_handle.emit("server_msg", format!("{}", "服务已开启")) {
             eprintln!("Failed to emit event: {}", ea);
         }
-        server_main_loop(app_handle, tx_clone, clients_clone, listener, shutdown_rx).await;
+        server_main_loop(app_handle, tx_clone, clients_clone, listener, shutdown_rx, access).await;
 
         // 服务器停止后更新状态
         let mut state = state_clone.write().await;
@@ -110,6 +120,7 @@ async fn server_main_loop(
     clients: Arc<RwLock<HashMap<std::net::SocketAddr, broadcast::Sender<String>>>>,
     listener: TcpListener,
     mut shutdown_rx: mpsc::Receiver<()>,
+    access: ServerAccess,
 ) {
     // 使用 accept() 替代 incoming()
     let shutdown_notified = false;
@@ -145,6 +156,15 @@ async fn server_main_loop(
             result = listener.accept() => {
                 match result {
                     Ok((stream, addr)) => {
+                        // 白名单校验，不在白名单内的连接直接丢弃
+                        if !access.is_allowed(addr.ip()) {
+                            println!("Rejected client not in allow list: {}", addr);
+                            if let Err(e) = app_handle.emit("server_msg", format!("拒绝连接 {}", addr)) {
+                                eprintln!("Failed to emit event: {}", e);
+                            }
+                            drop(stream);
+                            continue;
+                        }
                         println!("New client connected: {}", addr);
                         let app_handle_clone = app_handle.clone();
                         if let Err(e) = app_handle_clone.emit("conn_add", format!("{}", addr)) {
@@ -155,9 +175,10 @@ async fn server_main_loop(
                         let clients_clone = Arc::clone(&clients);
                         // 克隆客户端关闭通道
                         let client_shutdown_tx_clone = client_shutdown_tx.clone();
+                        let access_clone = access.clone();
 
                         tokio::spawn(async move {
-                            handle_client(app_handle_clone, stream, addr, tx_clone, clients_clone, client_shutdown_tx_clone).await;
+                            handle_client(app_handle_clone, stream, addr, tx_clone, clients_clone, client_shutdown_tx_clone, access_clone).await;
                         });
                     }
                     Err(e) => {
@@ -312,9 +333,34 @@ async fn handle_client(
     tx: Arc<broadcast::Sender<String>>, // 广播通道
     clients: Arc<RwLock<HashMap<std::net::SocketAddr, broadcast::Sender<String>>>>, // 客户端映射
     client_shutdown_tx: mpsc::Sender<()>, // 客户端关闭通知通道
+    access: ServerAccess,                 // 访问控制
 ) {
     // println!("Handling client connection from {}", addr);
 
+    // 拆分流为读写部分
+    let (mut reader, mut writer) = tokio::io::split(stream);
+
+    // 创建一个缓冲区用于累积数据
+    let mut buffer = Vec::new();
+
+    // 配置了令牌时先完成认证，认证通过前不加入客户端列表，也收不到任何广播
+    if access.requires_token() {
+        let authed = tokio::time::timeout(
+            AUTH_TIMEOUT,
+            authenticate(&mut reader, &mut writer, &mut buffer, addr, &access),
+        )
+        .await
+        .unwrap_or(false);
+        if !authed {
+            println!("Client {} closed: authentication failed", addr);
+            if let Err(e) = app_handle.emit("conn_del", format!("{}", addr)) {
+                eprintln!("Failed to emit event: {}", e);
+            }
+            let _ = client_shutdown_tx.send(()).await;
+            return;
+        }
+    }
+
     // 为客户端创建一个独立的发送通道
     let (client_tx, mut client_rx) = broadcast::channel(100);
 
@@ -327,11 +373,6 @@ async fn handle_client(
         clients.insert(addr, client_tx.clone());
     }
 
-    // 拆分流为读写部分
-    let (mut reader, mut writer) = tokio::io::split(stream);
-
-    // 创建一个缓冲区用于累积数据
-    let mut buffer = Vec::new();
     // 启动一个任务处理来自服务器的消息
     let writer_task = tokio::spawn(async move {
         // println!("Starting writer task for client {}", addr);
@@ -503,6 +544,55 @@ pub async fn get_connstr(state: State<'_, Arc<RwLock<TcpServerState>>>) -> Resul
     Ok(addresses.join(","))
 }
 
+// 写入一条以 \r\n 结尾的 JSON 消息
+async fn write_line(writer: &mut WriteHalf<TcpStream>, value: &Value) -> std::io::Result<()> {
+    writer.write_all((value.to_string() + "\r\n").as_bytes()).await
+}
+
+// 读取 {"auth": "<token>"} 消息完成认证
+// 连接断开、消息过长或连续失败 MAX_AUTH_FAILURES 次时返回 false，由调用方关闭连接
+// 认证消息之后已收到的数据留在 buffer 中
+async fn authenticate(
+    reader: &mut ReadHalf<TcpStream>,
+    writer: &mut WriteHalf<TcpStream>,
+    buffer: &mut Vec<u8>,
+    addr: std::net::SocketAddr,
+    access: &ServerAccess,
+) -> bool {
+    let mut failures = 0;
+    let mut buf = [0u8; 1024];
+    loop {
+        while let Some(delimiter_pos) = find_delimiter(buffer) {
+            let message = String::from_utf8_lossy(&buffer[..delimiter_pos]).into_owned();
+            buffer.drain(..delimiter_pos + 2);
+
+            let token = serde_json::from_str::<Value>(&message)
+                .ok()
+                .and_then(|v| v.get("auth").and_then(|t| t.as_str()).map(|t| t.to_string()));
+            if access.check_token(token.as_deref()) {
+                return write_line(writer, &json!({ "system": "auth_ok" })).await.is_ok();
+            }
+            failures += 1;
+            println!(
+                "Client {} failed authentication ({}/{})",
+                addr, failures, MAX_AUTH_FAILURES
+            );
+            if write_line(writer, &json!({ "error": "Unauthorized" })).await.is_err()
+                || failures >= MAX_AUTH_FAILURES
+            {
+                return false;
+            }
+        }
+        if buffer.len() > MAX_AUTH_MESSAGE {
+            return false;
+        }
+        match reader.read(&mut buf).await {
+            Ok(0) | Err(_) => return false,
+            Ok(n) => buffer.extend_from_slice(&buf[..n]),
+        }
+    }
+}
+
 // 查找消息分隔符 \r\n 的位置
 pub fn find_delimiter(buffer: &[u8]) -> Option<usize> {
     // 避免空缓冲区导致的溢出
@@ -6,8 +6,12 @@ use tauri::Emitter;
 use tauri::Manager;
 use tauri::{command, AppHandle, State};
 
+use crate::commands::{demo_progress_job, spawn_job};
 use crate::dto::ThreadState;
 
+pub mod timer;
+pub use timer::*;
+
 // 全局状态控制
 static RUNNING: AtomicBool = AtomicBool::new(false);
 
@@ -26,7 +30,9 @@ pub fn start_time(app_handle: AppHandle) {
                 eprintln!("Failed to emit event: {}", e);
                 break;
             }
-            thread::sleep(Duration::from_millis(1));
+            // 时间只精确到秒，睡到下一个整秒再发送
+            let wait = 1000 - now.timestamp_subsec_millis().min(999) as u64;
+            thread::sleep(Duration::from_millis(wait));
         }
         // 重置状态允许重新启动
         RUNNING.store(false, Ordering::SeqCst);
@@ -124,35 +130,8 @@ pub fn get_last_update(thread_state: State<'_, ThreadState>) -> Result<String, S
     Ok(last_updated.clone())
 }
 
+// 演示进度条，改为后台任务执行，不再阻塞命令线程
 #[tauri::command]
-pub fn progress_update(app_handle: AppHandle) {
-    let app_handle_clone = app_handle.clone();
-    let mut vector = vec![
-        (2, "操作准备中"),
-        (11, "下班回家"),
-        (24, "钉钉打卡"),
-        (32, "自行车行驶"),
-    ];
-    vector.push((44, "菜市场买菜"));
-    vector.push((54, "自行车停放"));
-    vector.push((64, "收快递回家"));
-    vector.push((77, "播放音乐"));
-    vector.push((87, "洗菜做饭"));
-    vector.push((92, "打开电视并吃饭"));
-    vector.push((100, "准备睡觉"));
-
-    for i in &vector {
-        let now: DateTime<Local> = Local::now();
-        let formatted_now = now.format("%Y-%m-%d %H:%M:%S").to_string();
-        let pro = format!("{},{}", formatted_now, i.1);
-        // println!("进度: {}%, 操作: {}", i.0, i.1);
-        if let Err(e) = app_handle_clone.emit("progress_msg", &pro) {
-            eprintln!("Failed to emit event: {}", e);
-        }
-        thread::sleep(Duration::from_millis(10));
-        if let Err(e) = app_handle_clone.emit("progress_update", i.0) {
-            eprintln!("Failed to emit event: {}", e);
-        }
-        thread::sleep(Duration::from_millis(1000));
-    }
+pub fn progress_update(app_handle: AppHandle) -> String {
+    spawn_job(&app_handle, "demo", demo_progress_job)
 }
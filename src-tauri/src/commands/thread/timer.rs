@@ -0,0 +1,261 @@
+use crate::util::{cron_util, uuid_util};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use log::info;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, State};
+use tokio::sync::watch;
+
+// 定时器调度方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimerSchedule {
+    // 固定间隔（毫秒）
+    Interval { millis: u64 },
+    // cron 表达式
+    Cron { expr: String },
+}
+
+// 定时器控制信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerStatus {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+// 返回给前端的定时器信息
+#[derive(Debug, Clone, Serialize)]
+pub struct TimerInfo {
+    pub id: String,
+    pub name: String,
+    pub schedule: TimerSchedule,
+    pub payload: Value,
+    pub status: TimerStatus,
+    pub ticks: u64,
+    pub created_at: String,
+    pub last_tick: Option<String>,
+}
+
+struct TimerEntry {
+    info: Arc<Mutex<TimerInfo>>,
+    control: watch::Sender<TimerStatus>,
+}
+
+// 命名定时器注册表
+#[derive(Default)]
+pub struct TimerRegistry {
+    timers: Mutex<HashMap<String, TimerEntry>>,
+}
+
+// 间隔下限，避免前端传入 0 导致空转
+const MIN_INTERVAL_MS: u64 = 10;
+
+#[command]
+pub fn create_timer(
+    app_handle: AppHandle,
+    registry: State<'_, TimerRegistry>,
+    name: String,
+    interval: Option<u64>,
+    cron: Option<String>,
+    payload: Option<Value>,
+) -> Result<TimerInfo, String> {
+    let schedule = match (interval, cron) {
+        (_, Some(expr)) => {
+            // 先校验表达式
+            cron_util::parse_cron(&expr)?;
+            TimerSchedule::Cron { expr }
+        }
+        (Some(millis), None) => TimerSchedule::Interval {
+            millis: millis.max(MIN_INTERVAL_MS),
+        },
+        (None, None) => return Err("必须指定 interval 或 cron".into()),
+    };
+
+    // 检查重名和插入在同一把锁内完成，避免并发创建同名定时器
+    let mut timers = registry.timers.lock().unwrap();
+    if timers.values().any(|t| t.info.lock().unwrap().name == name) {
+        return Err(format!("定时器 {} 已存在", name));
+    }
+
+    let info = TimerInfo {
+        id: uuid_util::new_id(),
+        name,
+        schedule,
+        payload: payload.unwrap_or(Value::Null),
+        status: TimerStatus::Running,
+        ticks: 0,
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        last_tick: None,
+    };
+    let (control, control_rx) = watch::channel(TimerStatus::Running);
+    let shared = Arc::new(Mutex::new(info.clone()));
+
+    match timers.entry(info.id.clone()) {
+        Entry::Occupied(_) => return Err(format!("定时器 {} 已存在", info.id)),
+        Entry::Vacant(slot) => {
+            slot.insert(TimerEntry {
+                info: Arc::clone(&shared),
+                control,
+            });
+        }
+    }
+    drop(timers);
+
+    tauri::async_runtime::spawn(run_timer(app_handle.clone(), shared, control_rx));
+    let _ = app_handle.emit("timer_changed", &info);
+    Ok(info)
+}
+
+// 定时器主循环
+async fn run_timer(
+    app_handle: AppHandle,
+    info: Arc<Mutex<TimerInfo>>,
+    mut control: watch::Receiver<TimerStatus>,
+) {
+    let schedule = info.lock().unwrap().schedule.clone();
+    let cron = match &schedule {
+        TimerSchedule::Cron { expr } => cron_util::parse_cron(expr).ok(),
+        TimerSchedule::Interval { .. } => None,
+    };
+
+    loop {
+        let status = *control.borrow_and_update();
+        match status {
+            TimerStatus::Cancelled => break,
+            TimerStatus::Paused => {
+                // 暂停时等待控制信号
+                if control.changed().await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            TimerStatus::Running => {}
+        }
+
+        let delay = match (&schedule, &cron) {
+            (TimerSchedule::Interval { millis }, _) => Duration::from_millis(*millis),
+            (TimerSchedule::Cron { .. }, Some(cron)) => match cron_util::duration_until_next(cron) {
+                Some(d) => d,
+                None => break, // 不会再触发
+            },
+            (TimerSchedule::Cron { .. }, None) => break,
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {
+                let event_data = {
+                    let mut info = info.lock().unwrap();
+                    info.ticks += 1;
+                    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                    info.last_tick = Some(timestamp.clone());
+                    json!({
+                        "id": info.id,
+                        "name": info.name,
+                        "payload": info.payload,
+                        "tick": info.ticks,
+                        "timestamp": timestamp,
+                    })
+                };
+                if app_handle.emit("timer_tick", &event_data).is_err() {
+                    break;
+                }
+            }
+            res = control.changed() => {
+                // 状态变化后重新进入循环，暂停/取消都会打断当前等待
+                if res.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut info = info.lock().unwrap();
+    info.status = TimerStatus::Cancelled;
+    info!("Timer {} stopped", info.name);
+}
+
+// 修改定时器状态
+fn set_timer_status(
+    app_handle: &AppHandle,
+    registry: &TimerRegistry,
+    id: &str,
+    status: TimerStatus,
+) -> Result<TimerInfo, String> {
+    let mut timers = registry.timers.lock().unwrap();
+    let entry = timers
+        .get(id)
+        .ok_or_else(|| format!("定时器 {} 不存在", id))?;
+    let info = {
+        let mut info = entry.info.lock().unwrap();
+        info.status = status;
+        info.clone()
+    };
+    let _ = entry.control.send(status);
+    if status == TimerStatus::Cancelled {
+        timers.remove(id);
+    }
+    let _ = app_handle.emit("timer_changed", &info);
+    Ok(info)
+}
+
+#[command]
+pub fn pause_timer(
+    app_handle: AppHandle,
+    registry: State<'_, TimerRegistry>,
+    id: String,
+) -> Result<TimerInfo, String> {
+    set_timer_status(&app_handle, &registry, &id, TimerStatus::Paused)
+}
+
+#[command]
+pub fn resume_timer(
+    app_handle: AppHandle,
+    registry: State<'_, TimerRegistry>,
+    id: String,
+) -> Result<TimerInfo, String> {
+    set_timer_status(&app_handle, &registry, &id, TimerStatus::Running)
+}
+
+#[command]
+pub fn cancel_timer(
+    app_handle: AppHandle,
+    registry: State<'_, TimerRegistry>,
+    id: String,
+) -> Result<TimerInfo, String> {
+    set_timer_status(&app_handle, &registry, &id, TimerStatus::Cancelled)
+}
+
+// 修改定时器携带的数据
+#[command]
+pub fn set_timer_payload(
+    registry: State<'_, TimerRegistry>,
+    id: String,
+    payload: Value,
+) -> Result<TimerInfo, String> {
+    let timers = registry.timers.lock().unwrap();
+    let entry = timers
+        .get(&id)
+        .ok_or_else(|| format!("定时器 {} 不存在", id))?;
+    let mut info = entry.info.lock().unwrap();
+    info.payload = payload;
+    Ok(info.clone())
+}
+
+// 列出所有未取消的定时器
+#[command]
+pub fn list_timers(registry: State<'_, TimerRegistry>) -> Vec<TimerInfo> {
+    let timers = registry.timers.lock().unwrap();
+    let mut list: Vec<TimerInfo> = timers
+        .values()
+        .map(|t| t.info.lock().unwrap().clone())
+        .filter(|t| t.status != TimerStatus::Cancelled)
+        .collect();
+    list.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    list
+}
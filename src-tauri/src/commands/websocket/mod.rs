@@ -0,0 +1,402 @@
+use crate::commands::{AppState, ServerAccess};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, EventId, Listener, Manager, State};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+// WebSocket 服务状态
+pub struct WsServerState {
+    pub running: bool,
+    pub tx: Option<broadcast::Sender<String>>, // 事件广播通道
+    pub clients: Arc<RwLock<HashMap<SocketAddr, mpsc::Sender<String>>>>, // 客户端映射
+    pub shutdown_tx: Option<mpsc::Sender<()>>,
+    pub listeners: Vec<EventId>, // 已注册的应用事件监听
+}
+
+impl Default for WsServerState {
+    fn default() -> Self {
+        Self {
+            running: false,
+            tx: None,
+            clients: Arc::new(RwLock::new(HashMap::new())),
+            shutdown_tx: None,
+            listeners: vec![],
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn start_ws_server(
+    state: State<'_, Arc<RwLock<WsServerState>>>,
+    app_handle: AppHandle,
+    ip: String,
+    port: u32,
+) -> Result<(), String> {
+    {
+        let state = state.read().await;
+        if state.running {
+            return Err("WebSocket server is already running".into());
+        }
+    }
+
+    let constr = format!("{}:{}", ip, port);
+    println!("Starting websocket server on {}", constr);
+    let listener = TcpListener::bind(&constr).await.map_err(|e| {
+        if let Err(ea) = app_handle.emit("ws_msg", format!("绑定失败 {}: {}", constr, e)) {
+            eprintln!("Failed to emit event: {}", ea);
+        }
+        format!("Failed to bind to {}: {}", constr, e)
+    })?;
+
+    let (tx, _) = broadcast::channel::<String>(256);
+    let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+    // 把需要转发的应用事件挂到广播通道上
+    let events = match app_handle.try_state::<AppState>() {
+        Some(app_state) => app_state
+            .config
+            .lock()
+            .map(|c| c.ws_events.clone())
+            .unwrap_or_default(),
+        None => vec![],
+    };
+    let mut listeners = Vec::new();
+    for event in events {
+        let tx_clone = tx.clone();
+        let name = event.clone();
+        let id = app_handle.listen_any(event, move |e| {
+            // 载荷本身是 JSON，解析失败时按字符串转发
+            let payload = serde_json::from_str::<Value>(e.payload())
+                .unwrap_or_else(|_| Value::String(e.payload().to_string()));
+            let _ = tx_clone.send(json!({ "event": name, "payload": payload }).to_string());
+        });
+        listeners.push(id);
+    }
+
+    let clients = {
+        let mut state = state.write().await;
+        state.running = true;
+        state.tx = Some(tx.clone());
+        state.shutdown_tx = Some(shutdown_tx);
+        state.listeners = listeners;
+        Arc::clone(&state.clients)
+    };
+
+    let access = ServerAccess::from_app(&app_handle);
+    let state_clone = Arc::clone(&state);
+    tokio::spawn(async move {
+        if let Err(e) = app_handle.emit("ws_msg", "服务已开启") {
+            eprintln!("Failed to emit event: {}", e);
+        }
+        ws_main_loop(app_handle.clone(), tx, clients, listener, shutdown_rx, access).await;
+
+        // 服务停止后清理状态和事件监听
+        let mut state = state_clone.write().await;
+        for id in state.listeners.drain(..) {
+            app_handle.unlisten(id);
+        }
+        state.running = false;
+        state.tx = None;
+        state.shutdown_tx = None;
+        println!("WebSocket server stopped");
+    });
+
+    Ok(())
+}
+
+async fn ws_main_loop(
+    app_handle: AppHandle,
+    tx: broadcast::Sender<String>,
+    clients: Arc<RwLock<HashMap<SocketAddr, mpsc::Sender<String>>>>,
+    listener: TcpListener,
+    mut shutdown_rx: mpsc::Receiver<()>,
+    access: ServerAccess,
+) {
+    let (client_shutdown_tx, client_shutdown_rx) = tokio::sync::watch::channel(false);
+    loop {
+        tokio::select! {
+            biased;
+
+            _ = shutdown_rx.recv() => {
+                println!("Shutting down websocket server");
+                let _ = client_shutdown_tx.send(true);
+                break;
+            }
+
+            result = listener.accept() => {
+                match result {
+                    Ok((stream, addr)) => {
+                        if !access.is_allowed(addr.ip()) {
+                            println!("Rejected websocket client not in allow list: {}", addr);
+                            if let Err(e) = app_handle.emit("ws_msg", format!("拒绝连接 {}", addr)) {
+                                eprintln!("Failed to emit event: {}", e);
+                            }
+                            continue;
+                        }
+                        let app_handle_clone = app_handle.clone();
+                        let rx = tx.subscribe();
+                        let clients_clone = Arc::clone(&clients);
+                        let access_clone = access.clone();
+                        let shutdown = client_shutdown_rx.clone();
+                        tokio::spawn(async move {
+                            handle_ws_client(app_handle_clone, stream, addr, rx, clients_clone, access_clone, shutdown).await;
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("Error accepting websocket connection: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    // 等待客户端任务处理关闭通知
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    clients.write().await.clear();
+    println!("All websocket clients closed");
+}
+
+// 处理单个 WebSocket 客户端
+async fn handle_ws_client(
+    app_handle: AppHandle,
+    stream: TcpStream,
+    addr: SocketAddr,
+    mut events_rx: broadcast::Receiver<String>,
+    clients: Arc<RwLock<HashMap<SocketAddr, mpsc::Sender<String>>>>,
+    access: ServerAccess,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    // 握手阶段校验令牌：ws://host:port/?token=xxx
+    // 回调的错误类型由 tungstenite 规定，无法装箱
+    #[allow(clippy::result_large_err)]
+    let auth_callback = |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        let token = req.uri().query().and_then(|q| {
+            q.split('&')
+                .filter_map(|kv| kv.split_once('='))
+                .find(|(k, _)| *k == "token")
+                .map(|(_, v)| v.to_string())
+        });
+        if access.check_token(token.as_deref()) {
+            Ok(resp)
+        } else {
+            let mut err = ErrorResponse::new(Some("Unauthorized".to_string()));
+            *err.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(err)
+        }
+    };
+    let ws_stream = match tokio_tungstenite::accept_hdr_async(stream, auth_callback).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            eprintln!("WebSocket handshake with {} failed: {}", addr, e);
+            return;
+        }
+    };
+    println!("New websocket client connected: {}", addr);
+    if let Err(e) = app_handle.emit("ws_conn_add", format!("{}", addr)) {
+        eprintln!("Failed to emit event: {}", e);
+    }
+
+    // 定向消息通道
+    let (client_tx, mut client_rx) = mpsc::channel::<String>(100);
+    clients.write().await.insert(addr, client_tx.clone());
+
+    let (mut writer, mut reader) = ws_stream.split();
+    // 客户端订阅的事件，为空表示全部
+    let mut subscriptions: HashSet<String> = HashSet::new();
+
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => {
+                let _ = writer.send(Message::Text(json!({ "system": "server_shutdown" }).to_string())).await;
+                let _ = writer.close().await;
+                break;
+            }
+
+            msg = events_rx.recv() => {
+                match msg {
+                    Ok(msg) => {
+                        // 订阅只过滤带 event 字段的应用事件，ws_send_to_clients 广播的消息总是发送
+                        if !subscriptions.is_empty() {
+                            let event = serde_json::from_str::<Value>(&msg)
+                                .ok()
+                                .and_then(|v| v["event"].as_str().map(|s| s.to_string()));
+                            if event.is_some_and(|e| !subscriptions.contains(&e)) {
+                                continue;
+                            }
+                        }
+                        if let Err(e) = writer.send(Message::Text(msg)).await {
+                            eprintln!("Error sending to {}: {}", addr, e);
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        println!("WebSocket client {} lagged {} events", addr, n);
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            msg = client_rx.recv() => {
+                match msg {
+                    Some(msg) => {
+                        if let Err(e) = writer.send(Message::Text(msg)).await {
+                            eprintln!("Error sending to {}: {}", addr, e);
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+
+            frame = reader.next() => {
+                match frame {
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = handle_ws_command(&app_handle, addr, &text, &mut subscriptions);
+                        if let Some(reply) = reply {
+                            if let Err(e) = writer.send(Message::Text(reply)).await {
+                                eprintln!("Error sending to {}: {}", addr, e);
+                                break;
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Ping(data))) => {
+                        let _ = writer.send(Message::Pong(data)).await;
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        eprintln!("Error reading from {}: {}", addr, e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    clients.write().await.remove(&addr);
+    println!("WebSocket client {} disconnected", addr);
+    if let Err(e) = app_handle.emit("ws_conn_del", format!("{}", addr)) {
+        eprintln!("Failed to emit event: {}", e);
+    }
+}
+
+// 处理浏览器发来的命令，返回需要回复给该客户端的消息
+// 控制命令：{"cmd":"subscribe","events":[...]}、{"cmd":"unsubscribe","events":[...]}、{"cmd":"ping"}
+// 其余 JSON 消息通过 ws_data 事件转交给前端处理
+fn handle_ws_command(
+    app_handle: &AppHandle,
+    addr: SocketAddr,
+    text: &str,
+    subscriptions: &mut HashSet<String>,
+) -> Option<String> {
+    let data = match serde_json::from_str::<Value>(text) {
+        Ok(data) => data,
+        Err(e) => {
+            return Some(json!({ "error": "Invalid JSON", "details": e.to_string() }).to_string());
+        }
+    };
+
+    let events = || -> Vec<String> {
+        data["events"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default()
+    };
+
+    match data["cmd"].as_str() {
+        Some("ping") => Some(json!({ "cmd": "pong" }).to_string()),
+        Some("subscribe") => {
+            subscriptions.extend(events());
+            Some(json!({ "cmd": "subscribed", "events": subscriptions.iter().collect::<Vec<_>>() }).to_string())
+        }
+        Some("unsubscribe") => {
+            for e in events() {
+                subscriptions.remove(&e);
+            }
+            Some(json!({ "cmd": "subscribed", "events": subscriptions.iter().collect::<Vec<_>>() }).to_string())
+        }
+        _ => {
+            let payload = json!({ "client": addr.to_string(), "data": data });
+            if let Err(e) = app_handle.emit("ws_data", payload) {
+                eprintln!("Failed to emit event: {}", e);
+            }
+            None
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_ws_server(state: State<'_, Arc<RwLock<WsServerState>>>) -> Result<(), String> {
+    let shutdown_tx = {
+        let state = state.read().await;
+        if !state.running {
+            return Err("WebSocket server is not running".into());
+        }
+        state.shutdown_tx.clone()
+    };
+    if let Some(tx) = shutdown_tx {
+        if tx.send(()).await.is_err() {
+            eprintln!("Failed to send shutdown signal");
+        }
+    }
+    Ok(())
+}
+
+// 向所有 WebSocket 客户端广播消息，不受客户端事件订阅的限制
+#[tauri::command]
+pub async fn ws_send_to_clients(
+    state: State<'_, Arc<RwLock<WsServerState>>>,
+    message: String,
+) -> Result<(), String> {
+    let _: Value = serde_json::from_str(&message).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let state = state.read().await;
+    let tx = state
+        .tx
+        .as_ref()
+        .ok_or_else(|| "WebSocket server is not running".to_string())?;
+    // 没有客户端时发送会失败，这里忽略
+    let _ = tx.send(message);
+    Ok(())
+}
+
+// 向指定 WebSocket 客户端发送消息
+#[tauri::command]
+pub async fn ws_send_to_client(
+    state: State<'_, Arc<RwLock<WsServerState>>>,
+    client_addr: String,
+    message: String,
+) -> Result<(), String> {
+    let _: Value = serde_json::from_str(&message).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let addr: SocketAddr = client_addr
+        .parse()
+        .map_err(|e| format!("Invalid client address: {}", e))?;
+    let client_tx = {
+        let state = state.read().await;
+        if !state.running {
+            return Err("WebSocket server is not running".into());
+        }
+        let clients = state.clients.read().await;
+        clients
+            .get(&addr)
+            .cloned()
+            .ok_or_else(|| format!("Client {} not found", addr))?
+    };
+    client_tx
+        .send(message)
+        .await
+        .map_err(|_| format!("Failed to send to client {}", addr))
+}
+
+#[tauri::command]
+pub async fn get_ws_clients(state: State<'_, Arc<RwLock<WsServerState>>>) -> Result<Vec<String>, String> {
+    let state = state.read().await;
+    let clients = state.clients.read().await;
+    Ok(clients.keys().map(|addr| addr.to_string()).collect())
+}
@@ -1,373 +1,163 @@
 use crate::{
-    commands::sqlx::POOL,
-    dto::{book::Book, BookQuery, Page},
+    commands::sqlx::{get_data_pool, DbPool},
+    dao::{fetch_rows, QuerySpec, Repository},
+    dto::{book::Book, BookFacets, BookQuery, BookStats, Page},
+    entity,
 };
 use anyhow::{Context, Result};
-use sqlx::MySqlPool;
-use sqlx::{MySql, QueryBuilder};
 
-/// Book 表的数据访问对象
-pub struct BookDao;
-
-impl BookDao {
-    /// 创建新书籍
-    pub async fn create(book: &Book) -> Result<u64> {
-        let pool = Self::get_pool().await?;
-
-        let result = sqlx::query(
-            r#"
-            INSERT INTO book (
-                price, sales, publish_date, title, author, category, rating, img, status
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(book.price)
-        .bind(book.sales)
-        .bind(book.publish_date)
-        .bind(&book.title)
-        .bind(&book.author)
-        .bind(&book.category)
-        .bind(&book.rating)
-        .bind(&book.img)
-        .bind(&book.status)
-        .execute(&pool)
-        .await
-        .context("Failed to create book")?;
-
-        Ok(result.last_insert_id())
+entity! {
+    Book {
+        table: "book",
+        id: id,
+        columns: [price, sales, publish_date, title, author, category, rating, img, status],
+        search: [title, author, category],
+        order_by: "publish_date DESC",
+        on_change: crate::dao::book_changed,
     }
+}
 
-    /// 根据 ID 获取书籍
-    pub async fn get_by_id(id: u32) -> Result<Option<Book>> {
-        let pool = Self::get_pool().await?;
-
-        let book = sqlx::query_as::<_, Book>(
-            r#"
-            SELECT id, price, sales, publish_date, title, author, category, rating, img, status
-            FROM book
-            WHERE id = ?
-            "#,
-        )
-        .bind(id)
-        .fetch_optional(&pool)
-        .await
-        .context("Failed to get book by ID")?;
-
-        Ok(book)
+/// Book 表的数据访问对象，增删改查由 Repository 提供
+pub type BookDao = Repository<Book>;
+
+impl From<&BookQuery> for QuerySpec {
+    fn from(query: &BookQuery) -> Self {
+        QuerySpec::new()
+            .eq("id", query.id)
+            .like("title", query.title.as_deref())
+            .like("author", query.author.as_deref())
+            .like("img", query.img.as_deref())
+            .eq("category", query.category.clone())
+            .eq("status", query.status.clone())
+            .eq("price", query.price)
+            .range("price", query.min_price, query.max_price)
+            .eq("rating", query.rating)
+            .range("rating", query.min_rating, query.max_rating)
+            .eq("sales", query.sales)
+            .range("sales", query.min_sales, query.max_sales)
+            .eq("publish_date", query.publish_date)
+            .range("publish_date", query.min_publish_date, query.max_publish_date)
+            .sorts(query.sort.iter().cloned())
     }
+}
 
-    /// 更新书籍信息
-    pub async fn update(book: &Book) -> Result<bool> {
-        let pool = Self::get_pool().await?;
-
-        let rows_affected = sqlx::query(
-            r#"
-            UPDATE book SET
-                price = ?,
-                sales = ?,
-                publish_date = ?,
-                title = ?,
-                author = ?,
-                category = ?,
-                rating = ?,
-                img = ?,
-                status = ?
-            WHERE id = ?
-            "#,
-        )
-        .bind(book.price)
-        .bind(book.sales)
-        .bind(book.publish_date)
-        .bind(&book.title)
-        .bind(&book.author)
-        .bind(&book.category)
-        .bind(book.rating)
-        .bind(&book.img)
-        .bind(&book.status)
-        .bind(book.id)
-        .execute(&pool)
-        .await
-        .context("Failed to update book")?
-        .rows_affected();
-
-        Ok(rows_affected > 0)
+impl Repository<Book> {
+    /// 按 BookQuery 中给出的条件分页查询
+    pub async fn dynamics_search(query: &BookQuery, page: i32, page_size: i32) -> Result<Page<Book>> {
+        Self::query(&QuerySpec::from(query), page, page_size).await
     }
 
-    /// 根据 ID 删除书籍
-    pub async fn delete(id: u32) -> Result<bool> {
-        let pool = Self::get_pool().await?;
-
-        let rows_affected = sqlx::query(
-            r#"
-            DELETE FROM book
-            WHERE id = ?
-            "#,
-        )
-        .bind(id)
-        .execute(&pool)
-        .await
-        .context("Failed to delete book")?
-        .rows_affected();
-
-        Ok(rows_affected > 0)
+    /// 当前条件下按分类、状态、评分分组计数
+    pub async fn facets(query: &BookQuery) -> Result<BookFacets> {
+        let spec = QuerySpec::from(query);
+        Ok(BookFacets {
+            category: Self::facet(&spec, "category").await?,
+            status: Self::facet(&spec, "status").await?,
+            rating: Self::facet(&spec, "rating").await?,
+        })
     }
 
-    pub async fn deletes(ids: &[u32]) -> Result<bool> {
-        if ids.is_empty() {
-            return Ok(false);
-        }
-
-        let pool = Self::get_pool().await?;
-
-        // 构建 IN 子句的占位符
-        let placeholders = (0..ids.len()).map(|_| "?").collect::<Vec<_>>().join(",");
+    /// 当前条件下的总销量、销售额、平均评分和按出版月份的销量
+    pub async fn stats(query: &BookQuery) -> Result<BookStats> {
+        let spec = QuerySpec::from(query);
+        let pool = get_data_pool().await?;
+        // 两种后端的取整和按月格式化写法不同
+        let (int, month) = match pool {
+            DbPool::MySql(_) => ("SIGNED", "DATE_FORMAT(publish_date, '%Y-%m')"),
+            _ => ("INTEGER", "strftime('%Y-%m', publish_date)"),
+        };
 
-        // 将格式化的SQL语句存储在变量中，延长其生命周期
+        let built = Self::build(&spec)?;
         let sql = format!(
-            r#"
-            DELETE FROM book
-            WHERE id IN ({})
-            "#,
-            placeholders
+            "SELECT COUNT(*) AS books, CAST(COALESCE(SUM(sales), 0) AS {int}) AS total_sales, \
+             COALESCE(SUM(price * sales), 0.0) AS revenue, AVG(rating) + 0e0 AS avg_rating FROM book{}",
+            built.where_sql,
+            int = int
         );
-
-        // 使用存储的SQL字符串创建查询
-        let mut query = sqlx::query(&sql);
-
-        // 绑定所有ID参数
-        for id in ids {
-            query = query.bind(id);
-        }
-
-        let rows_affected = query
-            .execute(&pool)
+        let mut stats: BookStats = fetch_rows(&pool, &sql, &built.cells)
             .await
-            .context("Failed to delete books")?
-            .rows_affected();
-
-        Ok(rows_affected > 0)
-    }
+            .context("Failed to get book stats")?
+            .pop()
+            .context("No book stats returned")?;
 
-    /// 分页获取书籍列表
-    pub async fn list(page: u32, page_size: u32) -> Result<Vec<Book>> {
-        let pool = Self::get_pool().await?;
-        let offset = (page - 1) * page_size;
-        println!("{:?}-{:?}", offset, page_size);
-        // 获取数据
-        let books = sqlx::query_as::<_, Book>(
-            r#"
-            SELECT id, price, sales, publish_date, title, author, category, rating, img, status
-            FROM book
-            ORDER BY publish_date DESC
-            LIMIT ? OFFSET ?
-            "#,
-        )
-        .bind(page_size)
-        .bind(offset)
-        .fetch_all(&pool)
-        .await
-        .context("Failed to list books")?;
-
-        Ok(books)
-    }
-
-    pub async fn search(query: &str, page: i32, page_size: i32) -> Result<Page<Book>> {
-        let pool = Self::get_pool().await?;
-        let offset = (page - 1) * page_size;
-        let search_pattern = format!("%{}%", query);
-
-        let str = r#"SELECT id, price, sales, publish_date, title, author, category, rating, img, status
-            FROM book
-            WHERE title LIKE ? OR author LIKE ? OR category LIKE ?
-            ORDER BY publish_date DESC
-            LIMIT ? OFFSET ?"#;
-        // 获取数据
-        let books = sqlx::query_as::<_, Book>(&str)
-            .bind(&search_pattern)
-            .bind(&search_pattern)
-            .bind(&search_pattern)
-            .bind(page_size)
-            .bind(offset)
-            .fetch_all(&mut *pool.acquire().await?)
+        let built = Self::build(&spec.is_null("publish_date", false))?;
+        let sql = format!(
+            "SELECT {month} AS month, COUNT(*) AS books, CAST(COALESCE(SUM(sales), 0) AS {int}) AS sales, \
+             COALESCE(SUM(price * sales), 0.0) AS revenue FROM book{} GROUP BY {month} ORDER BY month",
+            built.where_sql,
+            month = month,
+            int = int
+        );
+        stats.sales_by_month = fetch_rows(&pool, &sql, &built.cells)
             .await
-            .context("Failed to search books")?;
-
-        // 获取总数
-        let total: i32 = sqlx::query_scalar(
-            r#"
-            SELECT COUNT(*) 
-            FROM book
-            WHERE title LIKE ? OR author LIKE ? OR category LIKE ?
-            "#,
-        )
-        .bind(&search_pattern)
-        .bind(&search_pattern)
-        .bind(&search_pattern)
-        .fetch_one(&mut *pool.acquire().await?)
-        .await
-        .context("Failed to get search count")?;
-
-        let pres = Page {
-            data: books,
-            page_count: ((total as f32) / (page_size as f32)).ceil() as i32,
-            current_page: page,
-            total,
-            page_size,
-        };
-        Ok(pres)
+            .context("Failed to get monthly book sales")?;
+        Ok(stats)
     }
+}
 
-    pub async fn dynamics_search(
-        query: &BookQuery,
-        page: i32,
-        page_size: i32,
-    ) -> Result<Page<Book>> {
-        println!("{:?}", query);
-        let pool = Self::get_pool().await?;
-        let offset = (page - 1) * page_size;
-
-        let mut data_builder: QueryBuilder<MySql> =
-            QueryBuilder::new("SELECT * FROM book WHERE 1=1 ");
-        let mut count_builder: QueryBuilder<MySql> =
-            QueryBuilder::new("SELECT COUNT(*) FROM book WHERE 1=1 ");
-
-        if let Some(id) = query.id {
-            data_builder.push(" AND id = ");
-            data_builder.push_bind(format!("{}", id));
-            count_builder.push(" AND id = ");
-            count_builder.push_bind(format!("{}", id));
-        }
-        if let Some(title) = &query.title {
-            data_builder.push(" AND title like ");
-            data_builder.push_bind(format!("%{}%", title));
-            count_builder.push(" AND title like ");
-            count_builder.push_bind(format!("%{}%", title));
-        }
-        if let Some(author) = &query.author {
-            data_builder.push(" AND author like ");
-            data_builder.push_bind(format!("%{}%", author));
-            count_builder.push(" AND author like ");
-            count_builder.push_bind(format!("%{}%", author));
-        }
-        if let Some(img) = &query.img {
-            data_builder.push(" AND img like ");
-            data_builder.push_bind(format!("%{}%", img));
-            count_builder.push(" AND img like ");
-            count_builder.push_bind(format!("%{}%", img));
-        }
-        if let Some(category) = &query.category {
-            data_builder.push(" AND category = ");
-            data_builder.push_bind(format!("{}", category));
-            count_builder.push(" AND category = ");
-            count_builder.push_bind(format!("{}", category));
-        }
-        if let Some(status) = &query.status {
-            data_builder.push(" AND status = ");
-            data_builder.push_bind(format!("{}", status));
-            count_builder.push(" AND status = ");
-            count_builder.push_bind(format!("{}", status));
-        }
-        if let Some(rating) = query.rating {
-            data_builder.push(" AND rating = ");
-            data_builder.push_bind(format!("{}", rating));
-            count_builder.push(" AND rating = ");
-            count_builder.push_bind(format!("{}", rating));
-        }
-        if let Some(min_rating) = query.min_rating {
-            data_builder.push(" AND rating >= ");
-            data_builder.push_bind(format!("{}", min_rating));
-            count_builder.push(" AND rating >= ");
-            count_builder.push_bind(format!("{}", min_rating));
-        }
-        if let Some(max_rating) = query.max_rating {
-            data_builder.push(" AND rating <= ");
-            data_builder.push_bind(format!("{}", max_rating));
-            count_builder.push(" AND rating <= ");
-            count_builder.push_bind(format!("{}", max_rating));
-        }
-        if let Some(min_price) = query.min_price {
-            data_builder.push(" AND price >= ");
-            data_builder.push_bind(format!("{}", min_price));
-            count_builder.push(" AND price >= ");
-            count_builder.push_bind(format!("{}", min_price));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sqlx::connect_sqlite_file;
+    use crate::dao::PopulaDao;
+    use crate::dto::Popula;
+    use crate::util::uuid_util;
+    use chrono::NaiveDate;
+
+    fn book(title: &str, author: &str, price: f64) -> Book {
+        Book {
+            id: None,
+            price,
+            sales: 100,
+            publish_date: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+            title: title.to_string(),
+            author: author.to_string(),
+            category: "小说".to_string(),
+            rating: 5,
+            img: String::new(),
+            status: "on".to_string(),
         }
-        if let Some(max_price) = query.max_price {
-            data_builder.push(" AND price <= ");
-            data_builder.push_bind(format!("{}", max_price));
-            count_builder.push(" AND price <= ");
-            count_builder.push_bind(format!("{}", max_price));
-        }
-        if let Some(min_sales) = query.min_sales {
-            data_builder.push(" AND sales >= ");
-            data_builder.push_bind(format!("{}", min_sales));
-            count_builder.push(" AND sales >= ");
-            count_builder.push_bind(format!("{}", min_sales));
-        }
-        if let Some(max_sales) = query.max_sales {
-            data_builder.push(" AND sales <= ");
-            data_builder.push_bind(format!("{}", max_sales));
-            count_builder.push(" AND sales <= ");
-            count_builder.push_bind(format!("{}", max_sales));
-        }
-        if let Some(min_publish_date) = query.min_publish_date {
-            data_builder.push(" AND publish_date >= ");
-            data_builder.push_bind(format!("{}", min_publish_date));
-            count_builder.push(" AND publish_date >= ");
-            count_builder.push_bind(format!("{}", min_publish_date));
-        }
-        if let Some(max_publish_date) = query.max_publish_date {
-            data_builder.push(" AND publish_date <= ");
-            data_builder.push_bind(format!("{}", max_publish_date));
-            count_builder.push(" AND publish_date <= ");
-            count_builder.push_bind(format!("{}", max_publish_date));
-        }
-        data_builder.push(" ORDER BY publish_date DESC LIMIT  ");
-        data_builder.push_bind(page_size);
-        data_builder.push(" OFFSET ");
-        data_builder.push_bind(offset);
-
-        println!("SQL-data: {}", data_builder.sql());
-        println!("SQL-count: {}", count_builder.sql());
-        println!("Page size: {}, Offset: {}", page_size, offset);
-
-        let books = data_builder
-            .build_query_as()
-            .fetch_all(&mut *pool.acquire().await?)
-            .await
-            .context("Failed to search books")?;
-        let total: i32 = count_builder
-            .build_query_scalar()
-            .fetch_one(&mut *pool.acquire().await?)
-            .await
-            .context("Failed to get search count")?;
-
-        Ok(Page {
-            data: books,
-            page_count: ((total as f32) / (page_size as f32)).ceil() as i32,
-            current_page: page,
-            total,
-            page_size,
-        })
     }
 
-    // 创建查询条件
-    // let query = BookQuery {
-    //     title: Some("Rust".to_string()),
-    //     min_rating: Some(4),
-    //     category: Some("编程".to_string()),
-    //     ..Default::default()
-    // };
-
-    // // 执行搜索
-    // let results = search(&query, 1, 10).await?;
-
-    /// 获取数据库连接池
-    async fn get_pool() -> Result<MySqlPool> {
-        let guard = POOL.lock().await;
-        let pool = guard
-            .as_ref()
-            .context("Database connection not initialized")?
-            .clone();
-        Ok(pool)
+    // 在临时 SQLite 文件上执行迁移，再通过 DAO 读写
+    #[tokio::test]
+    async fn sqlite_round_trip() {
+        let path = std::env::temp_dir().join(format!("draft-test-{}.db", uuid_util::new_id()));
+        connect_sqlite_file(&path).await.unwrap();
+
+        let id = BookDao::create(&book("三体", "刘慈欣", 23.0)).await.unwrap() as i64;
+        BookDao::create(&book("Rust Programming", "Steve", 59.0)).await.unwrap();
+        let mut stored = BookDao::get_by_id(id).await.unwrap().unwrap();
+        assert_eq!((stored.id, stored.title.as_str()), (Some(id as i32), "三体"));
+
+        // 先建立全文索引，修改后索引中应是数据库中保存的记录
+        assert_eq!(BookDao::full_text_search("santi", 1, 10).await.unwrap().total, 1);
+        stored.price = 30.0;
+        assert!(BookDao::update(&stored).await.unwrap());
+        let hits = BookDao::full_text_search("santi", 1, 10).await.unwrap();
+        assert_eq!(hits.data[0].doc.price, 30.0);
+
+        let query = BookQuery {
+            min_price: Some(25.0),
+            ..Default::default()
+        };
+        let page = BookDao::dynamics_search(&query, 1, 10).await.unwrap();
+        assert_eq!(page.total, 2);
+        let page = BookDao::search("刘", &[], 1, 10).await.unwrap();
+        assert_eq!(page.data.len(), 1);
+        // 页码很大时偏移量不溢出
+        assert!(BookDao::list(i32::MAX, i32::MAX).await.unwrap().data.is_empty());
+
+        assert!(BookDao::delete(id).await.unwrap());
+        assert!(BookDao::get_by_id(id).await.unwrap().is_none());
+        assert_eq!(BookDao::full_text_search("santi", 1, 10).await.unwrap().total, 0);
+
+        let popula: Popula = serde_json::from_value(serde_json::json!({ "year": 2020, "population": 1411.78 })).unwrap();
+        let popula_id = PopulaDao::create(&popula).await.unwrap() as i64;
+        let stored = PopulaDao::get_by_id(popula_id).await.unwrap().unwrap();
+        assert_eq!((stored.year, stored.population), (Some(2020), Some(1411.78)));
+
+        get_data_pool().await.unwrap().close().await;
+        let _ = std::fs::remove_file(&path);
     }
 }
@@ -0,0 +1,116 @@
+use crate::{
+    commands::sqlx::data_pool_generation,
+    dao::{BookDao, Change, Repository},
+    dto::{Book, BookHit, Page},
+    util::{SearchIndex, Searchable},
+};
+use anyhow::Result;
+use log::info;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+// 构建索引期间书籍有变化时重新加载，最多尝试的次数
+const MAX_BUILD_ATTEMPTS: usize = 3;
+
+impl Searchable for Book {
+    const FIELDS: &'static [(&'static str, f32)] = &[("title", 3.0), ("author", 2.0), ("category", 1.0)];
+
+    fn texts(&self) -> Vec<String> {
+        vec![self.title.clone(), self.author.clone(), self.category.clone()]
+    }
+}
+
+// 索引及其数据来自的业务数据连接池代数
+struct BookIndex {
+    generation: u64,
+    index: SearchIndex<Book>,
+}
+
+// 首次检索时从数据库加载，之后随 BookDao 的增删改同步，业务数据库切换后重新加载
+static BOOK_INDEX: Lazy<RwLock<Option<BookIndex>>> = Lazy::new(|| RwLock::new(None));
+// 书籍每次变化加一，用来判断加载期间是否有修改
+static BOOK_VERSION: AtomicU64 = AtomicU64::new(0);
+
+// 索引是否存在且来自当前业务数据库
+fn index_current(index: &Option<BookIndex>) -> bool {
+    index.as_ref().is_some_and(|i| i.generation == data_pool_generation())
+}
+
+/// BookDao 修改书籍后同步索引
+pub fn book_changed(change: Change<'_, Book>) {
+    BOOK_VERSION.fetch_add(1, Ordering::SeqCst);
+    let mut guard = BOOK_INDEX.write().unwrap();
+    if !index_current(&guard) {
+        *guard = None;
+        return;
+    }
+    let Some(BookIndex { index, .. }) = guard.as_mut() else {
+        return;
+    };
+    match change {
+        Change::Saved(id, book) => index.upsert(id, book.clone()),
+        Change::Deleted(ids) => {
+            for id in ids {
+                index.remove(*id);
+            }
+        }
+    }
+}
+
+// 索引不存在或业务数据库已切换时从数据库加载全部书籍
+async fn ensure_index() -> Result<()> {
+    for attempt in 1..=MAX_BUILD_ATTEMPTS {
+        if index_current(&BOOK_INDEX.read().unwrap()) {
+            return Ok(());
+        }
+        let generation = data_pool_generation();
+        let version = BOOK_VERSION.load(Ordering::SeqCst);
+        let books = BookDao::all().await?;
+        let mut index = SearchIndex::new();
+        for book in books {
+            if let Some(id) = book.id {
+                index.upsert(i64::from(id), book);
+            }
+        }
+
+        let mut guard = BOOK_INDEX.write().unwrap();
+        if index_current(&guard) {
+            return Ok(());
+        }
+        let unchanged = BOOK_VERSION.load(Ordering::SeqCst) == version && data_pool_generation() == generation;
+        if unchanged || attempt == MAX_BUILD_ATTEMPTS {
+            info!("书籍检索索引已建立，共 {} 本", index.len());
+            *guard = Some(BookIndex { generation, index });
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// 丢弃索引并从数据库重新加载，用于绕过 BookDao 导入或修改数据之后
+pub async fn rebuild_book_index() -> Result<usize> {
+    *BOOK_INDEX.write().unwrap() = None;
+    ensure_index().await?;
+    Ok(BOOK_INDEX.read().unwrap().as_ref().map_or(0, |i| i.index.len()))
+}
+
+impl Repository<Book> {
+    /// 按书名、作者、分类全文检索，支持拼音、首字母和拼写容错，按相关度排序
+    pub async fn full_text_search(query: &str, page: i32, page_size: i32) -> Result<Page<BookHit>> {
+        ensure_index().await?;
+        let (page, page_size) = (page.max(1), page_size.max(1));
+        let offset = (page as usize - 1).saturating_mul(page_size as usize);
+        let (total, data) = match BOOK_INDEX.read().unwrap().as_ref() {
+            Some(book_index) => book_index.index.search(query, offset, page_size as usize),
+            None => (0, Vec::new()),
+        };
+        Ok(Page {
+            data,
+            page_count: ((total as f32) / (page_size as f32)).ceil() as i32,
+            current_page: page,
+            total: total as i32,
+            page_size,
+        })
+    }
+}
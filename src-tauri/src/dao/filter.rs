@@ -0,0 +1,287 @@
+use crate::{commands::sqlx::cell::Cell, dao::ToCell, dto::Sort};
+use anyhow::Result;
+
+/// 单个过滤条件，字段名在生成 SQL 时按白名单校验
+#[derive(Debug, Clone)]
+pub enum Filter {
+    // column = ?
+    Eq(String, Cell),
+    // column LIKE ?，值两端自动加 %，值中的 % 和 _ 按普通字符匹配
+    Like(String, String),
+    // min <= column <= max，两端可以只给一端
+    Range {
+        column: String,
+        min: Option<Cell>,
+        max: Option<Cell>,
+    },
+    // column IN (...)，列表为空时没有记录匹配
+    In(String, Vec<Cell>),
+    // column IS NULL / IS NOT NULL
+    Null(String, bool),
+    // 任一条件成立
+    Any(Vec<Filter>),
+}
+
+/// 由过滤条件生成的 SQL 片段
+#[derive(Debug, Default)]
+pub struct BuiltFilter {
+    // 为空或以 " WHERE " 开头
+    pub where_sql: String,
+    // 为空或以 " ORDER BY " 开头
+    pub order_sql: String,
+    // where_sql 中占位符对应的参数
+    pub cells: Vec<Cell>,
+}
+
+/// 查询条件构建器，同一份条件同时用于数据查询和计数查询
+/// ```ignore
+/// let spec = QuerySpec::new()
+///     .like("title", query.title.as_deref())
+///     .range("price", query.min_price, query.max_price)
+///     .order_by("publish_date", true);
+/// let page = BookDao::query(&spec, 1, 10).await?;
+/// ```
+/// eq、like、range 的值为 None 时忽略该条件，方便直接传入可选的查询参数
+#[derive(Debug, Clone, Default)]
+pub struct QuerySpec {
+    filters: Vec<Filter>,
+    sorts: Vec<Sort>,
+}
+
+// LIKE 的转义字符，MySQL 和 SQLite 都能直接使用
+pub(crate) const LIKE_ESCAPE: char = '!';
+
+pub(crate) fn like_pattern(value: &str) -> String {
+    let mut pattern = String::with_capacity(value.len() + 2);
+    pattern.push('%');
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | LIKE_ESCAPE) {
+            pattern.push(LIKE_ESCAPE);
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn check_column(columns: &[&str], column: &str) -> Result<()> {
+    if !columns.contains(&column) {
+        anyhow::bail!("不支持按字段 {} 查询", column);
+    }
+    Ok(())
+}
+
+impl Filter {
+    // 生成条件 SQL，参数追加到 cells
+    fn render(&self, columns: &[&str], cells: &mut Vec<Cell>) -> Result<String> {
+        Ok(match self {
+            Filter::Eq(column, value) => {
+                check_column(columns, column)?;
+                cells.push(value.clone());
+                format!("{} = ?", column)
+            }
+            Filter::Like(column, value) => {
+                check_column(columns, column)?;
+                cells.push(Cell::Text(Some(like_pattern(value))));
+                format!("{} LIKE ? ESCAPE '{}'", column, LIKE_ESCAPE)
+            }
+            Filter::Range { column, min, max } => {
+                check_column(columns, column)?;
+                let mut parts = Vec::new();
+                if let Some(min) = min {
+                    cells.push(min.clone());
+                    parts.push(format!("{} >= ?", column));
+                }
+                if let Some(max) = max {
+                    cells.push(max.clone());
+                    parts.push(format!("{} <= ?", column));
+                }
+                if parts.is_empty() {
+                    "1 = 1".to_string()
+                } else {
+                    parts.join(" AND ")
+                }
+            }
+            Filter::In(column, values) => {
+                check_column(columns, column)?;
+                if values.is_empty() {
+                    "1 = 0".to_string()
+                } else {
+                    cells.extend(values.iter().cloned());
+                    format!("{} IN ({})", column, vec!["?"; values.len()].join(", "))
+                }
+            }
+            Filter::Null(column, null) => {
+                check_column(columns, column)?;
+                format!("{} IS {}NULL", column, if *null { "" } else { "NOT " })
+            }
+            Filter::Any(filters) => {
+                if filters.is_empty() {
+                    "1 = 0".to_string()
+                } else {
+                    let parts = filters
+                        .iter()
+                        .map(|f| f.render(columns, cells))
+                        .collect::<Result<Vec<_>>>()?;
+                    format!("({})", parts.join(" OR "))
+                }
+            }
+        })
+    }
+}
+
+impl QuerySpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加任意条件
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// 等值条件
+    pub fn eq<V: ToCell>(self, column: &str, value: Option<V>) -> Self {
+        match value {
+            Some(v) => self.filter(Filter::Eq(column.to_string(), v.to_cell())),
+            None => self,
+        }
+    }
+
+    /// 模糊匹配，空字符串忽略
+    pub fn like<S: AsRef<str>>(self, column: &str, value: Option<S>) -> Self {
+        match value {
+            Some(v) if !v.as_ref().is_empty() => {
+                self.filter(Filter::Like(column.to_string(), v.as_ref().to_string()))
+            }
+            _ => self,
+        }
+    }
+
+    /// 关键字匹配多个字段中的任意一个，空字符串忽略
+    pub fn keyword(self, columns: &[&str], keyword: &str) -> Self {
+        if keyword.is_empty() || columns.is_empty() {
+            return self;
+        }
+        let any = columns
+            .iter()
+            .map(|c| Filter::Like(c.to_string(), keyword.to_string()))
+            .collect();
+        self.filter(Filter::Any(any))
+    }
+
+    /// 范围条件，两端都为 None 时忽略
+    pub fn range<V: ToCell>(self, column: &str, min: Option<V>, max: Option<V>) -> Self {
+        if min.is_none() && max.is_none() {
+            return self;
+        }
+        self.filter(Filter::Range {
+            column: column.to_string(),
+            min: min.map(|v| v.to_cell()),
+            max: max.map(|v| v.to_cell()),
+        })
+    }
+
+    /// 取值在列表中
+    pub fn in_list<V: ToCell>(self, column: &str, values: impl IntoIterator<Item = V>) -> Self {
+        let values = values.into_iter().map(|v| v.to_cell()).collect();
+        self.filter(Filter::In(column.to_string(), values))
+    }
+
+    /// 为空或不为空
+    pub fn is_null(self, column: &str, null: bool) -> Self {
+        self.filter(Filter::Null(column.to_string(), null))
+    }
+
+    /// 追加排序字段，按添加顺序排序
+    pub fn order_by(mut self, column: &str, desc: bool) -> Self {
+        self.sorts.push(Sort {
+            column: column.to_string(),
+            desc,
+        });
+        self
+    }
+
+    /// 追加多个排序字段
+    pub fn sorts(mut self, sorts: impl IntoIterator<Item = Sort>) -> Self {
+        self.sorts.extend(sorts);
+        self
+    }
+
+    /// 生成 WHERE 和 ORDER BY 子句，columns 为允许查询和排序的字段
+    pub fn build(&self, columns: &[&str]) -> Result<BuiltFilter> {
+        let mut built = BuiltFilter::default();
+        let conditions = self
+            .filters
+            .iter()
+            .map(|f| f.render(columns, &mut built.cells))
+            .collect::<Result<Vec<_>>>()?;
+        if !conditions.is_empty() {
+            built.where_sql = format!(" WHERE {}", conditions.join(" AND "));
+        }
+        if !self.sorts.is_empty() {
+            let orders = self
+                .sorts
+                .iter()
+                .map(|s| {
+                    check_column(columns, &s.column)?;
+                    Ok(format!("{} {}", s.column, if s.desc { "DESC" } else { "ASC" }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            built.order_sql = format!(" ORDER BY {}", orders.join(", "));
+        }
+        Ok(built)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLUMNS: &[&str] = &["title", "author", "price", "status"];
+
+    #[test]
+    fn like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("rust"), "%rust%");
+        assert_eq!(like_pattern("100%_!"), "%100!%!_!!%");
+    }
+
+    #[test]
+    fn builds_where_and_order() {
+        let built = QuerySpec::new()
+            .like("title", Some("rust"))
+            .like("author", Some(""))
+            .eq("status", None::<String>)
+            .range("price", Some(10.0), None)
+            .in_list("status", ["on".to_string(), "off".to_string()])
+            .order_by("price", true)
+            .build(COLUMNS)
+            .unwrap();
+        assert_eq!(
+            built.where_sql,
+            " WHERE title LIKE ? ESCAPE '!' AND price >= ? AND status IN (?, ?)"
+        );
+        assert_eq!(built.order_sql, " ORDER BY price DESC");
+        assert_eq!(built.cells.len(), 4);
+        assert!(matches!(&built.cells[0], Cell::Text(Some(p)) if p == "%rust%"));
+    }
+
+    #[test]
+    fn keyword_matches_any_column() {
+        let built = QuerySpec::new().keyword(&["title", "author"], "liu").build(COLUMNS).unwrap();
+        assert_eq!(
+            built.where_sql,
+            " WHERE (title LIKE ? ESCAPE '!' OR author LIKE ? ESCAPE '!')"
+        );
+        let empty = QuerySpec::new().keyword(&["title"], "").in_list("price", Vec::<f64>::new());
+        assert_eq!(empty.build(COLUMNS).unwrap().where_sql, " WHERE 1 = 0");
+    }
+
+    #[test]
+    fn rejects_unknown_columns() {
+        assert!(QuerySpec::new().eq("password", Some(1)).build(COLUMNS).is_err());
+        assert!(QuerySpec::new().order_by("1; DROP TABLE book", false).build(COLUMNS).is_err());
+        assert!(QuerySpec::new().build(COLUMNS).unwrap().where_sql.is_empty());
+    }
+}
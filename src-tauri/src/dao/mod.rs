@@ -1,4 +1,12 @@
+pub mod filter;
+pub use filter::*;
+pub mod repository;
+pub use repository::*;
 pub mod book_dao;
 pub use book_dao::*;
+pub mod book_index;
+pub use book_index::*;
 pub mod popula_dao;
 pub use popula_dao::*;
+pub mod query_dao;
+pub use query_dao::*;
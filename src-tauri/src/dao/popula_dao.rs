@@ -1,35 +1,19 @@
-use crate::commands::sqlx::POOL;
-use crate::dto::Popula;
-use anyhow::{Context, Result};
-use sqlx::MySqlPool;
+use crate::{dao::Repository, dto::Popula, entity};
 
-pub struct PopulaDao;
-
-impl PopulaDao {
-    /// 分页获取书籍列表
-    pub async fn list() -> Result<Vec<Popula>> {
-        let pool = Self::get_pool().await?;
-        // 获取数据
-        let populas = sqlx::query_as::<_, Popula>(
-            r#"
-            SELECT * 
-            FROM yearbook_popula
-            "#,
-        )
-        .fetch_all(&pool)
-        .await
-        .context("Failed to list populas")?;
-
-        Ok(populas)
-    }
-
-    /// 获取数据库连接池
-    async fn get_pool() -> Result<MySqlPool> {
-        let guard = POOL.lock().await;
-        let pool = guard
-            .as_ref()
-            .context("Database connection not initialized")?
-            .clone();
-        Ok(pool)
+entity! {
+    Popula {
+        table: "yearbook_popula",
+        id: id,
+        columns: [
+            year, population, growth_popula, annual_average, over15, newborn, death,
+            total_dependency_ratio, child_dependency_ratio, old_dependency_ratio,
+            birth_rate, mortality, growth_rate, first_marriage, unmarried, remarry,
+            divorce, y0_y14, y15_y64, over65,
+        ],
+        search: [],
+        order_by: "year",
     }
 }
+
+/// yearbook_popula 表的数据访问对象
+pub type PopulaDao = Repository<Popula>;
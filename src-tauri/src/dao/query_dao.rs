@@ -0,0 +1,258 @@
+use crate::{
+    commands::sqlx::{get_data_pool, DbPool},
+    dao::{like_pattern, LIKE_ESCAPE},
+    dto::{HistoryQuery, Page, QueryHistory, SavedQuery},
+    with_pool,
+};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use sqlx::QueryBuilder;
+
+/// query_history 表的数据访问对象
+pub struct QueryHistoryDao;
+
+impl QueryHistoryDao {
+    /// 记录一次查询
+    pub async fn record(history: &QueryHistory) -> Result<()> {
+        let pool = get_data_pool().await?;
+        with_pool!(pool, pool => sqlx::query(
+            r#"
+            INSERT INTO query_history (
+                profile, db_name, sql_text, duration_ms, row_count, error, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&history.profile)
+        .bind(&history.db_name)
+        .bind(&history.sql_text)
+        .bind(history.duration_ms)
+        .bind(history.row_count)
+        .bind(&history.error)
+        .bind(history.created_at)
+        .execute(&pool)
+        .await
+        .context("Failed to record query history")?
+        .rows_affected());
+        Ok(())
+    }
+
+    /// 按条件分页搜索查询历史，最近的记录在前
+    pub async fn search(query: &HistoryQuery, page: i32, page_size: i32) -> Result<Page<QueryHistory>> {
+        let pool = get_data_pool().await?;
+        let offset = (i64::from(page) - 1) * i64::from(page_size);
+
+        let (data, total): (Vec<QueryHistory>, i64) = with_pool!(pool, pool => {
+            let mut data_builder = QueryBuilder::new("SELECT * FROM query_history WHERE 1=1 ");
+            let mut count_builder = QueryBuilder::new("SELECT COUNT(*) FROM query_history WHERE 1=1 ");
+            for builder in [&mut data_builder, &mut count_builder] {
+                if let Some(keyword) = query.keyword.as_ref().filter(|k| !k.is_empty()) {
+                    builder.push(" AND sql_text LIKE ");
+                    builder.push_bind(like_pattern(keyword));
+                    builder.push(format!(" ESCAPE '{}'", LIKE_ESCAPE));
+                }
+                if let Some(profile) = &query.profile {
+                    builder.push(" AND profile = ");
+                    builder.push_bind(profile.clone());
+                }
+                if let Some(db_name) = &query.db_name {
+                    builder.push(" AND db_name = ");
+                    builder.push_bind(db_name.clone());
+                }
+                if query.errors_only {
+                    builder.push(" AND error IS NOT NULL");
+                }
+                if let Some(min) = query.min_created_at {
+                    builder.push(" AND created_at >= ");
+                    builder.push_bind(min);
+                }
+                if let Some(max) = query.max_created_at {
+                    builder.push(" AND created_at <= ");
+                    builder.push_bind(max);
+                }
+            }
+            data_builder.push(" ORDER BY id DESC LIMIT ");
+            data_builder.push_bind(page_size);
+            data_builder.push(" OFFSET ");
+            data_builder.push_bind(offset);
+
+            let data = data_builder
+                .build_query_as::<QueryHistory>()
+                .fetch_all(&pool)
+                .await
+                .context("Failed to search query history")?;
+            let total: i64 = count_builder
+                .build_query_scalar()
+                .fetch_one(&pool)
+                .await
+                .context("Failed to get query history count")?;
+            (data, total)
+        });
+
+        Ok(Page {
+            data,
+            page_count: ((total as f32) / (page_size as f32)).ceil() as i32,
+            current_page: page,
+            total: total as i32,
+            page_size,
+        })
+    }
+
+    /// 删除指定的历史记录
+    pub async fn deletes(ids: &[i64]) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let pool = get_data_pool().await?;
+        let placeholders = (0..ids.len()).map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!("DELETE FROM query_history WHERE id IN ({})", placeholders);
+
+        let rows_affected = with_pool!(pool, pool => {
+            let mut query = sqlx::query(&sql);
+            for id in ids {
+                query = query.bind(id);
+            }
+            query
+                .execute(&pool)
+                .await
+                .context("Failed to delete query history")?
+                .rows_affected()
+        });
+        Ok(rows_affected)
+    }
+
+    /// 清除 before 之前的历史记录，before 为空时全部清除
+    pub async fn clear(before: Option<NaiveDateTime>) -> Result<u64> {
+        let pool = get_data_pool().await?;
+        let rows_affected = with_pool!(pool, pool => match before {
+            Some(before) => sqlx::query("DELETE FROM query_history WHERE created_at < ?")
+                .bind(before)
+                .execute(&pool)
+                .await,
+            None => sqlx::query("DELETE FROM query_history").execute(&pool).await,
+        }
+        .context("Failed to clear query history")?
+        .rows_affected());
+        Ok(rows_affected)
+    }
+}
+
+/// saved_query 表的数据访问对象
+pub struct SavedQueryDao;
+
+impl SavedQueryDao {
+    /// 保存查询，id 为空时新建，否则修改；返回查询 id
+    pub async fn save(saved: &SavedQuery) -> Result<i64> {
+        let pool = get_data_pool().await?;
+        if let Some(id) = saved.id {
+            let rows_affected = with_pool!(pool, pool => sqlx::query(
+                r#"
+                UPDATE saved_query SET
+                    name = ?,
+                    description = ?,
+                    profile = ?,
+                    sql_text = ?,
+                    params = ?,
+                    updated_at = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&saved.name)
+            .bind(&saved.description)
+            .bind(&saved.profile)
+            .bind(&saved.sql_text)
+            .bind(&saved.params)
+            .bind(saved.updated_at)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .context("Failed to update saved query")?
+            .rows_affected());
+            if rows_affected == 0 {
+                anyhow::bail!("保存的查询 {} 不存在", id);
+            }
+            return Ok(id);
+        }
+
+        let sql = r#"
+            INSERT INTO saved_query (
+                name, description, profile, sql_text, params, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#;
+        // 两种后端获取自增 id 的方式不同，这里分开写
+        let id = match pool {
+            DbPool::MySql(pool) => sqlx::query(sql)
+                .bind(&saved.name)
+                .bind(&saved.description)
+                .bind(&saved.profile)
+                .bind(&saved.sql_text)
+                .bind(&saved.params)
+                .bind(saved.created_at)
+                .bind(saved.updated_at)
+                .execute(&pool)
+                .await
+                .context("Failed to create saved query")?
+                .last_insert_id() as i64,
+            DbPool::Sqlite(pool) => sqlx::query(sql)
+                .bind(&saved.name)
+                .bind(&saved.description)
+                .bind(&saved.profile)
+                .bind(&saved.sql_text)
+                .bind(&saved.params)
+                .bind(saved.created_at)
+                .bind(saved.updated_at)
+                .execute(&pool)
+                .await
+                .context("Failed to create saved query")?
+                .last_insert_rowid(),
+            DbPool::Postgres(_) => anyhow::bail!("业务数据暂不支持 PostgreSQL"),
+        };
+        Ok(id)
+    }
+
+    /// 列出保存的查询，keyword 匹配名称、说明和 SQL 文本
+    pub async fn list(keyword: Option<&str>) -> Result<Vec<SavedQuery>> {
+        let pool = get_data_pool().await?;
+        let pattern = like_pattern(keyword.unwrap_or_default());
+        let sql = format!(
+            r#"
+            SELECT * FROM saved_query
+            WHERE name LIKE ? ESCAPE '{0}' OR description LIKE ? ESCAPE '{0}' OR sql_text LIKE ? ESCAPE '{0}'
+            ORDER BY name
+            "#,
+            LIKE_ESCAPE
+        );
+        let list = with_pool!(pool, pool => sqlx::query_as::<_, SavedQuery>(&sql)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&pattern)
+        .fetch_all(&pool)
+        .await
+        .context("Failed to list saved queries")?);
+        Ok(list)
+    }
+
+    /// 按名称获取保存的查询
+    pub async fn get_by_name(name: &str) -> Result<Option<SavedQuery>> {
+        let pool = get_data_pool().await?;
+        let saved = with_pool!(pool, pool => sqlx::query_as::<_, SavedQuery>(
+            "SELECT * FROM saved_query WHERE name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&pool)
+        .await
+        .context("Failed to get saved query")?);
+        Ok(saved)
+    }
+
+    /// 根据 ID 删除保存的查询
+    pub async fn delete(id: i64) -> Result<bool> {
+        let pool = get_data_pool().await?;
+        let rows_affected = with_pool!(pool, pool => sqlx::query("DELETE FROM saved_query WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .context("Failed to delete saved query")?
+            .rows_affected());
+        Ok(rows_affected > 0)
+    }
+}
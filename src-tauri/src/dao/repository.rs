@@ -0,0 +1,419 @@
+use crate::{
+    commands::sqlx::{
+        cell::{self, Cell},
+        get_data_pool, DbPool,
+    },
+    dao::{BuiltFilter, QuerySpec},
+    dto::{FacetCount, Page, Sort},
+};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::mysql::MySqlRow;
+use sqlx::sqlite::SqliteRow;
+use sqlx::FromRow;
+use std::marker::PhantomData;
+
+/// 字段值转换为可绑定的单元格
+pub trait ToCell {
+    fn to_cell(&self) -> Cell;
+    // 该类型的 NULL 值
+    fn null_cell() -> Cell
+    where
+        Self: Sized;
+}
+
+macro_rules! impl_to_cell {
+    ($($ty:ty => $variant:ident($conv:expr)),* $(,)?) => {
+        $(
+            impl ToCell for $ty {
+                fn to_cell(&self) -> Cell {
+                    Cell::$variant(Some(($conv)(self)))
+                }
+                fn null_cell() -> Cell {
+                    Cell::$variant(None)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_cell! {
+    i32 => Int(|v: &i32| i64::from(*v)),
+    i64 => Int(|v: &i64| *v),
+    u32 => Int(|v: &u32| i64::from(*v)),
+    f32 => Float(|v: &f32| f64::from(*v)),
+    f64 => Float(|v: &f64| *v),
+    bool => Bool(|v: &bool| *v),
+    String => Text(|v: &String| v.clone()),
+    NaiveDate => Date(|v: &NaiveDate| *v),
+    NaiveDateTime => DateTime(|v: &NaiveDateTime| *v),
+}
+
+impl<T: ToCell> ToCell for Option<T> {
+    fn to_cell(&self) -> Cell {
+        match self {
+            Some(v) => v.to_cell(),
+            None => T::null_cell(),
+        }
+    }
+
+    fn null_cell() -> Cell {
+        T::null_cell()
+    }
+}
+
+/// MySQL 和 SQLite 的查询结果都能转换的类型
+pub trait DataRow: for<'r> FromRow<'r, MySqlRow> + for<'r> FromRow<'r, SqliteRow> + Send + Unpin {}
+
+impl<T> DataRow for T where T: for<'r> FromRow<'r, MySqlRow> + for<'r> FromRow<'r, SqliteRow> + Send + Unpin {}
+
+/// Repository 修改成功后通知给实体的变化
+pub enum Change<'a, T> {
+    // 新增或修改后从数据库重新读取的记录及其主键
+    Saved(i64, &'a T),
+    Deleted(&'a [i64]),
+}
+
+/// 可由 Repository 读写的表，一般用 entity! 宏声明
+pub trait Entity: DataRow + 'static {
+    const TABLE: &'static str;
+    // 自增主键字段
+    const ID: &'static str;
+    // 除主键外的字段，顺序与 values 一致
+    const COLUMNS: &'static [&'static str];
+    // 关键字搜索匹配的字段
+    const SEARCH_COLUMNS: &'static [&'static str];
+    // 列表和搜索的默认排序
+    const ORDER_BY: &'static str;
+
+    fn id(&self) -> Option<i64>;
+    fn values(&self) -> Vec<Cell>;
+
+    // 为 true 时修改记录后调用 on_change，新增和修改会多读取一次保存后的记录
+    const NOTIFY_CHANGES: bool = false;
+
+    // 通过 Repository 修改记录后调用，用于同步缓存和索引
+    fn on_change(_change: Change<'_, Self>) {}
+}
+
+/// 声明结构体对应的表，实现 Entity
+/// ```ignore
+/// entity! {
+///     Book {
+///         table: "book",
+///         id: id,
+///         columns: [price, sales, title],
+///         search: [title],
+///         order_by: "publish_date DESC",
+///         // 可选，记录修改后调用
+///         on_change: crate::dao::book_changed,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! entity {
+    ($ty:ty {
+        table: $table:literal,
+        id: $id:ident,
+        columns: [$($col:ident),* $(,)?],
+        search: [$($search:ident),* $(,)?],
+        order_by: $order:literal
+        $(, on_change: $hook:path)? $(,)?
+    }) => {
+        impl $crate::dao::Entity for $ty {
+            const TABLE: &'static str = $table;
+            const ID: &'static str = stringify!($id);
+            const COLUMNS: &'static [&'static str] = &[$(stringify!($col)),*];
+            const SEARCH_COLUMNS: &'static [&'static str] = &[$(stringify!($search)),*];
+            const ORDER_BY: &'static str = $order;
+
+            fn id(&self) -> Option<i64> {
+                self.$id.map(|v| v as i64)
+            }
+
+            fn values(&self) -> Vec<$crate::commands::sqlx::cell::Cell> {
+                vec![$($crate::dao::ToCell::to_cell(&self.$col)),*]
+            }
+
+            $(
+                const NOTIFY_CHANGES: bool = true;
+
+                fn on_change(change: $crate::dao::Change<'_, Self>) {
+                    $hook(change)
+                }
+            )?
+        }
+    };
+}
+
+// 执行修改语句的结果
+struct Done {
+    rows_affected: u64,
+    last_insert_id: u64,
+}
+
+/// 绑定参数执行查询，按后端转换结果行
+pub async fn fetch_rows<T: DataRow>(pool: &DbPool, sql: &str, cells: &[Cell]) -> Result<Vec<T>> {
+    macro_rules! fetch {
+        ($pool:expr, $bind:path) => {{
+            let mut query = sqlx::query(sql);
+            for cell in cells {
+                query = $bind(query, cell);
+            }
+            let rows = query.fetch_all($pool).await?;
+            rows.iter().map(T::from_row).collect::<Result<Vec<T>, sqlx::Error>>()?
+        }};
+    }
+    Ok(match pool {
+        DbPool::MySql(pool) => fetch!(pool, cell::bind_mysql),
+        DbPool::Sqlite(pool) => fetch!(pool, cell::bind_sqlite),
+        DbPool::Postgres(_) => anyhow::bail!("业务数据暂不支持 PostgreSQL"),
+    })
+}
+
+async fn fetch_count(pool: &DbPool, sql: &str, cells: &[Cell]) -> Result<i64> {
+    macro_rules! count {
+        ($pool:expr, $bind:path) => {{
+            let mut query = sqlx::query(sql);
+            for cell in cells {
+                query = $bind(query, cell);
+            }
+            let row = query.fetch_one($pool).await?;
+            sqlx::Row::try_get::<i64, _>(&row, 0)?
+        }};
+    }
+    Ok(match pool {
+        DbPool::MySql(pool) => count!(pool, cell::bind_mysql),
+        DbPool::Sqlite(pool) => count!(pool, cell::bind_sqlite),
+        DbPool::Postgres(_) => anyhow::bail!("业务数据暂不支持 PostgreSQL"),
+    })
+}
+
+async fn execute(pool: &DbPool, sql: &str, cells: &[Cell]) -> Result<Done> {
+    macro_rules! execute {
+        ($pool:expr, $bind:path) => {{
+            let mut query = sqlx::query(sql);
+            for cell in cells {
+                query = $bind(query, cell);
+            }
+            query.execute($pool).await?
+        }};
+    }
+    // 两种后端获取自增 id 的方式不同
+    Ok(match pool {
+        DbPool::MySql(pool) => {
+            let res = execute!(pool, cell::bind_mysql);
+            Done {
+                rows_affected: res.rows_affected(),
+                last_insert_id: res.last_insert_id(),
+            }
+        }
+        DbPool::Sqlite(pool) => {
+            let res = execute!(pool, cell::bind_sqlite);
+            Done {
+                rows_affected: res.rows_affected(),
+                last_insert_id: res.last_insert_rowid() as u64,
+            }
+        }
+        DbPool::Postgres(_) => anyhow::bail!("业务数据暂不支持 PostgreSQL"),
+    })
+}
+
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+/// 通用的增删改查，T 为 entity! 声明的表
+pub struct Repository<T>(PhantomData<T>);
+
+impl<T: Entity> Repository<T> {
+    /// 新增一行，返回自增 id
+    pub async fn create(entity: &T) -> Result<u64> {
+        let pool = get_data_pool().await?;
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            T::TABLE,
+            T::COLUMNS.join(", "),
+            placeholders(T::COLUMNS.len())
+        );
+        let done = execute(&pool, &sql, &entity.values())
+            .await
+            .with_context(|| format!("Failed to create {}", T::TABLE))?;
+        Self::notify_saved(done.last_insert_id as i64).await;
+        Ok(done.last_insert_id)
+    }
+
+    // 读取保存后的记录通知 on_change，数据库的默认值和类型转换以数据库中的为准
+    // 写入已经成功，读取失败只记录日志
+    async fn notify_saved(id: i64) {
+        if !T::NOTIFY_CHANGES {
+            return;
+        }
+        match Self::get_by_id(id).await {
+            Ok(Some(stored)) => T::on_change(Change::Saved(id, &stored)),
+            Ok(None) => T::on_change(Change::Deleted(&[id])),
+            Err(e) => eprintln!("读取保存后的 {} 失败: {:#}", T::TABLE, e),
+        }
+    }
+
+    /// 根据 ID 获取
+    pub async fn get_by_id(id: impl Into<i64>) -> Result<Option<T>> {
+        let pool = get_data_pool().await?;
+        let sql = format!("SELECT * FROM {} WHERE {} = ?", T::TABLE, T::ID);
+        let mut rows = fetch_rows(&pool, &sql, &[Cell::Int(Some(id.into()))])
+            .await
+            .with_context(|| format!("Failed to get {} by ID", T::TABLE))?;
+        Ok(rows.pop())
+    }
+
+    /// 按主键修改，返回是否有记录被修改
+    pub async fn update(entity: &T) -> Result<bool> {
+        let id = entity
+            .id()
+            .with_context(|| format!("{} 缺少主键 {}", T::TABLE, T::ID))?;
+        let pool = get_data_pool().await?;
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {} = ?",
+            T::TABLE,
+            T::COLUMNS
+                .iter()
+                .map(|c| format!("{} = ?", c))
+                .collect::<Vec<_>>()
+                .join(", "),
+            T::ID
+        );
+        let mut cells = entity.values();
+        cells.push(Cell::Int(Some(id)));
+        let done = execute(&pool, &sql, &cells)
+            .await
+            .with_context(|| format!("Failed to update {}", T::TABLE))?;
+        if done.rows_affected > 0 {
+            Self::notify_saved(id).await;
+        }
+        Ok(done.rows_affected > 0)
+    }
+
+    /// 根据 ID 删除，返回是否有记录被删除
+    pub async fn delete(id: impl Into<i64>) -> Result<bool> {
+        Ok(Self::deletes(&[id.into()]).await? > 0)
+    }
+
+    /// 批量删除，返回删除的行数
+    pub async fn deletes<I: Into<i64> + Copy>(ids: &[I]) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let pool = get_data_pool().await?;
+        let sql = format!(
+            "DELETE FROM {} WHERE {} IN ({})",
+            T::TABLE,
+            T::ID,
+            placeholders(ids.len())
+        );
+        let ids: Vec<i64> = ids.iter().map(|&id| id.into()).collect();
+        let cells: Vec<Cell> = ids.iter().map(|&id| Cell::Int(Some(id))).collect();
+        let done = execute(&pool, &sql, &cells)
+            .await
+            .with_context(|| format!("Failed to delete {}", T::TABLE))?;
+        if done.rows_affected > 0 && T::NOTIFY_CHANGES {
+            T::on_change(Change::Deleted(&ids));
+        }
+        Ok(done.rows_affected)
+    }
+
+    /// 全部记录
+    pub async fn all() -> Result<Vec<T>> {
+        let pool = get_data_pool().await?;
+        let sql = format!("SELECT * FROM {} ORDER BY {}", T::TABLE, T::ORDER_BY);
+        fetch_rows(&pool, &sql, &[])
+            .await
+            .with_context(|| format!("Failed to list {}", T::TABLE))
+    }
+
+    /// 分页列表
+    pub async fn list(page: i32, page_size: i32) -> Result<Page<T>> {
+        Self::query(&QuerySpec::new(), page, page_size).await
+    }
+
+    /// 分页搜索：keyword 模糊匹配 SEARCH_COLUMNS 中任一字段，sorts 为空时使用 ORDER_BY
+    pub async fn search(keyword: &str, sorts: &[Sort], page: i32, page_size: i32) -> Result<Page<T>> {
+        let spec = QuerySpec::new()
+            .keyword(T::SEARCH_COLUMNS, keyword)
+            .sorts(sorts.iter().cloned());
+        Self::query(&spec, page, page_size).await
+    }
+
+    /// 按本表允许的字段生成查询条件
+    pub fn build(spec: &QuerySpec) -> Result<BuiltFilter> {
+        let columns: Vec<&str> = std::iter::once(T::ID).chain(T::COLUMNS.iter().copied()).collect();
+        spec.build(&columns)
+    }
+
+    /// 符合条件的记录数
+    pub async fn count(spec: &QuerySpec) -> Result<i64> {
+        let built = Self::build(spec)?;
+        let pool = get_data_pool().await?;
+        let sql = format!("SELECT COUNT(*) FROM {}{}", T::TABLE, built.where_sql);
+        fetch_count(&pool, &sql, &built.cells)
+            .await
+            .with_context(|| format!("Failed to count {}", T::TABLE))
+    }
+
+    /// 按条件分页查询，未指定排序时使用 ORDER_BY
+    pub async fn query(spec: &QuerySpec, page: i32, page_size: i32) -> Result<Page<T>> {
+        let built = Self::build(spec)?;
+        let pool = get_data_pool().await?;
+        let (page, page_size) = (page.max(1), page_size.max(1));
+        let count_sql = format!("SELECT COUNT(*) FROM {}{}", T::TABLE, built.where_sql);
+        let total = fetch_count(&pool, &count_sql, &built.cells)
+            .await
+            .with_context(|| format!("Failed to count {}", T::TABLE))?;
+
+        let order_sql = if built.order_sql.is_empty() {
+            format!(" ORDER BY {}", T::ORDER_BY)
+        } else {
+            built.order_sql
+        };
+        let data_sql = format!(
+            "SELECT * FROM {}{}{} LIMIT ? OFFSET ?",
+            T::TABLE,
+            built.where_sql,
+            order_sql
+        );
+        let mut cells = built.cells;
+        cells.push(Cell::Int(Some(i64::from(page_size))));
+        // 用 i64 计算偏移量，页码很大时不会溢出
+        cells.push(Cell::Int(Some((i64::from(page) - 1) * i64::from(page_size))));
+        let data = fetch_rows(&pool, &data_sql, &cells)
+            .await
+            .with_context(|| format!("Failed to list {}", T::TABLE))?;
+
+        Ok(Page {
+            data,
+            page_count: ((total as f32) / (page_size as f32)).ceil() as i32,
+            current_page: page,
+            total: total as i32,
+            page_size,
+        })
+    }
+
+    /// 符合条件的记录按 column 分组计数，数量多的在前
+    pub async fn facet(spec: &QuerySpec, column: &str) -> Result<Vec<FacetCount>> {
+        let built = Self::build(&spec.clone().is_null(column, false))?;
+        let pool = get_data_pool().await?;
+        let text = match pool {
+            DbPool::MySql(_) => "CHAR",
+            _ => "TEXT",
+        };
+        let sql = format!(
+            "SELECT CAST({col} AS {text}) AS value, COUNT(*) AS count FROM {}{} GROUP BY {col} ORDER BY count DESC, value",
+            T::TABLE,
+            built.where_sql,
+            col = column,
+            text = text
+        );
+        fetch_rows(&pool, &sql, &built.cells)
+            .await
+            .with_context(|| format!("Failed to count {} by {}", T::TABLE, column))
+    }
+}
@@ -1,4 +1,5 @@
-use crate::dto::parse::parse_publish_date;
+use crate::dto::{parse::parse_publish_date, FacetCount, Sort};
+use crate::util::SearchHit;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -39,4 +40,39 @@ pub struct BookQuery {
     pub max_publish_date: Option<NaiveDateTime>,
     pub img: Option<String>,
     pub status: Option<String>,
+    // 排序字段，为空时按出版日期倒序
+    #[serde(default)]
+    pub sort: Vec<Sort>,
+}
+
+/// 全文检索结果，highlights 为标出匹配部分的书名、作者、分类
+pub type BookHit = SearchHit<Book>;
+
+/// 当前筛选条件下各分类、状态、评分的书籍数量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookFacets {
+    pub category: Vec<FacetCount>,
+    pub status: Vec<FacetCount>,
+    pub rating: Vec<FacetCount>,
+}
+
+/// 某月出版书籍的销量
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MonthSales {
+    // yyyy-mm
+    pub month: String,
+    pub books: i64,
+    pub sales: i64,
+    pub revenue: f64,
+}
+
+/// 书籍汇总统计，revenue 为 price × sales 之和
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BookStats {
+    pub books: i64,
+    pub total_sales: i64,
+    pub revenue: f64,
+    pub avg_rating: Option<f64>,
+    #[sqlx(skip)]
+    pub sales_by_month: Vec<MonthSales>,
 }
@@ -2,12 +2,8 @@ pub mod book;
 pub use book::*;
 pub mod sql;
 pub use sql::*;
-pub mod tablesql;
-pub use tablesql::*;
 pub mod page;
 pub use page::*;
-pub mod param;
-pub use param::*;
 pub mod statics;
 pub use statics::*;
 pub mod student;
@@ -18,3 +14,5 @@ pub mod teacher;
 pub use teacher::*;
 pub mod popula;
 pub use popula::*;
+pub mod query;
+pub use query::*;
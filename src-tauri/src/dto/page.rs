@@ -8,3 +8,52 @@ pub struct Page<T> {
     pub page_size: i32,
     pub page_count: i32,
 }
+
+impl<T> Page<T> {
+    /// 转换每条数据，分页信息不变
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            data: self.data.into_iter().map(f).collect(),
+            total: self.total,
+            current_page: self.current_page,
+            page_size: self.page_size,
+            page_count: self.page_count,
+        }
+    }
+}
+
+/// 排序字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sort {
+    pub column: String,
+    #[serde(default)]
+    pub desc: bool,
+}
+
+impl Sort {
+    /// 解析 "price,-sales" 形式的排序参数，字段前加 - 表示倒序
+    pub fn parse_list(value: &str) -> Vec<Sort> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| match s.strip_prefix('-') {
+                Some(column) => Sort {
+                    column: column.to_string(),
+                    desc: true,
+                },
+                None => Sort {
+                    column: s.to_string(),
+                    desc: false,
+                },
+            })
+            .collect()
+    }
+}
+
+/// 分组计数，value 统一转为文本，不含 NULL
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
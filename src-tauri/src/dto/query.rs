@@ -0,0 +1,74 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::FromRow;
+
+// 查询历史记录
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistory {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    // 连接配置 id
+    pub profile: String,
+    pub db_name: Option<String>,
+    pub sql_text: String,
+    pub duration_ms: i64,
+    // 查询返回的行数或修改影响的行数，出错时为空
+    pub row_count: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+// 查询历史的搜索条件
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    // 按 SQL 文本模糊匹配
+    pub keyword: Option<String>,
+    pub profile: Option<String>,
+    pub db_name: Option<String>,
+    // 只看执行出错的记录
+    pub errors_only: bool,
+    pub min_created_at: Option<NaiveDateTime>,
+    pub max_created_at: Option<NaiveDateTime>,
+}
+
+// 保存的查询中的参数，SQL 中以 :name 引用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParam {
+    pub name: String,
+    // 参数类型，取值同字段类型，如 int、decimal、varchar、date、datetime
+    #[serde(rename = "type", default = "default_param_type")]
+    pub kind: String,
+    // 未传值时使用的默认值
+    #[serde(default)]
+    pub default: Option<Value>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+fn default_param_type() -> String {
+    "varchar".to_string()
+}
+
+// 保存的查询
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub name: String,
+    pub description: Option<String>,
+    // 默认在哪个连接上执行，为空时使用默认连接
+    pub profile: Option<String>,
+    pub sql_text: String,
+    pub params: Json<Vec<QueryParam>>,
+    #[serde(default = "default_time")]
+    pub created_at: NaiveDateTime,
+    #[serde(default = "default_time")]
+    pub updated_at: NaiveDateTime,
+}
+
+fn default_time() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
@@ -1,4 +1,4 @@
-use commands::{ connect_db, AppState, BroadcastState, MulticastState, StudentMap, TcpClientState, TcpServerState, TeacherList };
+use commands::{ AppState, CredentialState, BroadcastState, MulticastState, StudentMap, TcpClientState, TcpServerState, TeacherList, TimerRegistry, WsServerState, HttpApiState };
 use dto::ThreadState;
 use tauri_plugin_autostart::MacosLauncher;
 
@@ -14,7 +14,7 @@ use std::time::Duration;
 use std::{ fs::{ self, OpenOptions }, io, path::PathBuf };
 use tauri::Manager;
 use tokio::sync::RwLock;
-use util::AppConfig;
+use util::{ AppConfig, CredentialStore };
 
 fn setup_logger(target_dir: &PathBuf) -> Result<(), fern::InitError> {
     let now = Local::now();
@@ -92,7 +92,10 @@ pub fn run() {
         .manage(Mutex::new(StudentMap::new()))
         .manage(Mutex::new(TeacherList::new()))
         .manage(ThreadState::default())
+        .manage(TimerRegistry::default())
         .manage(Arc::new(RwLock::new(TcpServerState::default())))
+        .manage(Arc::new(RwLock::new(WsServerState::default())))
+        .manage(HttpApiState::default())
         .setup(|app| {
             let document_dir = app.path().document_dir();
             if let Ok(dir) = &document_dir {
@@ -102,13 +105,36 @@ pub fn run() {
 
             let cp = AppConfig::config_path(app.handle());
             info!("配置文件地址: {:?}", cp);
-            let app_config = AppConfig::load(app.handle()).unwrap_or_else(|e| {
+            let mut app_config = AppConfig::load(app.handle()).unwrap_or_else(|e| {
                 eprintln!("Error loading config: {}, using default", e);
                 AppConfig::default()
             });
+            // 数据库密码保存在加密的凭据库中，旧配置中的明文密码在这里移入凭据库
+            let mut credential_store = cp.parent().and_then(|dir| {
+                CredentialStore::open(dir)
+                    .map_err(|e| eprintln!("凭据库打开失败，继续使用配置中的明文密码: {}", e))
+                    .ok()
+            });
+            if let Some(store) = credential_store.as_mut() {
+                match app_config.move_passwords(store) {
+                    Ok(true) => {
+                        if let Err(e) = app_config.save(app.handle()) {
+                            eprintln!("保存配置失败: {}", e);
+                        }
+                        info!("配置中的明文密码已移入凭据库");
+                    }
+                    Ok(false) => {}
+                    Err(e) => eprintln!("明文密码移入凭据库失败: {}", e),
+                }
+            }
+            app.manage(CredentialState::new(credential_store));
             app.manage(AppState {
                 config: Arc::new(Mutex::new(app_config)),
             });
+            app.manage(commands::JobManager::load(app.handle()));
+            app.manage(commands::SchedulerState::load(app.handle()));
+            // 加载配置中的定时任务
+            commands::start_scheduler(app.handle());
             // 获取配置的克隆（而不是持有锁）
             let config_clone;
             {
@@ -122,11 +148,19 @@ pub fn run() {
             if let Err(e) = commands::init_face_recognition(app.handle()) {
                 eprintln!("人脸识别初始化警告: {}", e);
             }
-            tauri::async_runtime::spawn(async move {
-                tokio::time::sleep(Duration::from_secs(2)).await;
-                let res = connect_db(&config_clone.ip, &config_clone.username, &config_clone.password).await;
-                info!("数据库启动 {:?}", res);
-            });
+
+            // 本地 HTTP 接口（默认关闭）
+            if config_clone.http_api_enabled {
+                let handle = app.handle().clone();
+                let (ip, port) = (config_clone.http_api_ip.clone(), config_clone.http_api_port);
+                tauri::async_runtime::spawn(async move {
+                    let res = commands::serve_http_api(&handle, &ip, port).await;
+                    info!("HTTP 接口启动 {:?}", res);
+                });
+            }
+
+            // 业务数据库由连接守护按配置连接，失败时自动重试，断开后自动重连
+            commands::start_db_supervisor(app.handle().clone());
 
             Ok(())
         })
@@ -1,3 +1,4 @@
+use crate::util::credential::{profile_credential, CredentialStore, Secret, APP_DB_CREDENTIAL};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -8,13 +9,33 @@ use tauri::Manager;
 #[serde(default)]
 pub struct AppConfig {
     pub username: String,
-    pub password: String,
+    // 密码保存在凭据库中，这里只用于读取旧版本明文配置
+    #[serde(skip_serializing_if = "Secret::is_empty")]
+    pub password: Secret,
     pub ip: String,
     pub isdebug: bool,
     pub volume: f64,
     pub notifications: Vec<String>,
     pub timeout: u32,
     pub alarms: Vec<AlarmList>,
+    // TCP/WebSocket 服务共用的访问令牌，为空表示不校验
+    pub server_token: String,
+    // TCP/WebSocket 服务共用的客户端 IP 白名单，为空表示不限制
+    pub server_allow_list: Vec<String>,
+    // WebSocket 服务转发给浏览器的应用事件
+    pub ws_events: Vec<String>,
+    // 启动时是否开启本地 HTTP 接口
+    pub http_api_enabled: bool,
+    pub http_api_ip: String,
+    pub http_api_port: u16,
+    // 定时任务，启动时重新加载
+    pub schedules: Vec<ScheduledTask>,
+    // 业务数据库后端："mysql" 或 "sqlite"
+    pub db_backend: String,
+    // SQLite 数据库文件路径，为空时使用应用数据目录下的 draft.db
+    pub sqlite_path: String,
+    // 数据库连接配置，可同时打开多个
+    pub db_profiles: Vec<DbProfile>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -26,11 +47,87 @@ pub struct AlarmList {
     pub level: u32,
 }
 
+// 定时任务定义
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduledTask {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    // cron 表达式，支持 5 段或 6 段（带秒）
+    pub cron: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub action: ScheduledAction,
+}
+
+// 定时任务可触发的内置动作
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    // 备份数据库，filename 支持 chrono 格式占位符，如 "backup-%Y%m%d%H%M.sql.gz"
+    // profile 为连接配置 id，为空时使用默认连接；gzip 为空时按文件名后缀判断
+    BackupDb {
+        dbname: String,
+        filename: String,
+        #[serde(default)]
+        gzip: Option<bool>,
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    // 删除指定的空目录
+    DeleteEmptyDirectories { paths: Vec<String> },
+    // 通过 TCP 服务向所有客户端广播消息
+    TcpBroadcast { message: String },
+}
+
+// 数据库连接配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DbProfile {
+    pub id: String,
+    pub name: String,
+    // "mysql" 或 "postgres"
+    pub dbtype: String,
+    pub host: String,
+    // 0 表示使用该类型数据库的默认端口
+    pub port: u16,
+    pub username: String,
+    // 密码保存在凭据库中，config.json 中不再保存
+    #[serde(skip_serializing_if = "Secret::is_empty")]
+    pub password: Secret,
+    // 默认数据库，为空时 MySQL 使用 mysql，PostgreSQL 使用 postgres
+    pub database: String,
+    pub pool_size: u32,
+    // 只读连接，数据库浏览器只允许执行查询语句
+    pub read_only: bool,
+}
+
+impl Default for DbProfile {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            dbtype: "mysql".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            username: String::new(),
+            password: Secret::default(),
+            database: String::new(),
+            pool_size: 5,
+            read_only: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             username: "sa".to_string(),
-            password: "Nanhui-380".to_string(),
+            password: Secret::default(),
             ip: "127.0.0.1".to_string(),
             isdebug: false,
             volume: 0.8,
@@ -50,6 +147,21 @@ impl Default for AppConfig {
                     level: 1,
                 },
             ],
+            server_token: String::new(),
+            server_allow_list: vec![],
+            ws_events: vec![
+                "server_data".to_string(),
+                "multicast-message".to_string(),
+                "value_updated".to_string(),
+                "recognition_result".to_string(),
+            ],
+            http_api_enabled: false,
+            http_api_ip: "127.0.0.1".to_string(),
+            http_api_port: 8787,
+            schedules: vec![],
+            db_backend: "mysql".to_string(),
+            sqlite_path: String::new(),
+            db_profiles: vec![],
         }
     }
 }
@@ -93,6 +205,24 @@ impl AppConfig {
         fs::write(&config_path, config_str).map_err(|e| format!("Failed to write config: {}", e))
     }
 
+    /// 把配置中的明文密码移到凭据库，返回是否有密码被移走（需要重新保存配置）
+    pub fn move_passwords(&mut self, store: &mut CredentialStore) -> Result<bool, String> {
+        let mut moved = false;
+        if !self.password.is_empty() {
+            store.set(APP_DB_CREDENTIAL, &self.password)?;
+            self.password = Secret::default();
+            moved = true;
+        }
+        for profile in &mut self.db_profiles {
+            if !profile.password.is_empty() {
+                store.set(&profile_credential(&profile.id), &profile.password)?;
+                profile.password = Secret::default();
+                moved = true;
+            }
+        }
+        Ok(moved)
+    }
+
     // 修复所有权问题
     pub fn update_field<T: serde::Serialize>(
         &mut self,
@@ -0,0 +1,442 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+// 设置后用该口令派生主密钥，适合没有系统钥匙串的无界面环境
+pub const PASSPHRASE_ENV: &str = "DRAFT_MASTER_PASSPHRASE";
+// 系统钥匙串中保存主密钥的条目
+const KEYRING_SERVICE: &str = "draft";
+const KEYRING_USER: &str = "credential-master-key";
+// 凭据文件和文件方式保存的主密钥
+const CREDENTIAL_FILE: &str = "credentials.json";
+const KEY_FILE: &str = "master.key";
+// 用于校验主密钥是否正确
+const CHECK_NAME: &str = "__check__";
+const CHECK_VALUE: &str = "draft-credentials";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// 密码等敏感字符串，调试输出时不显示内容
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Secret {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            f.write_str("\"\"")
+        } else {
+            f.write_str("\"******\"")
+        }
+    }
+}
+
+// 主密钥的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    // 由主口令派生
+    Passphrase,
+    // 随机生成并保存在系统钥匙串
+    Keyring,
+    // 随机生成并保存在配置目录下的 master.key
+    File,
+}
+
+// 凭据文件内容，每个凭据单独加密，格式为 base64(nonce + 密文)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CredentialFile {
+    version: u32,
+    key_source: KeySource,
+    // 口令派生密钥使用的盐
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    check: Option<String>,
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+}
+
+// 凭据库状态
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialStatus {
+    pub key_source: KeySource,
+    // 使用主口令但还没有解锁
+    pub locked: bool,
+    pub entries: usize,
+}
+
+/// 加密保存的凭据库，数据库密码等不再明文写入 config.json
+pub struct CredentialStore {
+    dir: PathBuf,
+    data: CredentialFile,
+    key: Option<Key<Aes256Gcm>>,
+}
+
+/// 连接配置密码在凭据库中的名称
+pub fn profile_credential(id: &str) -> String {
+    format!("profile:{}", id)
+}
+
+/// 业务数据库（config.json 中的 username/password）密码在凭据库中的名称
+pub const APP_DB_CREDENTIAL: &str = "app:db";
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>, String> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("主口令派生密钥失败: {}", e))?;
+    Ok(key)
+}
+
+fn decode_key(text: &str) -> Result<Key<Aes256Gcm>, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(text.trim())
+        .map_err(|e| format!("主密钥格式错误: {}", e))?;
+    if bytes.len() != 32 {
+        return Err("主密钥长度错误".to_string());
+    }
+    Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
+}
+
+fn encrypt(key: &Key<Aes256Gcm>, name: &str, value: &str) -> Result<String, String> {
+    let nonce = random_bytes::<NONCE_LEN>();
+    // 凭据名称作为附加数据，密文不能挪到其它名称下使用
+    let ciphertext = Aes256Gcm::new(key)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: value.as_bytes(),
+                aad: name.as_bytes(),
+            },
+        )
+        .map_err(|_| "加密凭据失败".to_string())?;
+    let mut out = nonce.to_vec();
+    out.extend(ciphertext);
+    Ok(general_purpose::STANDARD.encode(out))
+}
+
+fn decrypt(key: &Key<Aes256Gcm>, name: &str, text: &str) -> Result<String, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| format!("凭据 {} 格式错误: {}", name, e))?;
+    if bytes.len() <= NONCE_LEN {
+        return Err(format!("凭据 {} 格式错误", name));
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let plain = Aes256Gcm::new(key)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: name.as_bytes(),
+            },
+        )
+        .map_err(|_| format!("凭据 {} 解密失败，主密钥不正确", name))?;
+    String::from_utf8(plain).map_err(|e| e.to_string())
+}
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(|e| e.to_string())
+}
+
+// 读取系统钥匙串中的主密钥，create 为 true 且不存在时生成新的
+fn keyring_key(create: bool) -> Result<Key<Aes256Gcm>, String> {
+    let entry = keyring_entry()?;
+    match entry.get_password() {
+        Ok(text) => decode_key(&text),
+        Err(keyring::Error::NoEntry) if create => {
+            let key = Key::<Aes256Gcm>::from(random_bytes::<32>());
+            entry
+                .set_password(&general_purpose::STANDARD.encode(key))
+                .map_err(|e| e.to_string())?;
+            // 部分平台写入成功但读不出来（如无界面的 Linux），读回确认
+            let saved = entry.get_password().map_err(|e| e.to_string())?;
+            decode_key(&saved)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// 读取 master.key，create 为 true 且不存在时生成新的
+fn file_key(dir: &Path, create: bool) -> Result<Key<Aes256Gcm>, String> {
+    let path = dir.join(KEY_FILE);
+    if path.exists() {
+        let text = fs::read_to_string(&path).map_err(|e| format!("读取主密钥失败: {}", e))?;
+        return decode_key(&text);
+    }
+    if !create {
+        return Err(format!("主密钥文件 {} 不存在", path.display()));
+    }
+    let key = Key::<Aes256Gcm>::from(random_bytes::<32>());
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    fs::write(&path, general_purpose::STANDARD.encode(key)).map_err(|e| format!("保存主密钥失败: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o600));
+    }
+    Ok(key)
+}
+
+impl CredentialStore {
+    /// 打开配置目录下的凭据库，不存在时按可用的方式新建
+    /// 主口令来自环境变量 DRAFT_MASTER_PASSPHRASE；使用主口令但未设置时凭据库处于锁定状态
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(CREDENTIAL_FILE);
+        let passphrase = std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty());
+        if path.exists() {
+            let text = fs::read_to_string(&path).map_err(|e| format!("读取凭据文件失败: {}", e))?;
+            let data: CredentialFile =
+                serde_json::from_str(&text).map_err(|e| format!("解析凭据文件失败: {}", e))?;
+            let mut store = CredentialStore {
+                dir: dir.to_path_buf(),
+                data,
+                key: None,
+            };
+            // 取不到主密钥时凭据库保持锁定，不影响应用启动
+            let key = match store.data.key_source {
+                KeySource::Passphrase => match passphrase {
+                    Some(passphrase) => store.passphrase_key(&passphrase).map(Some),
+                    None => Ok(None),
+                },
+                KeySource::Keyring => keyring_key(false).map(Some),
+                KeySource::File => file_key(dir, false).map(Some),
+            };
+            match key.and_then(|key| key.map(|k| store.verify(&k).map(|_| k)).transpose()) {
+                Ok(key) => store.key = key,
+                Err(e) => eprintln!("凭据库解锁失败: {}", e),
+            }
+            return Ok(store);
+        }
+
+        // 新建：优先主口令，其次系统钥匙串，都不可用时使用密钥文件
+        let (key_source, salt, key) = match passphrase {
+            Some(passphrase) => {
+                let salt = random_bytes::<SALT_LEN>();
+                (KeySource::Passphrase, Some(salt.to_vec()), derive_key(&passphrase, &salt)?)
+            }
+            None => match keyring_key(true) {
+                Ok(key) => (KeySource::Keyring, None, key),
+                Err(e) => {
+                    println!("系统钥匙串不可用，使用密钥文件: {}", e);
+                    (KeySource::File, None, file_key(dir, true)?)
+                }
+            },
+        };
+        let mut store = CredentialStore {
+            dir: dir.to_path_buf(),
+            data: CredentialFile {
+                version: 1,
+                key_source,
+                salt: salt.map(|s| general_purpose::STANDARD.encode(s)),
+                check: None,
+                entries: BTreeMap::new(),
+            },
+            key: None,
+        };
+        store.data.check = Some(encrypt(&key, CHECK_NAME, CHECK_VALUE)?);
+        store.key = Some(key);
+        store.save()?;
+        Ok(store)
+    }
+
+    fn passphrase_key(&self, passphrase: &str) -> Result<Key<Aes256Gcm>, String> {
+        let salt = self
+            .data
+            .salt
+            .as_ref()
+            .ok_or_else(|| "凭据文件缺少盐".to_string())?;
+        let salt = general_purpose::STANDARD.decode(salt).map_err(|e| e.to_string())?;
+        derive_key(passphrase, &salt)
+    }
+
+    fn verify(&self, key: &Key<Aes256Gcm>) -> Result<(), String> {
+        match &self.data.check {
+            Some(check) if decrypt(key, CHECK_NAME, check).as_deref() == Ok(CHECK_VALUE) => Ok(()),
+            Some(_) => Err("主口令或主密钥不正确".to_string()),
+            None => Ok(()),
+        }
+    }
+
+    fn key(&self) -> Result<&Key<Aes256Gcm>, String> {
+        self.key
+            .as_ref()
+            .ok_or_else(|| "凭据库已锁定，请先输入主口令".to_string())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let text = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        let path = self.dir.join(CREDENTIAL_FILE);
+        fs::write(&path, text).map_err(|e| format!("保存凭据文件失败: {}", e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> CredentialStatus {
+        CredentialStatus {
+            key_source: self.data.key_source,
+            locked: self.key.is_none(),
+            entries: self.data.entries.len(),
+        }
+    }
+
+    /// 使用主口令解锁
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), String> {
+        if self.data.key_source != KeySource::Passphrase {
+            return Err("凭据库没有设置主口令".to_string());
+        }
+        let key = self.passphrase_key(passphrase)?;
+        self.verify(&key)?;
+        self.key = Some(key);
+        Ok(())
+    }
+
+    /// 读取凭据，不存在时返回 None
+    pub fn get(&self, name: &str) -> Result<Option<Secret>, String> {
+        match self.data.entries.get(name) {
+            Some(text) => Ok(Some(Secret(decrypt(self.key()?, name, text)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// 保存凭据，值为空时删除
+    pub fn set(&mut self, name: &str, value: &Secret) -> Result<(), String> {
+        if value.is_empty() {
+            return self.remove(name);
+        }
+        let text = encrypt(self.key()?, name, value.expose())?;
+        self.data.entries.insert(name.to_string(), text);
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<(), String> {
+        if self.data.entries.remove(name).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 更换主密钥并重新加密全部凭据：passphrase 不为空时改用主口令，否则改用系统钥匙串或密钥文件
+    pub fn change_key(&mut self, passphrase: Option<&str>) -> Result<KeySource, String> {
+        let old_key = *self.key()?;
+        let plain = self
+            .data
+            .entries
+            .iter()
+            .map(|(name, text)| Ok((name.clone(), decrypt(&old_key, name, text)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let (key_source, salt, key) = match passphrase.filter(|p| !p.is_empty()) {
+            Some(passphrase) => {
+                let salt = random_bytes::<SALT_LEN>();
+                (KeySource::Passphrase, Some(salt.to_vec()), derive_key(passphrase, &salt)?)
+            }
+            None => match keyring_key(true) {
+                Ok(key) => (KeySource::Keyring, None, key),
+                Err(e) => {
+                    println!("系统钥匙串不可用，使用密钥文件: {}", e);
+                    (KeySource::File, None, file_key(&self.dir, true)?)
+                }
+            },
+        };
+        let mut entries = BTreeMap::new();
+        for (name, value) in plain {
+            entries.insert(name.clone(), encrypt(&key, &name, &value)?);
+        }
+        self.data = CredentialFile {
+            version: 1,
+            key_source,
+            salt: salt.map(|s| general_purpose::STANDARD.encode(s)),
+            check: Some(encrypt(&key, CHECK_NAME, CHECK_VALUE)?),
+            entries,
+        };
+        self.key = Some(key);
+        self.save()?;
+        Ok(key_source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt_round_trip() {
+        let key = derive_key("口令", b"0123456789abcdef").unwrap();
+        let text = encrypt(&key, "app:db", "p@ss 密码").unwrap();
+        assert_ne!(text, encrypt(&key, "app:db", "p@ss 密码").unwrap());
+        assert_eq!(decrypt(&key, "app:db", &text).unwrap(), "p@ss 密码");
+    }
+
+    #[test]
+    fn decrypt_rejects_wrong_key_or_name() {
+        let key = derive_key("口令", b"0123456789abcdef").unwrap();
+        let text = encrypt(&key, "app:db", "secret").unwrap();
+        let other = derive_key("其它口令", b"0123456789abcdef").unwrap();
+        assert!(decrypt(&other, "app:db", &text).is_err());
+        // 密文绑定凭据名称，不能用在其它名称下
+        assert!(decrypt(&key, "profile:x", &text).is_err());
+        assert!(decrypt(&key, "app:db", "not base64!").is_err());
+    }
+
+    #[test]
+    fn secret_debug_hides_value() {
+        let secret = Secret::new("hunter2");
+        assert!(!format!("{:?}", secret).contains("hunter2"));
+        assert_eq!(secret.expose(), "hunter2");
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+// 解析 cron 表达式
+// 支持标准 5 段（分 时 日 月 周），以及 cron 库的 6/7 段（秒 分 时 日 月 周 [年]）
+// 标准 5 段的星期为 0-7（0 和 7 都是周日），cron 库为 1-7（1 是周日），数字星期需要换算
+pub fn parse_cron(expr: &str) -> Result<Schedule, String> {
+    let expr = expr.trim();
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let normalized = if fields.len() == 5 {
+        let weekday = weekday_field(fields[4]).map_err(|e| format!("无效的 cron 表达式 '{}': {}", expr, e))?;
+        format!("0 {} {}", fields[..4].join(" "), weekday)
+    } else {
+        expr.to_string()
+    };
+    Schedule::from_str(&normalized).map_err(|e| format!("无效的 cron 表达式 '{}': {}", expr, e))
+}
+
+// 把标准 cron 的星期段换算成 cron 库的写法
+// *、? 和 MON 等名称两边含义相同，原样保留；数字、范围和步长展开成 1-7 的列表
+fn weekday_field(field: &str) -> Result<String, String> {
+    let items = field
+        .split(',')
+        .map(weekday_item)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items.join(","))
+}
+
+fn weekday_item(item: &str) -> Result<String, String> {
+    let (base, step) = match item.split_once('/') {
+        Some((base, step)) => (base, Some(step)),
+        None => (item, None),
+    };
+    if base.is_empty() || !base.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+        return Ok(item.to_string());
+    }
+
+    let invalid = || format!("无效的星期 '{}'", item);
+    let day = |s: &str| s.parse::<u32>().ok().filter(|d| *d <= 7).ok_or_else(invalid);
+    let (start, end) = match base.split_once('-') {
+        Some((start, end)) => (day(start)?, day(end)?),
+        None if step.is_some() => (day(base)?, 6),
+        None => (day(base)?, day(base)?),
+    };
+    let step = match step {
+        Some(step) => step.parse::<usize>().ok().filter(|s| *s > 0).ok_or_else(invalid)?,
+        None => 1,
+    };
+    if start > end {
+        return Err(invalid());
+    }
+
+    let days: BTreeSet<u32> = (start..=end).step_by(step).map(|d| d % 7 + 1).collect();
+    Ok(days.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(","))
+}
+
+// 计算下一次触发时间
+pub fn next_fire(schedule: &Schedule) -> Option<DateTime<Local>> {
+    schedule.upcoming(Local).next()
+}
+
+// 距离下一次触发的等待时长
+pub fn duration_until_next(schedule: &Schedule) -> Option<std::time::Duration> {
+    let next = next_fire(schedule)?;
+    Some((next - Local::now()).to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike, Weekday};
+    use std::collections::HashSet;
+
+    fn weekdays(expr: &str) -> HashSet<Weekday> {
+        parse_cron(expr)
+            .unwrap()
+            .upcoming(Local)
+            .take(20)
+            .map(|t| t.weekday())
+            .collect()
+    }
+
+    #[test]
+    fn parses_five_and_six_field_expressions() {
+        let next = next_fire(&parse_cron("30 2 * * *").unwrap()).unwrap();
+        assert_eq!((next.hour(), next.minute(), next.second()), (2, 30, 0));
+        let next = next_fire(&parse_cron(" 15 */5 * * * * ").unwrap()).unwrap();
+        assert_eq!((next.minute() % 5, next.second()), (0, 15));
+        assert!(duration_until_next(&parse_cron("* * * * *").unwrap()).unwrap().as_secs() <= 60);
+    }
+
+    #[test]
+    fn five_field_weekdays_follow_standard_cron() {
+        use Weekday::*;
+        assert_eq!(weekdays("0 9 * * 1-5"), HashSet::from([Mon, Tue, Wed, Thu, Fri]));
+        assert_eq!(weekdays("0 9 * * 0"), HashSet::from([Sun]));
+        assert_eq!(weekdays("0 9 * * 7"), HashSet::from([Sun]));
+        assert_eq!(weekdays("0 9 * * 0,6"), HashSet::from([Sat, Sun]));
+        assert_eq!(weekdays("0 9 * * 5-7"), HashSet::from([Fri, Sat, Sun]));
+        assert_eq!(weekdays("0 9 * * 1-5/2"), HashSet::from([Mon, Wed, Fri]));
+        assert_eq!(weekdays("0 9 * * MON-FRI"), HashSet::from([Mon, Tue, Wed, Thu, Fri]));
+        // 6 段表达式按 cron 库的写法，1 是周日
+        assert_eq!(weekdays("0 0 9 * * 1"), HashSet::from([Sun]));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(parse_cron("").is_err());
+        assert!(parse_cron("61 * * * *").is_err());
+        assert!(parse_cron("every day").is_err());
+        assert!(parse_cron("0 9 * * 8").is_err());
+        assert!(parse_cron("0 9 * * 5-1").is_err());
+    }
+}
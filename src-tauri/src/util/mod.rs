@@ -1,3 +1,8 @@
 pub mod config;
+pub mod credential;
+pub mod cron_util;
+pub mod search;
 pub mod uuid_util;
 pub use config::*;
+pub use credential::*;
+pub use search::*;
@@ -0,0 +1,529 @@
+use pinyin::ToPinyin;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// 词项前缀，区分原文词、从某个汉字开始的全拼和首字母
+const WORD: &str = "w:";
+const PINYIN: &str = "p:";
+const INITIALS: &str = "i:";
+// 拼音词项最多包含的汉字数
+const MAX_PINYIN_CHARS: usize = 12;
+
+// 各种匹配方式的得分系数
+const EXACT_WEIGHT: f32 = 1.0;
+const PINYIN_WEIGHT: f32 = 0.9;
+const PREFIX_WEIGHT: f32 = 0.8;
+const INITIALS_WEIGHT: f32 = 0.7;
+const FUZZY_WEIGHT: f32 = 0.6;
+
+/// 可放入全文索引的文档
+pub trait Searchable: Clone {
+    // 参与检索的字段名和权重
+    const FIELDS: &'static [(&'static str, f32)];
+    // 与 FIELDS 顺序一致的字段文本
+    fn texts(&self) -> Vec<String>;
+}
+
+/// 检索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit<T> {
+    #[serde(flatten)]
+    pub doc: T,
+    pub score: f32,
+    // 字段名 -> 用 <mark> 标出匹配部分的文本，已做 HTML 转义
+    pub highlights: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    doc: i64,
+    field: usize,
+    // 词项在字段中的起始字符位置
+    pos: usize,
+}
+
+struct Doc<T> {
+    item: T,
+    texts: Vec<Vec<char>>,
+    terms: HashSet<String>,
+}
+
+// 查询中的一个词在某个文档中的匹配位置
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    field: usize,
+    start: usize,
+    len: usize,
+}
+
+/// 内存中的倒排索引，支持中文单字/双字、拼音全拼和首字母前缀、英文前缀和拼写容错
+pub struct SearchIndex<T> {
+    docs: HashMap<i64, Doc<T>>,
+    // 有序词典，便于前缀查找
+    terms: BTreeMap<String, Vec<Posting>>,
+}
+
+impl<T: Searchable> Default for SearchIndex<T> {
+    fn default() -> Self {
+        Self {
+            docs: HashMap::new(),
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}')
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_cjk(c)
+}
+
+fn pinyin_of(c: char) -> Option<&'static str> {
+    c.to_pinyin().map(|p| p.plain())
+}
+
+// 按字符切分出连续的汉字段和单词
+fn split_runs(chars: &[char]) -> Vec<(usize, usize, bool)> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        if is_cjk(chars[i]) {
+            while i < chars.len() && is_cjk(chars[i]) {
+                i += 1;
+            }
+            runs.push((start, i, true));
+        } else if is_word_char(chars[i]) {
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            runs.push((start, i, false));
+        } else {
+            i += 1;
+        }
+    }
+    runs
+}
+
+fn lowercase(chars: &[char]) -> String {
+    chars.iter().flat_map(|c| c.to_lowercase()).collect()
+}
+
+// 字段文本生成的词项及其位置
+fn analyze(chars: &[char]) -> Vec<(String, usize)> {
+    let mut terms = Vec::new();
+    for (start, end, cjk) in split_runs(chars) {
+        if !cjk {
+            terms.push((format!("{}{}", WORD, lowercase(&chars[start..end])), start));
+            continue;
+        }
+        for k in start..end {
+            terms.push((format!("{}{}", WORD, chars[k]), k));
+            if k + 1 < end {
+                terms.push((format!("{}{}{}", WORD, chars[k], chars[k + 1]), k));
+            }
+            // 从该字开始的全拼和首字母，查询时按前缀匹配
+            let pinyins: Vec<&str> = chars[k..end]
+                .iter()
+                .take(MAX_PINYIN_CHARS)
+                .map_while(|c| pinyin_of(*c))
+                .collect();
+            if !pinyins.is_empty() {
+                terms.push((format!("{}{}", PINYIN, pinyins.concat()), k));
+                let initials: String = pinyins.iter().filter_map(|p| p.chars().next()).collect();
+                terms.push((format!("{}{}", INITIALS, initials), k));
+            }
+        }
+    }
+    terms
+}
+
+// 查询拆分成的词，汉字按双字（单字时按单字）切分
+fn query_tokens(query: &str) -> Vec<(String, bool)> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    for (start, end, cjk) in split_runs(&chars) {
+        if !cjk {
+            tokens.push((lowercase(&chars[start..end]), false));
+        } else if end - start == 1 {
+            tokens.push((chars[start].to_string(), true));
+        } else {
+            for k in start..end - 1 {
+                tokens.push((chars[k..k + 2].iter().collect(), true));
+            }
+        }
+    }
+    tokens
+}
+
+// 允许的拼写错误个数
+fn max_typos(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+// 用 <mark> 标出 spans 覆盖的字符
+fn highlight(chars: &[char], spans: &[(usize, usize)]) -> String {
+    let mut marked = vec![false; chars.len()];
+    for &(start, len) in spans {
+        for m in marked.iter_mut().skip(start).take(len) {
+            *m = true;
+        }
+    }
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let mark = marked[i];
+        while i < chars.len() && marked[i] == mark {
+            i += 1;
+        }
+        let text: String = chars[start..i].iter().collect();
+        if mark {
+            out.push_str("<mark>");
+            escape_html(&text, &mut out);
+            out.push_str("</mark>");
+        } else {
+            escape_html(&text, &mut out);
+        }
+    }
+    out
+}
+
+impl<T: Searchable> SearchIndex<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// 新增或替换文档
+    pub fn upsert(&mut self, id: i64, item: T) {
+        self.remove(id);
+        let texts: Vec<Vec<char>> = item.texts().iter().map(|t| t.chars().collect()).collect();
+        let mut terms = HashSet::new();
+        for (field, chars) in texts.iter().enumerate().take(T::FIELDS.len()) {
+            for (term, pos) in analyze(chars) {
+                self.terms
+                    .entry(term.clone())
+                    .or_default()
+                    .push(Posting { doc: id, field, pos });
+                terms.insert(term);
+            }
+        }
+        self.docs.insert(id, Doc { item, texts, terms });
+    }
+
+    /// 删除文档
+    pub fn remove(&mut self, id: i64) {
+        let Some(doc) = self.docs.remove(&id) else {
+            return;
+        };
+        for term in doc.terms {
+            if let Some(postings) = self.terms.get_mut(&term) {
+                postings.retain(|p| p.doc != id);
+                if postings.is_empty() {
+                    self.terms.remove(&term);
+                }
+            }
+        }
+    }
+
+    // 词项的逆文档频率
+    fn idf(&self, postings: &[Posting]) -> f32 {
+        let docs: HashSet<i64> = postings.iter().map(|p| p.doc).collect();
+        (1.0 + self.docs.len() as f32 / docs.len().max(1) as f32).ln()
+    }
+
+    fn prefixed<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a String, &'a Vec<Posting>)> + 'a {
+        self.terms
+            .range(prefix.to_string()..)
+            .take_while(move |(term, _)| term.starts_with(prefix))
+    }
+
+    // 拼音前缀匹配时，从 start 开始覆盖 query 所需的汉字数
+    fn pinyin_len(&self, doc: i64, field: usize, start: usize, query_len: usize) -> usize {
+        let Some(chars) = self.docs.get(&doc).and_then(|d| d.texts.get(field)) else {
+            return 1;
+        };
+        let mut covered = 0;
+        let mut count = 0;
+        for c in chars.iter().skip(start) {
+            let Some(p) = pinyin_of(*c) else {
+                break;
+            };
+            covered += p.len();
+            count += 1;
+            if covered >= query_len {
+                break;
+            }
+        }
+        count.max(1)
+    }
+
+    // 单个查询词在各文档中的最高得分和匹配位置
+    fn match_token(&self, token: &str, cjk: bool) -> HashMap<i64, (f32, Vec<Span>)> {
+        let mut result: HashMap<i64, (f32, Vec<Span>)> = HashMap::new();
+        let mut add = |postings: &[Posting], weight: f32, len: &dyn Fn(&Posting) -> usize| {
+            let idf = self.idf(postings);
+            for p in postings {
+                let score = weight * idf * T::FIELDS[p.field].1;
+                let entry = result.entry(p.doc).or_insert((0.0, Vec::new()));
+                entry.0 = entry.0.max(score);
+                entry.1.push(Span {
+                    field: p.field,
+                    start: p.pos,
+                    len: len(p),
+                });
+            }
+        };
+
+        let token_len = token.chars().count();
+        let exact = format!("{}{}", WORD, token);
+        if let Some(postings) = self.terms.get(&exact) {
+            add(postings, EXACT_WEIGHT, &|_| token_len);
+        }
+        if cjk {
+            return result;
+        }
+
+        if token_len >= 2 {
+            for (term, postings) in self.prefixed(&exact) {
+                if *term != exact {
+                    let len = term.chars().count() - WORD.len();
+                    add(postings, PREFIX_WEIGHT, &|_| len);
+                }
+            }
+        }
+
+        let typos = max_typos(token_len);
+        if typos > 0 {
+            let query: Vec<char> = token.chars().collect();
+            for (term, postings) in self.prefixed(WORD) {
+                let word: Vec<char> = term[WORD.len()..].chars().collect();
+                if word.len().abs_diff(query.len()) > typos || word == query {
+                    continue;
+                }
+                if edit_distance(&word, &query) <= typos {
+                    let len = word.len();
+                    add(postings, FUZZY_WEIGHT, &|_| len);
+                }
+            }
+        }
+
+        // 字母查询同时按拼音全拼和首字母匹配汉字
+        if token_len >= 2 && token.chars().all(|c| c.is_ascii_lowercase()) {
+            let full = format!("{}{}", PINYIN, token);
+            for (_, postings) in self.prefixed(&full) {
+                add(postings, PINYIN_WEIGHT, &|p| {
+                    self.pinyin_len(p.doc, p.field, p.pos, token_len)
+                });
+            }
+            let initials = format!("{}{}", INITIALS, token);
+            for (_, postings) in self.prefixed(&initials) {
+                add(postings, INITIALS_WEIGHT, &|_| token_len);
+            }
+        }
+        result
+    }
+
+    /// 按相关度检索，返回命中总数和 offset 开始的 limit 条结果
+    /// 至少要匹配一半的查询词
+    pub fn search(&self, query: &str, offset: usize, limit: usize) -> (usize, Vec<SearchHit<T>>) {
+        let tokens = query_tokens(query);
+        if tokens.is_empty() {
+            return (0, Vec::new());
+        }
+
+        let mut scores: HashMap<i64, (f32, usize, Vec<Span>)> = HashMap::new();
+        for (token, cjk) in &tokens {
+            for (doc, (score, spans)) in self.match_token(token, *cjk) {
+                let entry = scores.entry(doc).or_insert((0.0, 0, Vec::new()));
+                entry.0 += score;
+                entry.1 += 1;
+                entry.2.extend(spans);
+            }
+        }
+
+        let mut ranked: Vec<(i64, f32, Vec<Span>)> = scores
+            .into_iter()
+            .filter(|(_, (_, matched, _))| matched * 2 >= tokens.len())
+            .map(|(doc, (score, matched, spans))| {
+                (doc, score * matched as f32 / tokens.len() as f32, spans)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let total = ranked.len();
+        let hits = ranked
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|(id, score, spans)| {
+                let doc = self.docs.get(&id)?;
+                let mut highlights = HashMap::new();
+                for (field, (name, _)) in T::FIELDS.iter().enumerate() {
+                    let field_spans: Vec<(usize, usize)> = spans
+                        .iter()
+                        .filter(|s| s.field == field)
+                        .map(|s| (s.start, s.len))
+                        .collect();
+                    if let (false, Some(chars)) = (field_spans.is_empty(), doc.texts.get(field)) {
+                        highlights.insert(name.to_string(), highlight(chars, &field_spans));
+                    }
+                }
+                Some(SearchHit {
+                    doc: doc.item.clone(),
+                    score,
+                    highlights,
+                })
+            })
+            .collect();
+        (total, hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct Note {
+        title: String,
+        author: String,
+    }
+
+    impl Searchable for Note {
+        const FIELDS: &'static [(&'static str, f32)] = &[("title", 2.0), ("author", 1.0)];
+
+        fn texts(&self) -> Vec<String> {
+            vec![self.title.clone(), self.author.clone()]
+        }
+    }
+
+    fn index(notes: &[(&str, &str)]) -> SearchIndex<Note> {
+        let mut index = SearchIndex::new();
+        for (id, (title, author)) in notes.iter().enumerate() {
+            index.upsert(
+                id as i64 + 1,
+                Note {
+                    title: title.to_string(),
+                    author: author.to_string(),
+                },
+            );
+        }
+        index
+    }
+
+    fn titles(index: &SearchIndex<Note>, query: &str) -> Vec<String> {
+        index.search(query, 0, 10).1.into_iter().map(|h| h.doc.title).collect()
+    }
+
+    #[test]
+    fn query_tokens_split_cjk_into_bigrams() {
+        assert_eq!(
+            query_tokens("红楼梦 Rust-Lang"),
+            vec![
+                ("红楼".to_string(), true),
+                ("楼梦".to_string(), true),
+                ("rust".to_string(), false),
+                ("lang".to_string(), false),
+            ]
+        );
+        assert_eq!(query_tokens("书"), vec![("书".to_string(), true)]);
+        assert!(query_tokens(" ,. ").is_empty());
+    }
+
+    #[test]
+    fn analyze_emits_words_pinyin_and_initials() {
+        let chars: Vec<char> = "三体".chars().collect();
+        let terms: Vec<String> = analyze(&chars).into_iter().map(|(t, _)| t).collect();
+        for expected in ["w:三", "w:三体", "w:体", "p:santi", "i:st", "p:ti", "i:t"] {
+            assert!(terms.contains(&expected.to_string()), "缺少词项 {}", expected);
+        }
+    }
+
+    #[test]
+    fn search_matches_pinyin_and_initials_prefix() {
+        let index = index(&[("红楼梦", "曹雪芹"), ("三体", "刘慈欣"), ("Rust Programming", "Steve")]);
+        assert_eq!(titles(&index, "hongloumeng"), vec!["红楼梦"]);
+        assert_eq!(titles(&index, "hong"), vec!["红楼梦"]);
+        assert_eq!(titles(&index, "hlm"), vec!["红楼梦"]);
+        assert_eq!(titles(&index, "lcx"), vec!["三体"]);
+        assert_eq!(titles(&index, "prog"), vec!["Rust Programming"]);
+    }
+
+    #[test]
+    fn search_tolerates_typos() {
+        let index = index(&[("Rust Programming", "Steve"), ("Go in Action", "William")]);
+        assert_eq!(titles(&index, "programing"), vec!["Rust Programming"]);
+        // 短词不做拼写容错
+        assert!(titles(&index, "ruts").is_empty());
+    }
+
+    #[test]
+    fn edit_distance_counts_edits() {
+        let d = |a: &str, b: &str| edit_distance(&a.chars().collect::<Vec<_>>(), &b.chars().collect::<Vec<_>>());
+        assert_eq!(d("kitten", "sitting"), 3);
+        assert_eq!(d("", "abc"), 3);
+        assert_eq!(d("abc", ""), 3);
+        assert_eq!(d("书名", "书名"), 0);
+        assert_eq!(d("flaw", "lawn"), 2);
+    }
+
+    #[test]
+    fn highlight_escapes_html() {
+        let chars: Vec<char> = "a<b>&'c\"".chars().collect();
+        assert_eq!(highlight(&chars, &[(1, 3)]), "a<mark>&lt;b&gt;</mark>&amp;&#39;c&quot;");
+        assert_eq!(highlight(&chars, &[]), "a&lt;b&gt;&amp;&#39;c&quot;");
+    }
+
+    #[test]
+    fn search_highlights_and_pages() {
+        let index = index(&[("三体", "刘慈欣"), ("三体II", "刘慈欣"), ("球状闪电", "刘慈欣")]);
+        let (total, hits) = index.search("刘慈欣", 0, 2);
+        assert_eq!(total, 3);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].highlights["author"], "<mark>刘慈欣</mark>");
+        let (total, hits) = index.search("刘慈欣", usize::MAX, 2);
+        assert_eq!((total, hits.len()), (3, 0));
+    }
+}
@@ -1 +1,6 @@
+use uuid::Uuid;
 
+// 生成不带短横线的 UUID，用作定时器、任务等的 id
+pub fn new_id() -> String {
+    Uuid::new_v4().to_string().replace("-", "")
+}